@@ -66,30 +66,146 @@ fn decrypt_xor(input : &Vec<u8>) -> (u8, f32) {
     (xor_character, xor_delta)
 }
 
+struct Detection<R> {
+    line : usize,
+    octets : Vec<u8>,
+    score : f32,
+    report : R,
+}
+
+trait Detector {
+    type Report;
+    fn detect(&self, octets : &[u8]) -> (f32, Self::Report);
+}
+
+fn scan<D : Detector, B : BufRead>(input : B, detector : &D) -> Vec<Detection<D::Report>> {
+    let mut detections : Vec<Detection<D::Report>> = Vec::new();
+    for (i, line) in input.lines().enumerate() {
+        let octets = hex2octets(&line.unwrap().into_bytes());
+        let (score, report) = detector.detect(&octets);
+        detections.push(Detection {
+            line : i,
+            octets : octets,
+            score : score,
+            report : report,
+        });
+    }
+    detections.sort_by(|a, b| a.score.partial_cmp(&b.score).unwrap());
+    detections
+}
+
+struct XorDetector;
+
+impl Detector for XorDetector {
+    type Report = u8;
+
+    fn detect(&self, octets : &[u8]) -> (f32, u8) {
+        let (c, delta) = decrypt_xor(&octets.to_vec());
+        (delta, c)
+    }
+}
+
 fn main() {
     let f = File::open("4.txt").unwrap();
     let b = BufReader::new(&f);
-    let mut best_line = String::from("");
-    let mut key = 0u8;
-    let mut best_delta = std::f32::INFINITY;
-    for line in b.lines() {
-        let octets = line.unwrap().into_bytes();
-        let (c, delta) = decrypt_xor(&hex2octets(&octets));
-        if delta < best_delta {
-            best_delta = delta;
-            key = c;
-            best_line = String::from_utf8(octets).unwrap();
-        }
-    }
-
-    println!("Line: {}", best_line);
-    println!("Key: {}", key);
-    println!("Delta: {}", best_delta);
+    let detections = scan(b, &XorDetector);
+    let best = detections.first().unwrap();
 
-    let input = hex2octets(&best_line.into_bytes());
-    println!("Decrypted: {}", String::from_utf8(fixed_xor(&input, key)).unwrap());
+    println!("Line: {}", best.line + 1);
+    println!("Key: {}", best.report);
+    println!("Delta: {}", best.score);
+    println!("Decrypted: {}", String::from_utf8(fixed_xor(&best.octets, best.report)).unwrap());
 }
 
 #[cfg(test)]
 mod tests {
-}
\ No newline at end of file
+
+    use std::collections::HashMap;
+    use std::fs::File;
+    use std::io::BufReader;
+    use std::io::Cursor;
+    use Detector;
+
+    struct EcbReport {
+        duplicate_blocks : usize,
+        repeated_blocks : Vec<Vec<usize>>,
+    }
+
+    struct EcbDetector {
+        block_size : usize,
+    }
+
+    impl EcbDetector {
+        fn new(block_size : usize) -> Result<EcbDetector, ()> {
+            if block_size == 0 {
+                return Err(());
+            }
+            Ok(EcbDetector {
+                block_size : block_size,
+            })
+        }
+    }
+
+    impl Detector for EcbDetector {
+        type Report = EcbReport;
+
+        fn detect(&self, octets : &[u8]) -> (f32, EcbReport) {
+            let mut positions : HashMap<&[u8], Vec<usize>> = HashMap::new();
+            let mut blocks = 0usize;
+            for (i, block) in octets.chunks(self.block_size).enumerate() {
+                positions.entry(block).or_insert(Vec::new()).push(i);
+                blocks += 1;
+            }
+            let mut repeated_blocks : Vec<Vec<usize>> = positions.into_iter().map(|(_, positions)| positions).filter(|positions| positions.len() > 1).collect();
+            repeated_blocks.sort();
+            let duplicate_blocks = repeated_blocks.iter().fold(0, |count, positions| count + positions.len() - 1);
+            let score = match blocks {
+                0 => 1f32,
+                _ => 1f32 - duplicate_blocks as f32 / blocks as f32,
+            };
+            (score, EcbReport {
+                duplicate_blocks : duplicate_blocks,
+                repeated_blocks : repeated_blocks,
+            })
+        }
+    }
+
+    #[test]
+    fn test_scan() {
+        let input = Cursor::new(String::from("1b37373331363f78151b7f2b783431333d78397828372d363c78373e783a393b3736\n0e3647e8592d35514a081243582536ed3de6734059001e3f535ce6271032\n"));
+        let detections = ::scan(input, &::XorDetector);
+        assert!(detections.len() == 2);
+        assert!(detections[0].line == 0);
+        assert!(::fixed_xor(&detections[0].octets, detections[0].report) == String::from("Cooking MC's like a pound of bacon").into_bytes());
+    }
+
+    #[test]
+    fn test_4_txt() {
+        let f = File::open("4.txt").unwrap();
+        let detections = ::scan(BufReader::new(&f), &::XorDetector);
+        let best = &detections[0];
+        assert!(::fixed_xor(&best.octets, best.report) == String::from("Now that the party is jumping\n").into_bytes());
+    }
+
+    #[test]
+    fn test_ecb_scan() {
+        assert!(EcbDetector::new(0).is_err());
+        let detector = EcbDetector::new(4).unwrap();
+        let (score, report) = detector.detect(b"abcdefghabcdijklabcdefgh");
+        assert!(report.duplicate_blocks == 3);
+        assert!(report.repeated_blocks == vec![vec![0, 2, 4], vec![1, 5]]);
+        assert!(score == 0.5f32);
+
+        let input = Cursor::new(String::from("0e3647e8592d35514a081243582536ed3de6734059001e3f535ce6271032\n00112233001122330011223344556677\n"));
+        let detections = ::scan(input, &EcbDetector::new(4).unwrap());
+        assert!(detections.len() == 2);
+        assert!(detections[0].line == 1);
+        assert!(detections[0].report.repeated_blocks == vec![vec![0, 1, 2]]);
+        assert!(detections[1].report.duplicate_blocks == 0);
+
+        let f = File::open("4.txt").unwrap();
+        let detections = ::scan(BufReader::new(&f), &EcbDetector::new(16).unwrap());
+        assert!(detections.len() == 327);
+        assert!(detections.iter().all(|detection| detection.report.duplicate_blocks == 0));
+    }
+}
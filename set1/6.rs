@@ -68,19 +68,17 @@ fn fixed_xor(input: &[u8], key: u8) -> Vec<u8> {
     xor
 }
 
-fn histogram(input : &[u8], key : u8) -> f32 {
+fn score(output : &[u8]) -> f32 {
     let letter_frequencies = vec![0.08167f32, 0.01492f32, 0.02782f32, 0.04253f32, 0.12702f32, 0.02228f32, 0.02015f32, 0.06094f32, 0.06966f32, 0.00153f32, 0.00772f32, 0.04025f32, 0.02406f32, 0.06749f32, 0.07507f32, 0.01929f32, 0.00095f32, 0.05987f32, 0.06327f32, 0.09056f32, 0.02758f32, 0.00978f32, 0.02361f32, 0.00150f32, 0.01974f32, 0.00074f32];
     assert!(letter_frequencies.len() == 26);
 
-    let output = fixed_xor(input, key);
-
     let mut letter_count : Vec<f32> = Vec::with_capacity(letter_frequencies.len());
     for _ in 0 .. letter_frequencies.len() {
         letter_count.push(0f32);
     }
     let mut nonletter_count = 0f32;
     let mut cumulative_delta = 0f32;
-    for octet in &output {
+    for octet in output {
         match *octet {
             0x41u8 ... 0x5Au8 | 0x61u8 ... 0x7Au8 => { letter_count[((octet | 0x20u8) - 0x61u8) as usize] += 1f32; },
             0x20 => (),
@@ -96,13 +94,14 @@ fn histogram(input : &[u8], key : u8) -> f32 {
     cumulative_delta / (ratio * ratio)
 }
 
-fn decrypt_fixed_xor(input : &[u8]) -> (u8, f32) {
+fn decrypt_column<F>(input : &[u8], decrypt : F) -> (u8, f32) where F : Fn(&[u8], u8) -> Vec<u8> {
 
     let mut best_key = 0u8;
     let mut best_score = std::f32::INFINITY;
 
-    for key in 0u8 .. std::u8::MAX {
-        let score = histogram(input, key);
+    for candidate in 0u16 .. 256u16 {
+        let key = candidate as u8;
+        let score = score(&decrypt(input, key));
         if score < best_score {
             best_key = key;
             best_score = score;
@@ -112,27 +111,82 @@ fn decrypt_fixed_xor(input : &[u8]) -> (u8, f32) {
     (best_key, best_score)
 }
 
-fn find_repeating_xor_key(input : &[u8], keysize : usize) -> Vec<(u8, f32)> {
-    let mut key : Vec<(u8, f32)> = Vec::with_capacity(keysize);
-    let mut data : Vec<Vec<u8>> = Vec::with_capacity(keysize);
-    for i in 0 .. data.capacity() {
-        data.push(Vec::with_capacity(input.len() / keysize + match i <= input.len() % keysize {
-            true => 1,
-            false => 0,
-        }));
+fn decrypt_fixed_xor(input : &[u8]) -> (u8, f32) {
+    decrypt_column(input, fixed_xor)
+}
+
+fn column_len(len : usize, columns : usize, column : usize) -> usize {
+    len / columns + match column < len % columns {
+        true => 1,
+        false => 0,
+    }
+}
+
+fn transpose(input : &[u8], columns : usize) -> Vec<Vec<u8>> {
+    assert!(columns > 0);
+    let mut data : Vec<Vec<u8>> = Vec::with_capacity(columns);
+    for i in 0 .. columns {
+        data.push(Vec::with_capacity(column_len(input.len(), columns, i)));
     }
-    for chunk in input.chunks(keysize) {
+    for chunk in input.chunks(columns) {
         for (datum, vec) in chunk.iter().zip(data.iter_mut()) {
             vec.push(*datum);
         }
     }
-    for octets in data {
-        let (c, delta) = decrypt_fixed_xor(&octets);
+    data
+}
+
+fn untranspose(data : &[Vec<u8>]) -> Vec<u8> {
+    let len = data.iter().fold(0, |len, column| len + column.len());
+    let mut output : Vec<u8> = Vec::with_capacity(len);
+    for row in 0 .. data.iter().map(|column| column.len()).max().unwrap_or(0) {
+        for column in data {
+            if row < column.len() {
+                output.push(column[row]);
+            }
+        }
+    }
+    assert!(output.len() == len);
+    output
+}
+
+struct Columns<'a> {
+    input : &'a [u8],
+    columns : usize,
+    column : usize,
+}
+
+fn columns<'a>(input : &'a [u8], columns : usize) -> Columns<'a> {
+    assert!(columns > 0);
+    Columns { input : input, columns : columns, column : 0 }
+}
+
+impl<'a> Iterator for Columns<'a> {
+    type Item = std::iter::StepBy<std::iter::Skip<std::slice::Iter<'a, u8>>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.column >= self.columns {
+            return None;
+        }
+        let column = self.input.iter().skip(self.column).step_by(self.columns);
+        self.column += 1;
+        Some(column)
+    }
+}
+
+fn find_repeating_key<F>(input : &[u8], keysize : usize, decrypt : F) -> Vec<(u8, f32)> where F : Fn(&[u8], usize) -> (u8, f32) {
+    let mut key : Vec<(u8, f32)> = Vec::with_capacity(keysize);
+    for (column, octets) in transpose(input, keysize).iter().enumerate() {
+        let (c, delta) = decrypt(octets, column);
         key.push((c, delta));
     }
     key
 }
 
+fn find_repeating_xor_key(input : &[u8], keysize : usize) -> Vec<(u8, f32)> {
+    find_repeating_key(input, keysize, |octets, _| decrypt_fixed_xor(octets))
+}
+
 fn rotating_xor(input: &[u8], key: &[u8]) -> Vec<u8> {
     let mut xor : Vec<u8> = Vec::with_capacity(input.len());
     for (octet, byte) in input.iter().zip(key.iter().cycle()) {
@@ -141,6 +195,79 @@ fn rotating_xor(input: &[u8], key: &[u8]) -> Vec<u8> {
     xor
 }
 
+fn incrementing_xor(input: &[u8], key: &[u8], step: u8) -> Vec<u8> {
+    let mut xor : Vec<u8> = Vec::with_capacity(input.len());
+    for (i, (octet, byte)) in input.iter().zip(key.iter().cycle()).enumerate() {
+        xor.push(octet ^ byte.wrapping_add((i as u8).wrapping_mul(step)))
+    }
+    xor
+}
+
+fn find_incrementing_xor_key(input : &[u8], keysize : usize, step : u8) -> Vec<(u8, f32)> {
+    find_repeating_key(input, keysize, |octets, column| decrypt_column(octets, |octets, key| {
+        let mut xor : Vec<u8> = Vec::with_capacity(octets.len());
+        for (j, octet) in octets.iter().enumerate() {
+            let i = (column + j * keysize) as u8;
+            xor.push(octet ^ key.wrapping_add(i.wrapping_mul(step)))
+        }
+        xor
+    }))
+}
+
+fn rotating_add(input: &[u8], key: &[u8]) -> Vec<u8> {
+    let mut sum : Vec<u8> = Vec::with_capacity(input.len());
+    for (octet, byte) in input.iter().zip(key.iter().cycle()) {
+        sum.push(octet.wrapping_add(*byte))
+    }
+    sum
+}
+
+fn rotating_subtract(input: &[u8], key: &[u8]) -> Vec<u8> {
+    let mut difference : Vec<u8> = Vec::with_capacity(input.len());
+    for (octet, byte) in input.iter().zip(key.iter().cycle()) {
+        difference.push(octet.wrapping_sub(*byte))
+    }
+    difference
+}
+
+fn find_rotating_add_key(input : &[u8], keysize : usize) -> Vec<(u8, f32)> {
+    find_repeating_key(input, keysize, |octets, _| decrypt_column(octets, |octets, key| {
+        let mut difference : Vec<u8> = Vec::with_capacity(octets.len());
+        for octet in octets {
+            difference.push(octet.wrapping_sub(key))
+        }
+        difference
+    }))
+}
+
+fn chained_xor(input: &[u8], key: &[u8]) -> Vec<u8> {
+    let mut xor : Vec<u8> = Vec::with_capacity(input.len());
+    let mut previous = 0u8;
+    for (octet, byte) in input.iter().zip(key.iter().cycle()) {
+        previous = octet ^ byte ^ previous;
+        xor.push(previous)
+    }
+    xor
+}
+
+fn unchain_xor(input: &[u8]) -> Vec<u8> {
+    let mut xor : Vec<u8> = Vec::with_capacity(input.len());
+    let mut previous = 0u8;
+    for octet in input {
+        xor.push(octet ^ previous);
+        previous = *octet;
+    }
+    xor
+}
+
+fn unchained_xor(input: &[u8], key: &[u8]) -> Vec<u8> {
+    rotating_xor(&unchain_xor(input), key)
+}
+
+fn find_chained_xor_key(input : &[u8], keysize : usize) -> Vec<(u8, f32)> {
+    find_repeating_xor_key(&unchain_xor(input), keysize)
+}
+
 fn main() {
     let mut f = File::open("6.txt").unwrap();
     let mut text = Vec::new();
@@ -222,4 +349,76 @@ mod tests {
         println!("Recoded: {}", recoded);
         assert!(original == recoded);
     }
+
+    #[test]
+    fn test_transpose() {
+        let input = String::from("abcdefghij").into_bytes();
+        let data = ::transpose(&input, 3);
+        assert!(data == vec![String::from("adgj").into_bytes(), String::from("beh").into_bytes(), String::from("cfi").into_bytes()]);
+        for (i, column) in data.iter().enumerate() {
+            assert!(column.len() == ::column_len(input.len(), 3, i));
+        }
+        assert!(::untranspose(&data) == input);
+        for keysize in 1 .. 13 {
+            assert!(::untranspose(&::transpose(&input, keysize)) == input);
+        }
+        assert!(::transpose(&input, 12)[11].is_empty());
+        assert!(::untranspose(&::transpose(&[], 4)).is_empty());
+        let ragged = vec![String::from("a").into_bytes(), String::from("bcd").into_bytes(), String::from("ef").into_bytes()];
+        assert!(::untranspose(&ragged) == String::from("abecfd").into_bytes());
+    }
+
+    #[test]
+    fn test_columns() {
+        let input = String::from("abcdefghij").into_bytes();
+        let data = ::transpose(&input, 4);
+        assert!(::columns(&input, 4).count() == 4);
+        for (column, octets) in ::columns(&input, 4).zip(data.iter()) {
+            assert!(column.cloned().collect::<Vec<u8>>() == *octets);
+        }
+    }
+
+    fn plaintext() -> Vec<u8> {
+        let mut f = File::open("6.txt").unwrap();
+        let mut text = Vec::new();
+        match f.read_to_end(&mut text) {
+            Ok(_) => (),
+            Err(_) => panic!(),
+        };
+        text.retain(|&c| c as char != '\n');
+        ::rotating_xor(&::base64decode(&text), &String::from("Terminator X: Bring the noise").into_bytes())
+    }
+
+    fn recovered_key(key : Vec<(u8, f32)>) -> Vec<u8> {
+        key.into_iter().map(|(c, _)| c).collect()
+    }
+
+    #[test]
+    fn test_incrementing_xor() {
+        let input = plaintext();
+        let key = String::from("malware").into_bytes();
+        let encrypted = ::incrementing_xor(&input, &key, 3u8);
+        assert!(encrypted != ::rotating_xor(&input, &key));
+        assert!(recovered_key(::find_incrementing_xor_key(&encrypted, key.len(), 3u8)) == key);
+        assert!(::incrementing_xor(&encrypted, &key, 3u8) == input);
+    }
+
+    #[test]
+    fn test_rotating_add() {
+        let input = plaintext();
+        let key = vec![0x13u8, 0x37u8, 0xC0u8, 0xDEu8, 0xFFu8];
+        let encrypted = ::rotating_add(&input, &key);
+        assert!(recovered_key(::find_rotating_add_key(&encrypted, key.len())) == key);
+        assert!(::rotating_subtract(&encrypted, &key) == input);
+    }
+
+    #[test]
+    fn test_chained_xor() {
+        let input = plaintext();
+        let key = String::from("ICE").into_bytes();
+        let encrypted = ::chained_xor(&input, &key);
+        assert!(::unchain_xor(&::chained_xor(&input, &[0u8])) == input);
+        assert!(recovered_key(::find_chained_xor_key(&encrypted, key.len())) == key);
+        assert!(::unchained_xor(&encrypted, &key) == input);
+    }
 }
\ No newline at end of file
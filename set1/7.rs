@@ -0,0 +1,286 @@
+use std::fs::File;
+use std::io::Read;
+
+fn base64decode(input : &[u8]) -> Vec<u8> {
+    let mut octets : Vec<u8> = Vec::new();
+
+    let mut input_octets = input.iter().map(|c| match *c {
+        0x41u8 ... 0x5Au8 => c - 0x41u8,
+        0x61u8 ... 0x7Au8 => c - 0x47u8,
+        0x30u8 ... 0x39u8 => c + 0x04u8,
+        0x2Bu8 => 0x3Eu8,
+        0x2Fu8 => 0x3Fu8,
+        0x3Du8 => 0xFFu8,
+        i => panic!("{}", i),
+    }).collect::<Vec<u8>>();
+    input_octets.retain(|c| *c != 0xFFu8);
+    for chunk in input_octets.chunks(4) {
+        let groups = match chunk.len() {
+            2 => vec![((chunk[0] << 2) & 0xFCu8) + ((chunk[1] >> 4) & 0x03u8)],
+            3 => vec![((chunk[0] << 2) & 0xFCu8) + ((chunk[1] >> 4) & 0x03u8), ((chunk[1] << 4) & 0xF0u8) + ((chunk[2] >> 2) & 0x0F)],
+            4 => vec![((chunk[0] << 2) & 0xFCu8) + ((chunk[1] >> 4) & 0x03u8), ((chunk[1] << 4) & 0xF0u8) + ((chunk[2] >> 2) & 0x0F), ((chunk[2] << 6) & 0xC0u8) + (chunk[3] & 0x3Fu8)],
+            _ => panic!(),
+        };
+        octets.reserve(groups.len());
+        for group in groups {
+            octets.push(group)
+        }
+    }
+
+    octets
+}
+
+fn gf_multiply(left : u8, right : u8) -> u8 {
+    let mut a = left;
+    let mut b = right;
+    let mut product = 0u8;
+    while b != 0 {
+        if b & 1 == 1 {
+            product ^= a;
+        }
+        a = (a << 1) ^ match a & 0x80u8 {
+            0 => 0x00u8,
+            _ => 0x1Bu8,
+        };
+        b >>= 1;
+    }
+    product
+}
+
+fn gf_inverse(octet : u8) -> u8 {
+    if octet == 0 {
+        return 0;
+    }
+    let mut inverse = 1u8;
+    while gf_multiply(octet, inverse) != 1 {
+        inverse += 1;
+    }
+    inverse
+}
+
+fn sboxes() -> ([u8; 256], [u8; 256]) {
+    let mut sbox = [0u8; 256];
+    let mut inverse_sbox = [0u8; 256];
+    for i in 0 .. 256 {
+        let b = gf_inverse(i as u8);
+        let s = b ^ b.rotate_left(1) ^ b.rotate_left(2) ^ b.rotate_left(3) ^ b.rotate_left(4) ^ 0x63u8;
+        sbox[i] = s;
+        inverse_sbox[s as usize] = i as u8;
+    }
+    (sbox, inverse_sbox)
+}
+
+struct Aes {
+    sbox : [u8; 256],
+    inverse_sbox : [u8; 256],
+    round_keys : Vec<[u8; 16]>,
+}
+
+impl Aes {
+    fn new(key : &[u8]) -> Result<Aes, ()> {
+        let key_words = match key.len() {
+            16 | 24 | 32 => key.len() / 4,
+            _ => return Err(()),
+        };
+        let rounds = key_words + 6;
+        let (sbox, inverse_sbox) = sboxes();
+
+        let mut words : Vec<[u8; 4]> = Vec::with_capacity(4 * (rounds + 1));
+        for chunk in key.chunks(4) {
+            words.push([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        }
+        let mut round_constant = 0x01u8;
+        for i in key_words .. 4 * (rounds + 1) {
+            let mut word = words[i - 1];
+            if i % key_words == 0 {
+                word = [sbox[word[1] as usize] ^ round_constant, sbox[word[2] as usize], sbox[word[3] as usize], sbox[word[0] as usize]];
+                round_constant = gf_multiply(round_constant, 0x02u8);
+            } else if key_words > 6 && i % key_words == 4 {
+                word = [sbox[word[0] as usize], sbox[word[1] as usize], sbox[word[2] as usize], sbox[word[3] as usize]];
+            }
+            let previous = words[i - key_words];
+            words.push([previous[0] ^ word[0], previous[1] ^ word[1], previous[2] ^ word[2], previous[3] ^ word[3]]);
+        }
+
+        let mut round_keys : Vec<[u8; 16]> = Vec::with_capacity(rounds + 1);
+        for chunk in words.chunks(4) {
+            let mut round_key = [0u8; 16];
+            for (i, word) in chunk.iter().enumerate() {
+                round_key[4 * i .. 4 * i + 4].copy_from_slice(word);
+            }
+            round_keys.push(round_key);
+        }
+
+        Ok(Aes {
+            sbox : sbox,
+            inverse_sbox : inverse_sbox,
+            round_keys : round_keys,
+        })
+    }
+
+    fn rounds(&self) -> usize {
+        self.round_keys.len() - 1
+    }
+
+    fn add_round_key(state : &mut [u8; 16], round_key : &[u8; 16]) {
+        for (octet, key) in state.iter_mut().zip(round_key.iter()) {
+            *octet ^= *key;
+        }
+    }
+
+    fn substitute_bytes(state : &mut [u8; 16], sbox : &[u8; 256]) {
+        for octet in state.iter_mut() {
+            *octet = sbox[*octet as usize];
+        }
+    }
+
+    fn shift_rows(state : &mut [u8; 16]) {
+        let original = *state;
+        for row in 1 .. 4 {
+            for column in 0 .. 4 {
+                state[row + 4 * column] = original[row + 4 * ((column + row) % 4)];
+            }
+        }
+    }
+
+    fn inverse_shift_rows(state : &mut [u8; 16]) {
+        let original = *state;
+        for row in 1 .. 4 {
+            for column in 0 .. 4 {
+                state[row + 4 * ((column + row) % 4)] = original[row + 4 * column];
+            }
+        }
+    }
+
+    fn mix_columns(state : &mut [u8; 16], coefficients : &[u8; 4]) {
+        for column in state.chunks_mut(4) {
+            let original = [column[0], column[1], column[2], column[3]];
+            for row in 0 .. 4 {
+                column[row] = 0;
+                for i in 0 .. 4 {
+                    column[row] ^= gf_multiply(coefficients[(4 + i - row) % 4], original[i]);
+                }
+            }
+        }
+    }
+
+    fn encrypt_block(&self, block : &[u8]) -> Vec<u8> {
+        assert!(block.len() == 16);
+        let mut state = [0u8; 16];
+        state.copy_from_slice(block);
+
+        Aes::add_round_key(&mut state, &self.round_keys[0]);
+        for round in 1 .. self.rounds() + 1 {
+            Aes::substitute_bytes(&mut state, &self.sbox);
+            Aes::shift_rows(&mut state);
+            if round != self.rounds() {
+                Aes::mix_columns(&mut state, &[0x02u8, 0x03u8, 0x01u8, 0x01u8]);
+            }
+            Aes::add_round_key(&mut state, &self.round_keys[round]);
+        }
+
+        state.to_vec()
+    }
+
+    fn decrypt_block(&self, block : &[u8]) -> Vec<u8> {
+        assert!(block.len() == 16);
+        let mut state = [0u8; 16];
+        state.copy_from_slice(block);
+
+        for round in (1 .. self.rounds() + 1).rev() {
+            Aes::add_round_key(&mut state, &self.round_keys[round]);
+            if round != self.rounds() {
+                Aes::mix_columns(&mut state, &[0x0Eu8, 0x0Bu8, 0x0Du8, 0x09u8]);
+            }
+            Aes::inverse_shift_rows(&mut state);
+            Aes::substitute_bytes(&mut state, &self.inverse_sbox);
+        }
+        Aes::add_round_key(&mut state, &self.round_keys[0]);
+
+        state.to_vec()
+    }
+}
+
+fn main() {
+    let mut f = File::open("7.txt").unwrap();
+    let mut text = Vec::new();
+    match f.read_to_end(&mut text) {
+        Ok(_) => (),
+        Err(_) => panic!(),
+    };
+    text.retain(|&c| c as char != '\n');
+    let data = base64decode(&text);
+
+    let aes = Aes::new(&String::from("YELLOW SUBMARINE").into_bytes()).unwrap();
+    let mut decrypted : Vec<u8> = Vec::with_capacity(data.len());
+    for block in data.chunks(16) {
+        decrypted.extend(aes.decrypt_block(block));
+    }
+    println!("{}", String::from_utf8(decrypted).unwrap());
+}
+
+#[cfg(test)]
+mod tests {
+
+    fn hex2nibble(hex : u8) -> Result<u8, ()> {
+        match hex {
+            0x30u8 ... 0x39u8 => Ok(hex - 0x30u8),
+            0x41u8 ... 0x46u8 | 0x61u8 ... 0x66u8 => Ok((hex & !0x20u8) - 0x37),
+            _ => Err(()),
+        }
+    }
+
+    fn hex2octets(hex: &[u8]) -> Vec<u8> {
+        let mut octets : Vec<u8> = Vec::with_capacity(&hex.len() / 2 + &hex.len() % 2);
+        if hex.len() % 2 == 1 {
+            octets.push(hex2nibble(hex[0]).unwrap())
+        }
+        for octet in hex[&hex.len() % 2 ..].chunks(2) {
+            octets.push((hex2nibble(octet[0]).unwrap() << 4) + hex2nibble(octet[1]).unwrap())
+        }
+        octets
+    }
+
+    #[test]
+    fn test_sboxes() {
+        let (sbox, inverse_sbox) = ::sboxes();
+        assert!(sbox[0x00] == 0x63u8);
+        assert!(sbox[0x53] == 0xEDu8);
+        assert!(sbox[0xFF] == 0x16u8);
+        assert!(inverse_sbox[0x63] == 0x00u8);
+        assert!(inverse_sbox[0xED] == 0x53u8);
+    }
+
+    #[test]
+    fn test_key_expansion() {
+        assert!(::Aes::new(&[0u8; 15]).is_err());
+        let aes = ::Aes::new(&hex2octets(b"2b7e151628aed2a6abf7158809cf4f3c")).unwrap();
+        assert!(aes.rounds() == 10);
+        assert!(aes.round_keys[1].to_vec() == hex2octets(b"a0fafe1788542cb123a339392a6c7605"));
+        assert!(aes.round_keys[10].to_vec() == hex2octets(b"d014f9a8c9ee2589e13f0cc8b6630ca6"));
+        let aes = ::Aes::new(&hex2octets(b"8e73b0f7da0e6452c810f32b809079e562f8ead2522c6b7b")).unwrap();
+        assert!(aes.rounds() == 12);
+        assert!(aes.round_keys[12].to_vec() == hex2octets(b"e98ba06f448c773c8ecc720401002202"));
+        let aes = ::Aes::new(&hex2octets(b"603deb1015ca71be2b73aef0857d77811f352c073b6108d72d9810a30914dff4")).unwrap();
+        assert!(aes.rounds() == 14);
+        assert!(aes.round_keys[14].to_vec() == hex2octets(b"fe4890d1e6188d0b046df344706c631e"));
+    }
+
+    #[test]
+    fn test_fips197_vectors() {
+        let plaintext = hex2octets(b"00112233445566778899aabbccddeeff");
+        let vectors = vec![
+            (hex2octets(b"000102030405060708090a0b0c0d0e0f"), hex2octets(b"69c4e0d86a7b0430d8cdb78070b4c55a")),
+            (hex2octets(b"000102030405060708090a0b0c0d0e0f1011121314151617"), hex2octets(b"dda97ca4864cdfe06eaf70a0ec0d7191")),
+            (hex2octets(b"000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f"), hex2octets(b"8ea2b7ca516745bfeafc49904b496089")),
+        ];
+        for (key, ciphertext) in vectors {
+            let aes = ::Aes::new(&key).unwrap();
+            assert!(aes.encrypt_block(&plaintext) == ciphertext);
+            assert!(aes.decrypt_block(&ciphertext) == plaintext);
+        }
+
+        let aes = ::Aes::new(&hex2octets(b"2b7e151628aed2a6abf7158809cf4f3c")).unwrap();
+        assert!(aes.encrypt_block(&hex2octets(b"3243f6a8885a308d313198a2e0370734")) == hex2octets(b"3925841d02dc09fbdc118597196a0b32"));
+    }
+}
@@ -0,0 +1,175 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufRead;
+use std::io::BufReader;
+
+fn hex2nibble(hex : u8) -> Result<u8, ()> {
+    match hex {
+        0x30u8 ... 0x39u8 => Ok(hex - 0x30u8),
+        0x41u8 ... 0x46u8 | 0x61u8 ... 0x66u8 => Ok((hex & !0x20u8) - 0x37),
+        _ => Err(()),
+    }
+}
+
+fn hex2octets(hex: &Vec<u8>) -> Vec<u8> {
+    let mut octets : Vec<u8> = Vec::with_capacity(&hex.len() / 2 + &hex.len() % 2);
+    if hex.len() % 2 == 1 {
+        octets.push(hex2nibble(hex[0]).unwrap())
+    }
+    for octet in hex[&hex.len() % 2 ..].chunks(2) {
+        octets.push((hex2nibble(octet[0]).unwrap() << 4) + hex2nibble(octet[1]).unwrap());
+    }
+    octets
+}
+
+fn nibble2hex(nibble : u8) -> Result<u8, ()> {
+    match nibble {
+        0u8 ... 9u8 => Ok(nibble + 0x30u8),
+        10u8 ... 15u8 => Ok(nibble + 0x57u8),
+        _ => Err(()),
+    }
+}
+
+fn octets2hex(octets: &[u8]) -> Vec<u8> {
+    let mut hex : Vec<u8> = Vec::with_capacity(octets.len() * 2);
+    for octet in octets {
+        hex.push(nibble2hex((octet & 0xF0u8) >> 4).unwrap());
+        hex.push(nibble2hex(octet & 0x0Fu8).unwrap());
+    }
+    hex
+}
+
+struct Detection<R> {
+    line : usize,
+    octets : Vec<u8>,
+    score : f32,
+    report : R,
+}
+
+trait Detector {
+    type Report;
+    fn detect(&self, octets : &[u8]) -> (f32, Self::Report);
+}
+
+fn scan<D : Detector, B : BufRead>(input : B, detector : &D) -> Vec<Detection<D::Report>> {
+    let mut detections : Vec<Detection<D::Report>> = Vec::new();
+    for (i, line) in input.lines().enumerate() {
+        let octets = hex2octets(&line.unwrap().into_bytes());
+        let (score, report) = detector.detect(&octets);
+        detections.push(Detection {
+            line : i,
+            octets : octets,
+            score : score,
+            report : report,
+        });
+    }
+    detections.sort_by(|a, b| a.score.partial_cmp(&b.score).unwrap());
+    detections
+}
+
+struct EcbReport {
+    duplicate_blocks : usize,
+    repeated_blocks : Vec<Vec<usize>>,
+}
+
+struct EcbDetector {
+    block_size : usize,
+}
+
+impl EcbDetector {
+    fn new(block_size : usize) -> Result<EcbDetector, ()> {
+        if block_size == 0 {
+            return Err(());
+        }
+        Ok(EcbDetector {
+            block_size : block_size,
+        })
+    }
+}
+
+impl Detector for EcbDetector {
+    type Report = EcbReport;
+
+    fn detect(&self, octets : &[u8]) -> (f32, EcbReport) {
+        let mut positions : HashMap<&[u8], Vec<usize>> = HashMap::new();
+        let mut blocks = 0usize;
+        for (i, block) in octets.chunks(self.block_size).enumerate() {
+            positions.entry(block).or_insert(Vec::new()).push(i);
+            blocks += 1;
+        }
+        let mut repeated_blocks : Vec<Vec<usize>> = positions.into_iter().map(|(_, positions)| positions).filter(|positions| positions.len() > 1).collect();
+        repeated_blocks.sort();
+        let duplicate_blocks = repeated_blocks.iter().fold(0, |count, positions| count + positions.len() - 1);
+        let score = match blocks {
+            0 => 1f32,
+            _ => 1f32 - duplicate_blocks as f32 / blocks as f32,
+        };
+        (score, EcbReport {
+            duplicate_blocks : duplicate_blocks,
+            repeated_blocks : repeated_blocks,
+        })
+    }
+}
+
+fn main() {
+    let f = File::open("8.txt").unwrap();
+    let b = BufReader::new(&f);
+    let detections = scan(b, &EcbDetector::new(16).unwrap());
+    let best = detections.first().unwrap();
+
+    println!("Line: {}", best.line + 1);
+    println!("Ciphertext: {}", String::from_utf8(octets2hex(&best.octets)).unwrap());
+    println!("Score: {}", best.score);
+    println!("Duplicate blocks: {}", best.report.duplicate_blocks);
+    println!("Repeated blocks: {:?}", best.report.repeated_blocks);
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::fs::File;
+    use std::io::BufReader;
+    use std::io::Cursor;
+    use Detector;
+
+    #[test]
+    fn test_detect() {
+        assert!(::EcbDetector::new(0).is_err());
+        let detector = ::EcbDetector::new(4).unwrap();
+        let (score, report) = detector.detect(b"abcdefghabcdijklabcdefgh");
+        assert!(report.duplicate_blocks == 3);
+        assert!(report.repeated_blocks == vec![vec![0, 2, 4], vec![1, 5]]);
+        assert!(score == 0.5f32);
+
+        let (score, report) = detector.detect(b"abcdefghijkl");
+        assert!(report.duplicate_blocks == 0);
+        assert!(report.repeated_blocks.is_empty());
+        assert!(score == 1f32);
+    }
+
+    #[test]
+    fn test_scan() {
+        let block = "d880619740a8a19b7840a8a31c810a3d";
+        let ecb = format!("{}{}08649af70dc06f4fd5d2d69c744cd283{}", block, block, block);
+        let input = Cursor::new(format!("{}\n{}\n{}\n",
+            "8a10247f90d0a05538888ad6205882196f5f6d05c21ec8dca0cb0be02c3f8b09e382963f443aa514daa501257b09a36b",
+            ecb,
+            "e2b7b6b4f9e1d2a3c4b5a6978877665544332211000f1e2d3c4b5a69788796a5b4c3d2e1f00f1e2d3c4b5a6978879600"));
+        let detections = ::scan(input, &::EcbDetector::new(16).unwrap());
+        assert!(detections.len() == 3);
+        assert!(detections[0].line == 1);
+        assert!(detections[0].report.duplicate_blocks == 2);
+        assert!(detections[0].report.repeated_blocks == vec![vec![0, 1, 3]]);
+        assert!(detections[1].report.duplicate_blocks == 0);
+    }
+
+    #[test]
+    fn test_8_txt() {
+        let f = File::open("8.txt").unwrap();
+        let detections = ::scan(BufReader::new(&f), &::EcbDetector::new(16).unwrap());
+        assert!(detections.len() == 204);
+        assert!(detections[0].line == 132);
+        assert!(detections[0].report.repeated_blocks == vec![vec![0, 2, 5, 7]]);
+        assert!(detections[1].report.duplicate_blocks == 0);
+    }
+}
@@ -0,0 +1,388 @@
+use std::collections::HashMap;
+
+fn score(output : &[u8]) -> f32 {
+    let letter_frequencies = vec![0.08167f32, 0.01492f32, 0.02782f32, 0.04253f32, 0.12702f32, 0.02228f32, 0.02015f32, 0.06094f32, 0.06966f32, 0.00153f32, 0.00772f32, 0.04025f32, 0.02406f32, 0.06749f32, 0.07507f32, 0.01929f32, 0.00095f32, 0.05987f32, 0.06327f32, 0.09056f32, 0.02758f32, 0.00978f32, 0.02361f32, 0.00150f32, 0.01974f32, 0.00074f32];
+    assert!(letter_frequencies.len() == 26);
+
+    let mut letter_count : Vec<f32> = Vec::with_capacity(letter_frequencies.len());
+    for _ in 0 .. letter_frequencies.len() {
+        letter_count.push(0f32);
+    }
+    let mut nonletter_count = 0f32;
+    let mut cumulative_delta = 0f32;
+    for octet in output {
+        match *octet {
+            0x41u8 ... 0x5Au8 | 0x61u8 ... 0x7Au8 => { letter_count[((octet | 0x20u8) - 0x61u8) as usize] += 1f32; },
+            0x20 => (),
+            0x80u8 ... 0xFFu8 => { cumulative_delta = std::f32::INFINITY; }
+            _ => { nonletter_count += 1f32; },
+        }
+    }
+
+    for i in 0 .. letter_frequencies.len() {
+        cumulative_delta += (letter_frequencies[i] - (letter_count[i] / output.len() as f32)).abs();
+    }
+    let ratio = (output.len() as f32 - nonletter_count) / output.len() as f32;
+    cumulative_delta / (ratio * ratio)
+}
+
+fn letter_index(octet : u8) -> Option<u8> {
+    match octet {
+        0x41u8 ... 0x5Au8 | 0x61u8 ... 0x7Au8 => Some((octet | 0x20u8) - 0x61u8),
+        _ => None,
+    }
+}
+
+fn with_letter_index(octet : u8, index : u8) -> u8 {
+    (octet & 0x20u8) + 0x41u8 + index
+}
+
+fn letters(input : &[u8]) -> Vec<u8> {
+    input.iter().filter_map(|octet| letter_index(*octet)).collect()
+}
+
+fn substitute<F>(input : &[u8], mut map : F) -> Vec<u8> where F : FnMut(u8) -> u8 {
+    let mut output : Vec<u8> = Vec::with_capacity(input.len());
+    for octet in input {
+        output.push(match letter_index(*octet) {
+            Some(index) => with_letter_index(*octet, map(index) % 26),
+            None => *octet,
+        })
+    }
+    output
+}
+
+fn caesar_encrypt(input : &[u8], shift : u8) -> Vec<u8> {
+    substitute(input, |index| index + shift % 26)
+}
+
+fn caesar_decrypt(input : &[u8], shift : u8) -> Vec<u8> {
+    caesar_encrypt(input, 26 - shift % 26)
+}
+
+fn rot13(input : &[u8]) -> Vec<u8> {
+    caesar_encrypt(input, 13)
+}
+
+fn break_caesar(input : &[u8]) -> (u8, f32) {
+    let mut best_shift = 0u8;
+    let mut best_score = std::f32::INFINITY;
+
+    for shift in 0u8 .. 26u8 {
+        let score = score(&caesar_decrypt(input, shift));
+        if score < best_score {
+            best_shift = shift;
+            best_score = score;
+        }
+    }
+
+    (best_shift, best_score)
+}
+
+fn vigenere_shifts(key : &[u8]) -> Result<Vec<u8>, ()> {
+    let shifts = letters(key);
+    if shifts.is_empty() {
+        return Err(());
+    }
+    Ok(shifts)
+}
+
+fn vigenere_encrypt(input : &[u8], key : &[u8]) -> Result<Vec<u8>, ()> {
+    let mut shifts = vigenere_shifts(key)?.into_iter().cycle();
+    Ok(substitute(input, |index| index + shifts.next().unwrap()))
+}
+
+fn vigenere_decrypt(input : &[u8], key : &[u8]) -> Result<Vec<u8>, ()> {
+    let mut shifts = vigenere_shifts(key)?.into_iter().cycle();
+    Ok(substitute(input, |index| index + 26 - shifts.next().unwrap()))
+}
+
+fn index_of_coincidence(indices : &[u8]) -> f32 {
+    if indices.len() < 2 {
+        return 0f32;
+    }
+    let mut counts = [0u32; 26];
+    for index in indices {
+        counts[*index as usize] += 1;
+    }
+    let mut coincidences = 0u32;
+    for count in counts.iter() {
+        coincidences += count * count.saturating_sub(1);
+    }
+    coincidences as f32 / (indices.len() * (indices.len() - 1)) as f32
+}
+
+fn find_vigenere_keysize(input : &[u8], max_keysize : usize) -> Vec<(usize, f32)> {
+    let indices = letters(input);
+    let mut keys : Vec<(usize, f32)> = Vec::with_capacity(max_keysize);
+    for keysize in 1 .. max_keysize + 1 {
+        let mut average = 0f32;
+        for column in 0 .. keysize {
+            let octets = indices.iter().skip(column).step_by(keysize).cloned().collect::<Vec<u8>>();
+            average += index_of_coincidence(&octets);
+        }
+        keys.push((keysize, average / keysize as f32));
+    }
+    keys.sort_by(|&(_, ioc_a), &(_, ioc_b)| ioc_b.partial_cmp(&ioc_a).unwrap());
+    keys
+}
+
+fn break_vigenere(input : &[u8], max_keysize : usize) -> Result<Vec<u8>, ()> {
+    let keysizes = find_vigenere_keysize(input, max_keysize);
+    let best = match keysizes.first() {
+        Some(&(_, ioc)) => ioc,
+        None => return Err(()),
+    };
+    let keysize = match keysizes.iter().filter(|&&(_, ioc)| ioc > best * 0.9f32).map(|&(keysize, _)| keysize).min() {
+        Some(keysize) => keysize,
+        None => return Err(()),
+    };
+
+    let indices = letters(input);
+    let mut key : Vec<u8> = Vec::with_capacity(keysize);
+    for column in 0 .. keysize {
+        let octets = indices.iter().skip(column).step_by(keysize).map(|index| index + 0x61u8).collect::<Vec<u8>>();
+        let (shift, _) = break_caesar(&octets);
+        key.push(shift + 0x61u8);
+    }
+    Ok(key)
+}
+
+fn modular_inverse(a : u8, modulus : u8) -> Option<u8> {
+    for x in 1 .. modulus {
+        if (a as u32 * x as u32) % modulus as u32 == 1 {
+            return Some(x);
+        }
+    }
+    None
+}
+
+fn affine_encrypt(input : &[u8], a : u8, b : u8) -> Result<Vec<u8>, ()> {
+    if modular_inverse(a % 26, 26).is_none() {
+        return Err(());
+    }
+    Ok(substitute(input, |index| ((a as u32 * index as u32 + b as u32) % 26) as u8))
+}
+
+fn affine_decrypt(input : &[u8], a : u8, b : u8) -> Result<Vec<u8>, ()> {
+    let inverse = match modular_inverse(a % 26, 26) {
+        Some(inverse) => inverse,
+        None => return Err(()),
+    };
+    Ok(substitute(input, |index| ((inverse as u32 * (index as u32 + 26 - b as u32 % 26)) % 26) as u8))
+}
+
+fn break_affine(input : &[u8]) -> (u8, u8, f32) {
+    let mut best_key = (1u8, 0u8);
+    let mut best_score = std::f32::INFINITY;
+
+    for a in 1u8 .. 26u8 {
+        for b in 0u8 .. 26u8 {
+            let score = match affine_decrypt(input, a, b) {
+                Ok(decrypted) => score(&decrypted),
+                Err(()) => break,
+            };
+            if score < best_score {
+                best_key = (a, b);
+                best_score = score;
+            }
+        }
+    }
+
+    (best_key.0, best_key.1, best_score)
+}
+
+fn substitution_encrypt(input : &[u8], key : &[u8]) -> Vec<u8> {
+    assert!(key.len() == 26);
+    substitute(input, |index| letter_index(key[index as usize]).unwrap())
+}
+
+fn substitution_decrypt(input : &[u8], key : &[u8]) -> Vec<u8> {
+    assert!(key.len() == 26);
+    let mut inverse = [0u8; 26];
+    for (index, octet) in key.iter().enumerate() {
+        inverse[letter_index(*octet).unwrap() as usize] = index as u8;
+    }
+    substitute(input, |index| inverse[index as usize])
+}
+
+struct Ngrams {
+    n : usize,
+    log_probabilities : HashMap<Vec<u8>, f32>,
+    floor : f32,
+}
+
+impl Ngrams {
+    fn train(corpus : &[u8], n : usize) -> Result<Ngrams, ()> {
+        if n == 0 {
+            return Err(());
+        }
+        let indices = letters(corpus);
+        let mut counts : HashMap<Vec<u8>, u32> = HashMap::new();
+        for window in indices.windows(n) {
+            *counts.entry(window.to_vec()).or_insert(0) += 1;
+        }
+        let total = indices.len().saturating_sub(n - 1).max(1) as f32;
+        let log_probabilities = counts.into_iter().map(|(ngram, count)| (ngram, (count as f32 / total).log10())).collect();
+        Ok(Ngrams {
+            n : n,
+            log_probabilities : log_probabilities,
+            floor : (0.01f32 / total).log10(),
+        })
+    }
+
+    fn score(&self, indices : &[u8]) -> f32 {
+        let mut score = 0f32;
+        for window in indices.windows(self.n) {
+            score += *self.log_probabilities.get(window).unwrap_or(&self.floor);
+        }
+        score
+    }
+}
+
+fn break_substitution(input : &[u8], ngrams : &[Ngrams]) -> Vec<u8> {
+    let english_order = b"etaoinshrdlcumwfgypbvkjxqz";
+    let indices = letters(input);
+    let mut counts : Vec<(u8, u32)> = (0u8 .. 26u8).map(|index| (index, 0u32)).collect();
+    for index in &indices {
+        counts[*index as usize].1 += 1;
+    }
+    counts.sort_by(|&(_, count_a), &(_, count_b)| count_b.cmp(&count_a));
+
+    let mut key = [0u8; 26];
+    for (&(cipher, _), plain) in counts.iter().zip(english_order.iter()) {
+        key[letter_index(*plain).unwrap() as usize] = cipher;
+    }
+
+    let decrypt = |key : &[u8; 26]| {
+        let mut inverse = [0u8; 26];
+        for (index, cipher) in key.iter().enumerate() {
+            inverse[*cipher as usize] = index as u8;
+        }
+        indices.iter().map(|index| inverse[*index as usize]).collect::<Vec<u8>>()
+    };
+    let score = |indices : &[u8]| ngrams.iter().fold(0f32, |score, ngrams| score + ngrams.score(indices));
+    let mut best_score = score(&decrypt(&key));
+    let mut improved = true;
+    while improved {
+        improved = false;
+        for i in 0 .. 26 {
+            for j in i + 1 .. 26 {
+                key.swap(i, j);
+                let candidate = score(&decrypt(&key));
+                if candidate > best_score {
+                    best_score = candidate;
+                    improved = true;
+                } else {
+                    key.swap(i, j);
+                }
+            }
+        }
+    }
+
+    key.iter().map(|index| index + 0x61u8).collect()
+}
+
+fn main() {
+    let plaintext = String::from("Cryptanalysis of the classical ciphers rests on a simple observation: the letters of a natural language do not occur with equal frequency, and a cipher that only rearranges or relabels them leaves those frequencies for an attacker to find.").into_bytes();
+
+    let (shift, _) = break_caesar(&caesar_encrypt(&plaintext, 7));
+    println!("Caesar shift: {}", shift);
+
+    let encrypted = vigenere_encrypt(&plaintext, &String::from("lemon").into_bytes()).unwrap();
+    let key = break_vigenere(&encrypted, 10).unwrap();
+    println!("Vigenere key: {}", String::from_utf8(key.clone()).unwrap());
+    println!("Vigenere: {}", String::from_utf8(vigenere_decrypt(&encrypted, &key).unwrap()).unwrap());
+
+    let (a, b, _) = break_affine(&affine_encrypt(&plaintext, 5, 8).unwrap());
+    println!("Affine key: ({}, {})", a, b);
+
+    let encrypted = substitution_encrypt(&plaintext, &String::from("phqgiumeaylnofdxjkrcvstzwb").into_bytes());
+    let ngrams = vec![Ngrams::train(&plaintext, 1).unwrap(), Ngrams::train(&plaintext, 2).unwrap(), Ngrams::train(&plaintext, 3).unwrap()];
+    let key = break_substitution(&encrypted, &ngrams);
+    println!("Substitution: {}", String::from_utf8(substitution_decrypt(&encrypted, &key)).unwrap());
+
+    println!("ROT13: {}", String::from_utf8(rot13(&plaintext)).unwrap());
+}
+
+#[cfg(test)]
+mod tests {
+
+    fn corpus() -> Vec<u8> {
+        String::from("It was late in the autumn when the letters began to arrive, and nobody in the village could say who had sent them. \
+            Each one was written in the same careful hand on the same thin grey paper, and each one was addressed to a different house. \
+            The schoolmaster was the first to notice that the words made no sense at all, though the spacing between them looked like ordinary writing. \
+            He spent several evenings counting the letters on every page, and he found that one of the symbols appeared far more often than any other. \
+            From there it was only a matter of patience: the most common symbol had to stand for the letter e, the next few for t and a and o, \
+            and the short words that appeared again and again were almost certainly the and and and of. \
+            By the end of the week he could read most of what had been written, and he was surprised to discover that the letters were not threats or secrets at all. \
+            They were recipes, copied out one at a time by an old woman who had moved away many years before and who wanted her neighbours to remember her cooking. \
+            The schoolmaster kept the method to himself for a while, but in the spring he taught it to his pupils, who were delighted to learn that a secret message \
+            is often only as strong as the patience of the person trying to read it. They practised on notes passed across the classroom until nobody bothered to encode anything any more.").into_bytes()
+    }
+
+    fn plaintext() -> Vec<u8> {
+        String::from("Four score and seven years ago our fathers brought forth on this continent, a new nation, conceived in Liberty, and dedicated to the proposition that all men are created equal. \
+            Now we are engaged in a great civil war, testing whether that nation, or any nation so conceived and so dedicated, can long endure. \
+            We are met on a great battle-field of that war. We have come to dedicate a portion of that field, as a final resting place for those who here gave their lives that that nation might live. \
+            It is altogether fitting and proper that we should do this. But, in a larger sense, we can not dedicate, we can not consecrate, we can not hallow this ground. \
+            The brave men, living and dead, who struggled here, have consecrated it, far above our poor power to add or detract. \
+            The world will little note, nor long remember what we say here, but it can never forget what they did here.").into_bytes()
+    }
+
+    #[test]
+    fn test_caesar() {
+        let input = plaintext();
+        assert!(::caesar_encrypt(&String::from("Hello, World").into_bytes(), 3) == String::from("Khoor, Zruog").into_bytes());
+        assert!(::rot13(&::rot13(&input)) == input);
+        for shift in 0u8 .. 26u8 {
+            let encrypted = ::caesar_encrypt(&input, shift);
+            assert!(::caesar_decrypt(&encrypted, shift) == input);
+            assert!(::break_caesar(&encrypted).0 == shift);
+        }
+    }
+
+    #[test]
+    fn test_vigenere() {
+        let input = plaintext();
+        assert!(::vigenere_encrypt(&String::from("ATTACKATDAWN").into_bytes(), &String::from("LEMON").into_bytes()) == Ok(String::from("LXFOPVEFRNHR").into_bytes()));
+        assert!(::vigenere_encrypt(&input, &String::from("1234 !").into_bytes()).is_err());
+        assert!(::vigenere_decrypt(&input, &[]).is_err());
+        let key = String::from("whistle").into_bytes();
+        let encrypted = ::vigenere_encrypt(&input, &key).unwrap();
+        assert!(::vigenere_decrypt(&encrypted, &key) == Ok(input.clone()));
+        assert!(::find_vigenere_keysize(&input, 1)[0].1 > 0.06f32);
+        assert!(::break_vigenere(&encrypted, 20) == Ok(key));
+        assert!(::break_vigenere(&encrypted, 0).is_err());
+        assert!(::break_vigenere(&String::from("1234, 5678 !").into_bytes(), 10).is_err());
+    }
+
+    #[test]
+    fn test_affine() {
+        let input = plaintext();
+        assert!(::modular_inverse(13, 26) == None);
+        assert!(::modular_inverse(5, 26) == Some(21));
+        assert!(::affine_encrypt(&String::from("AFFINE cipher").into_bytes(), 5, 8) == Ok(String::from("IHHWVC swfrcp").into_bytes()));
+        assert!(::affine_encrypt(&input, 13, 8).is_err());
+        assert!(::affine_decrypt(&input, 4, 8).is_err());
+        let encrypted = ::affine_encrypt(&input, 7, 11).unwrap();
+        assert!(::affine_decrypt(&encrypted, 7, 11) == Ok(input.clone()));
+        let (a, b, _) = ::break_affine(&encrypted);
+        assert!((a, b) == (7, 11));
+    }
+
+    #[test]
+    fn test_substitution() {
+        let input = plaintext();
+        let key = String::from("phqgiumeaylnofdxjkrcvstzwb").into_bytes();
+        let encrypted = ::substitution_encrypt(&input, &key);
+        assert!(::substitution_decrypt(&encrypted, &key) == input);
+
+        assert!(::Ngrams::train(&corpus(), 0).is_err());
+        let ngrams = vec![::Ngrams::train(&corpus(), 1).unwrap(), ::Ngrams::train(&corpus(), 2).unwrap(), ::Ngrams::train(&corpus(), 3).unwrap()];
+        let recovered = ::break_substitution(&encrypted, &ngrams);
+        let decrypted = ::substitution_decrypt(&encrypted, &recovered);
+        let correct = decrypted.iter().zip(input.iter()).filter(|&(a, b)| a == b).count();
+        assert!(correct as f32 / input.len() as f32 > 0.95f32);
+    }
+}
@@ -0,0 +1,629 @@
+use std::fs::File;
+use std::io::Read;
+
+fn base64decode(input : &[u8]) -> Vec<u8> {
+    let mut octets : Vec<u8> = Vec::new();
+
+    let mut input_octets = input.iter().map(|c| match *c {
+        0x41u8 ... 0x5Au8 => c - 0x41u8,
+        0x61u8 ... 0x7Au8 => c - 0x47u8,
+        0x30u8 ... 0x39u8 => c + 0x04u8,
+        0x2Bu8 => 0x3Eu8,
+        0x2Fu8 => 0x3Fu8,
+        0x3Du8 => 0xFFu8,
+        i => panic!("{}", i),
+    }).collect::<Vec<u8>>();
+    input_octets.retain(|c| *c != 0xFFu8);
+    for chunk in input_octets.chunks(4) {
+        let groups = match chunk.len() {
+            2 => vec![((chunk[0] << 2) & 0xFCu8) + ((chunk[1] >> 4) & 0x03u8)],
+            3 => vec![((chunk[0] << 2) & 0xFCu8) + ((chunk[1] >> 4) & 0x03u8), ((chunk[1] << 4) & 0xF0u8) + ((chunk[2] >> 2) & 0x0F)],
+            4 => vec![((chunk[0] << 2) & 0xFCu8) + ((chunk[1] >> 4) & 0x03u8), ((chunk[1] << 4) & 0xF0u8) + ((chunk[2] >> 2) & 0x0F), ((chunk[2] << 6) & 0xC0u8) + (chunk[3] & 0x3Fu8)],
+            _ => panic!(),
+        };
+        octets.reserve(groups.len());
+        for group in groups {
+            octets.push(group)
+        }
+    }
+
+    octets
+}
+
+fn fixed_xor(left: &[u8], right: &[u8]) -> Vec<u8> {
+    assert!(left.len() == right.len());
+    let mut xor : Vec<u8> = Vec::with_capacity(left.len());
+    for i in 0 .. left.len() {
+        xor.push(left[i] ^ right[i])
+    }
+    xor
+}
+
+fn gf_multiply(left : u8, right : u8) -> u8 {
+    let mut a = left;
+    let mut b = right;
+    let mut product = 0u8;
+    while b != 0 {
+        if b & 1 == 1 {
+            product ^= a;
+        }
+        a = (a << 1) ^ match a & 0x80u8 {
+            0 => 0x00u8,
+            _ => 0x1Bu8,
+        };
+        b >>= 1;
+    }
+    product
+}
+
+fn gf_inverse(octet : u8) -> u8 {
+    if octet == 0 {
+        return 0;
+    }
+    let mut inverse = 1u8;
+    while gf_multiply(octet, inverse) != 1 {
+        inverse += 1;
+    }
+    inverse
+}
+
+fn sboxes() -> ([u8; 256], [u8; 256]) {
+    let mut sbox = [0u8; 256];
+    let mut inverse_sbox = [0u8; 256];
+    for i in 0 .. 256 {
+        let b = gf_inverse(i as u8);
+        let s = b ^ b.rotate_left(1) ^ b.rotate_left(2) ^ b.rotate_left(3) ^ b.rotate_left(4) ^ 0x63u8;
+        sbox[i] = s;
+        inverse_sbox[s as usize] = i as u8;
+    }
+    (sbox, inverse_sbox)
+}
+
+struct Aes {
+    sbox : [u8; 256],
+    inverse_sbox : [u8; 256],
+    round_keys : Vec<[u8; 16]>,
+}
+
+impl Aes {
+    fn new(key : &[u8]) -> Result<Aes, ()> {
+        let key_words = match key.len() {
+            16 | 24 | 32 => key.len() / 4,
+            _ => return Err(()),
+        };
+        let rounds = key_words + 6;
+        let (sbox, inverse_sbox) = sboxes();
+
+        let mut words : Vec<[u8; 4]> = Vec::with_capacity(4 * (rounds + 1));
+        for chunk in key.chunks(4) {
+            words.push([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        }
+        let mut round_constant = 0x01u8;
+        for i in key_words .. 4 * (rounds + 1) {
+            let mut word = words[i - 1];
+            if i % key_words == 0 {
+                word = [sbox[word[1] as usize] ^ round_constant, sbox[word[2] as usize], sbox[word[3] as usize], sbox[word[0] as usize]];
+                round_constant = gf_multiply(round_constant, 0x02u8);
+            } else if key_words > 6 && i % key_words == 4 {
+                word = [sbox[word[0] as usize], sbox[word[1] as usize], sbox[word[2] as usize], sbox[word[3] as usize]];
+            }
+            let previous = words[i - key_words];
+            words.push([previous[0] ^ word[0], previous[1] ^ word[1], previous[2] ^ word[2], previous[3] ^ word[3]]);
+        }
+
+        let mut round_keys : Vec<[u8; 16]> = Vec::with_capacity(rounds + 1);
+        for chunk in words.chunks(4) {
+            let mut round_key = [0u8; 16];
+            for (i, word) in chunk.iter().enumerate() {
+                round_key[4 * i .. 4 * i + 4].copy_from_slice(word);
+            }
+            round_keys.push(round_key);
+        }
+
+        Ok(Aes {
+            sbox : sbox,
+            inverse_sbox : inverse_sbox,
+            round_keys : round_keys,
+        })
+    }
+
+    fn rounds(&self) -> usize {
+        self.round_keys.len() - 1
+    }
+
+    fn add_round_key(state : &mut [u8; 16], round_key : &[u8; 16]) {
+        for (octet, key) in state.iter_mut().zip(round_key.iter()) {
+            *octet ^= *key;
+        }
+    }
+
+    fn substitute_bytes(state : &mut [u8; 16], sbox : &[u8; 256]) {
+        for octet in state.iter_mut() {
+            *octet = sbox[*octet as usize];
+        }
+    }
+
+    fn shift_rows(state : &mut [u8; 16]) {
+        let original = *state;
+        for row in 1 .. 4 {
+            for column in 0 .. 4 {
+                state[row + 4 * column] = original[row + 4 * ((column + row) % 4)];
+            }
+        }
+    }
+
+    fn inverse_shift_rows(state : &mut [u8; 16]) {
+        let original = *state;
+        for row in 1 .. 4 {
+            for column in 0 .. 4 {
+                state[row + 4 * ((column + row) % 4)] = original[row + 4 * column];
+            }
+        }
+    }
+
+    fn mix_columns(state : &mut [u8; 16], coefficients : &[u8; 4]) {
+        for column in state.chunks_mut(4) {
+            let original = [column[0], column[1], column[2], column[3]];
+            for row in 0 .. 4 {
+                column[row] = 0;
+                for i in 0 .. 4 {
+                    column[row] ^= gf_multiply(coefficients[(4 + i - row) % 4], original[i]);
+                }
+            }
+        }
+    }
+
+    fn encrypt_block(&self, block : &[u8]) -> Vec<u8> {
+        assert!(block.len() == 16);
+        let mut state = [0u8; 16];
+        state.copy_from_slice(block);
+
+        Aes::add_round_key(&mut state, &self.round_keys[0]);
+        for round in 1 .. self.rounds() + 1 {
+            Aes::substitute_bytes(&mut state, &self.sbox);
+            Aes::shift_rows(&mut state);
+            if round != self.rounds() {
+                Aes::mix_columns(&mut state, &[0x02u8, 0x03u8, 0x01u8, 0x01u8]);
+            }
+            Aes::add_round_key(&mut state, &self.round_keys[round]);
+        }
+
+        state.to_vec()
+    }
+
+    fn decrypt_block(&self, block : &[u8]) -> Vec<u8> {
+        assert!(block.len() == 16);
+        let mut state = [0u8; 16];
+        state.copy_from_slice(block);
+
+        for round in (1 .. self.rounds() + 1).rev() {
+            Aes::add_round_key(&mut state, &self.round_keys[round]);
+            if round != self.rounds() {
+                Aes::mix_columns(&mut state, &[0x0Eu8, 0x0Bu8, 0x0Du8, 0x09u8]);
+            }
+            Aes::inverse_shift_rows(&mut state);
+            Aes::substitute_bytes(&mut state, &self.inverse_sbox);
+        }
+        Aes::add_round_key(&mut state, &self.round_keys[0]);
+
+        state.to_vec()
+    }
+}
+
+trait BlockCipher {
+    fn block_size(&self) -> usize;
+    fn encrypt_block(&self, block : &[u8]) -> Vec<u8>;
+    fn decrypt_block(&self, block : &[u8]) -> Vec<u8>;
+}
+
+impl BlockCipher for Aes {
+    fn block_size(&self) -> usize {
+        16
+    }
+
+    fn encrypt_block(&self, block : &[u8]) -> Vec<u8> {
+        Aes::encrypt_block(self, block)
+    }
+
+    fn decrypt_block(&self, block : &[u8]) -> Vec<u8> {
+        Aes::decrypt_block(self, block)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Direction {
+    Encrypt,
+    Decrypt,
+}
+
+trait Mode {
+    fn update(&mut self, input : &[u8]) -> Vec<u8>;
+    fn finish(self) -> Result<Vec<u8>, ()>;
+}
+
+fn process<M : Mode>(mut mode : M, input : &[u8]) -> Result<Vec<u8>, ()> {
+    let mut output = mode.update(input);
+    output.extend(mode.finish()?);
+    Ok(output)
+}
+
+fn take_blocks(buffer : &mut Vec<u8>, input : &[u8], block_size : usize) -> Vec<u8> {
+    buffer.extend_from_slice(input);
+    let full = buffer.len() - buffer.len() % block_size;
+    buffer.drain(.. full).collect()
+}
+
+fn apply_keystream<F>(keystream : &mut Vec<u8>, input : &[u8], mut next : F) -> Vec<u8> where F : FnMut() -> Vec<u8> {
+    let mut output : Vec<u8> = Vec::with_capacity(input.len());
+    let mut remaining = input;
+    while !remaining.is_empty() {
+        if keystream.is_empty() {
+            *keystream = next();
+        }
+        let n = std::cmp::min(remaining.len(), keystream.len());
+        output.extend(fixed_xor(&remaining[.. n], &keystream[.. n]));
+        keystream.drain(.. n);
+        remaining = &remaining[n ..];
+    }
+    output
+}
+
+struct Ecb<C : BlockCipher> {
+    cipher : C,
+    direction : Direction,
+    buffer : Vec<u8>,
+}
+
+impl<C : BlockCipher> Ecb<C> {
+    fn new(cipher : C, direction : Direction) -> Ecb<C> {
+        Ecb {
+            cipher : cipher,
+            direction : direction,
+            buffer : Vec::new(),
+        }
+    }
+}
+
+impl<C : BlockCipher> Mode for Ecb<C> {
+    fn update(&mut self, input : &[u8]) -> Vec<u8> {
+        let blocks = take_blocks(&mut self.buffer, input, self.cipher.block_size());
+        let mut output : Vec<u8> = Vec::with_capacity(blocks.len());
+        for block in blocks.chunks(self.cipher.block_size()) {
+            output.extend(match self.direction {
+                Direction::Encrypt => self.cipher.encrypt_block(block),
+                Direction::Decrypt => self.cipher.decrypt_block(block),
+            });
+        }
+        output
+    }
+
+    fn finish(self) -> Result<Vec<u8>, ()> {
+        match self.buffer.is_empty() {
+            true => Ok(Vec::new()),
+            false => Err(()),
+        }
+    }
+}
+
+struct Cbc<C : BlockCipher> {
+    cipher : C,
+    direction : Direction,
+    previous : Vec<u8>,
+    buffer : Vec<u8>,
+}
+
+impl<C : BlockCipher> Cbc<C> {
+    fn new(cipher : C, iv : &[u8], direction : Direction) -> Cbc<C> {
+        assert!(iv.len() == cipher.block_size());
+        Cbc {
+            cipher : cipher,
+            direction : direction,
+            previous : iv.to_vec(),
+            buffer : Vec::new(),
+        }
+    }
+}
+
+impl<C : BlockCipher> Mode for Cbc<C> {
+    fn update(&mut self, input : &[u8]) -> Vec<u8> {
+        let blocks = take_blocks(&mut self.buffer, input, self.cipher.block_size());
+        let mut output : Vec<u8> = Vec::with_capacity(blocks.len());
+        for block in blocks.chunks(self.cipher.block_size()) {
+            match self.direction {
+                Direction::Encrypt => {
+                    self.previous = self.cipher.encrypt_block(&fixed_xor(block, &self.previous));
+                    output.extend_from_slice(&self.previous);
+                },
+                Direction::Decrypt => {
+                    output.extend(fixed_xor(&self.cipher.decrypt_block(block), &self.previous));
+                    self.previous = block.to_vec();
+                },
+            }
+        }
+        output
+    }
+
+    fn finish(self) -> Result<Vec<u8>, ()> {
+        match self.buffer.is_empty() {
+            true => Ok(Vec::new()),
+            false => Err(()),
+        }
+    }
+}
+
+fn increment_counter(counter : &mut [u8]) {
+    for octet in counter.iter_mut().rev() {
+        *octet = octet.wrapping_add(1);
+        if *octet != 0 {
+            break;
+        }
+    }
+}
+
+struct Ctr<C : BlockCipher> {
+    cipher : C,
+    counter : Vec<u8>,
+    keystream : Vec<u8>,
+}
+
+impl<C : BlockCipher> Ctr<C> {
+    fn new(cipher : C, iv : &[u8]) -> Ctr<C> {
+        assert!(iv.len() == cipher.block_size());
+        Ctr {
+            cipher : cipher,
+            counter : iv.to_vec(),
+            keystream : Vec::new(),
+        }
+    }
+}
+
+impl<C : BlockCipher> Mode for Ctr<C> {
+    fn update(&mut self, input : &[u8]) -> Vec<u8> {
+        let cipher = &self.cipher;
+        let counter = &mut self.counter;
+        apply_keystream(&mut self.keystream, input, || {
+            let block = cipher.encrypt_block(counter);
+            increment_counter(counter);
+            block
+        })
+    }
+
+    fn finish(self) -> Result<Vec<u8>, ()> {
+        Ok(Vec::new())
+    }
+}
+
+struct Ofb<C : BlockCipher> {
+    cipher : C,
+    feedback : Vec<u8>,
+    keystream : Vec<u8>,
+}
+
+impl<C : BlockCipher> Ofb<C> {
+    fn new(cipher : C, iv : &[u8]) -> Ofb<C> {
+        assert!(iv.len() == cipher.block_size());
+        Ofb {
+            cipher : cipher,
+            feedback : iv.to_vec(),
+            keystream : Vec::new(),
+        }
+    }
+}
+
+impl<C : BlockCipher> Mode for Ofb<C> {
+    fn update(&mut self, input : &[u8]) -> Vec<u8> {
+        let cipher = &self.cipher;
+        let feedback = &mut self.feedback;
+        apply_keystream(&mut self.keystream, input, || {
+            *feedback = cipher.encrypt_block(feedback);
+            feedback.clone()
+        })
+    }
+
+    fn finish(self) -> Result<Vec<u8>, ()> {
+        Ok(Vec::new())
+    }
+}
+
+struct Cfb<C : BlockCipher> {
+    cipher : C,
+    direction : Direction,
+    register : Vec<u8>,
+    feedback : Vec<u8>,
+    keystream : Vec<u8>,
+}
+
+impl<C : BlockCipher> Cfb<C> {
+    fn new(cipher : C, iv : &[u8], direction : Direction) -> Cfb<C> {
+        assert!(iv.len() == cipher.block_size());
+        Cfb {
+            cipher : cipher,
+            direction : direction,
+            register : iv.to_vec(),
+            feedback : Vec::with_capacity(iv.len()),
+            keystream : Vec::new(),
+        }
+    }
+}
+
+impl<C : BlockCipher> Mode for Cfb<C> {
+    fn update(&mut self, input : &[u8]) -> Vec<u8> {
+        let mut output : Vec<u8> = Vec::with_capacity(input.len());
+        for octet in input {
+            if self.keystream.is_empty() {
+                if self.feedback.len() == self.cipher.block_size() {
+                    self.register = self.feedback.split_off(0);
+                }
+                self.keystream = self.cipher.encrypt_block(&self.register);
+            }
+            let xor = octet ^ self.keystream.remove(0);
+            self.feedback.push(match self.direction {
+                Direction::Encrypt => xor,
+                Direction::Decrypt => *octet,
+            });
+            output.push(xor);
+        }
+        output
+    }
+
+    fn finish(self) -> Result<Vec<u8>, ()> {
+        Ok(Vec::new())
+    }
+}
+
+fn main() {
+    let mut f = File::open("10.txt").unwrap();
+    let mut text = Vec::new();
+    match f.read_to_end(&mut text) {
+        Ok(_) => (),
+        Err(_) => panic!(),
+    };
+    text.retain(|&c| c as char != '\n');
+    let data = base64decode(&text);
+
+    let aes = Aes::new(&String::from("YELLOW SUBMARINE").into_bytes()).unwrap();
+    let decrypted = process(Cbc::new(aes, &[0u8; 16], Direction::Decrypt), &data).unwrap();
+    println!("{}", String::from_utf8(decrypted).unwrap());
+}
+
+#[cfg(test)]
+mod tests {
+
+    use Direction;
+    use Mode;
+
+    fn hex2nibble(hex : u8) -> Result<u8, ()> {
+        match hex {
+            0x30u8 ... 0x39u8 => Ok(hex - 0x30u8),
+            0x41u8 ... 0x46u8 | 0x61u8 ... 0x66u8 => Ok((hex & !0x20u8) - 0x37),
+            _ => Err(()),
+        }
+    }
+
+    fn hex2octets(hex: &[u8]) -> Vec<u8> {
+        let mut octets : Vec<u8> = Vec::with_capacity(&hex.len() / 2 + &hex.len() % 2);
+        if hex.len() % 2 == 1 {
+            octets.push(hex2nibble(hex[0]).unwrap())
+        }
+        for octet in hex[&hex.len() % 2 ..].chunks(2) {
+            octets.push((hex2nibble(octet[0]).unwrap() << 4) + hex2nibble(octet[1]).unwrap())
+        }
+        octets
+    }
+
+    struct ToyCipher {
+        key : Vec<u8>,
+    }
+
+    impl ::BlockCipher for ToyCipher {
+        fn block_size(&self) -> usize {
+            self.key.len()
+        }
+
+        fn encrypt_block(&self, block : &[u8]) -> Vec<u8> {
+            let mut output = ::fixed_xor(block, &self.key);
+            output.rotate_left(1);
+            output
+        }
+
+        fn decrypt_block(&self, block : &[u8]) -> Vec<u8> {
+            let mut output = block.to_vec();
+            output.rotate_right(1);
+            ::fixed_xor(&output, &self.key)
+        }
+    }
+
+    fn aes() -> ::Aes {
+        ::Aes::new(&hex2octets(b"2b7e151628aed2a6abf7158809cf4f3c")).unwrap()
+    }
+
+    fn plaintext() -> Vec<u8> {
+        hex2octets(b"6bc1bee22e409f96e93d7e117393172aae2d8a571e03ac9c9eb76fac45af8e5130c81c46a35ce411e5fbc1191a0a52eff69f2445df4f9b17ad2b417be66c3710")
+    }
+
+    #[test]
+    fn test_ecb() {
+        let ciphertext = hex2octets(b"3ad77bb40d7a3660a89ecaf32466ef97f5d3d58503b9699de785895a96fdbaaf43b1cd7f598ece23881b00e3ed0306887b0c785e27e8ad3f8223207104725dd4");
+        assert!(::process(::Ecb::new(aes(), Direction::Encrypt), &plaintext()) == Ok(ciphertext.clone()));
+        assert!(::process(::Ecb::new(aes(), Direction::Decrypt), &ciphertext) == Ok(plaintext()));
+        assert!(::process(::Ecb::new(aes(), Direction::Encrypt), &plaintext()[1 ..]) == Err(()));
+    }
+
+    #[test]
+    fn test_cbc() {
+        let iv = hex2octets(b"000102030405060708090a0b0c0d0e0f");
+        let ciphertext = hex2octets(b"7649abac8119b246cee98e9b12e9197d5086cb9b507219ee95db113a917678b273bed6b8e3c1743b7116e69e222295163ff1caa1681fac09120eca307586e1a7");
+        assert!(::process(::Cbc::new(aes(), &iv, Direction::Encrypt), &plaintext()) == Ok(ciphertext.clone()));
+        assert!(::process(::Cbc::new(aes(), &iv, Direction::Decrypt), &ciphertext) == Ok(plaintext()));
+    }
+
+    #[test]
+    fn test_cfb() {
+        let iv = hex2octets(b"000102030405060708090a0b0c0d0e0f");
+        let ciphertext = hex2octets(b"3b3fd92eb72dad20333449f8e83cfb4ac8a64537a0b3a93fcde3cdad9f1ce58b26751f67a3cbb140b1808cf187a4f4dfc04b05357c5d1c0eeac4c66f9ff7f2e6");
+        assert!(::process(::Cfb::new(aes(), &iv, Direction::Encrypt), &plaintext()) == Ok(ciphertext.clone()));
+        assert!(::process(::Cfb::new(aes(), &iv, Direction::Decrypt), &ciphertext) == Ok(plaintext()));
+    }
+
+    #[test]
+    fn test_ofb() {
+        let iv = hex2octets(b"000102030405060708090a0b0c0d0e0f");
+        let ciphertext = hex2octets(b"3b3fd92eb72dad20333449f8e83cfb4a7789508d16918f03f53c52dac54ed8259740051e9c5fecf64344f7a82260edcc304c6528f659c77866a510d9c1d6ae5e");
+        assert!(::process(::Ofb::new(aes(), &iv), &plaintext()) == Ok(ciphertext.clone()));
+        assert!(::process(::Ofb::new(aes(), &iv), &ciphertext) == Ok(plaintext()));
+    }
+
+    #[test]
+    fn test_ctr() {
+        let iv = hex2octets(b"f0f1f2f3f4f5f6f7f8f9fafbfcfdfeff");
+        let ciphertext = hex2octets(b"874d6191b620e3261bef6864990db6ce9806f66b7970fdff8617187bb9fffdff5ae4df3edbd5d35e5b4f09020db03eab1e031dda2fbe03d1792170a0f3009cee");
+        assert!(::process(::Ctr::new(aes(), &iv), &plaintext()) == Ok(ciphertext.clone()));
+        assert!(::process(::Ctr::new(aes(), &iv), &ciphertext) == Ok(plaintext()));
+
+        let mut counter = vec![0x00u8, 0xFFu8, 0xFFu8];
+        ::increment_counter(&mut counter);
+        assert!(counter == vec![0x01u8, 0x00u8, 0x00u8]);
+    }
+
+    #[test]
+    fn test_streaming() {
+        let iv = hex2octets(b"000102030405060708090a0b0c0d0e0f");
+        let input = plaintext();
+        let expected = ::process(::Cbc::new(aes(), &iv, Direction::Encrypt), &input).unwrap();
+        let mut mode = ::Cbc::new(aes(), &iv, Direction::Encrypt);
+        let mut output : Vec<u8> = Vec::new();
+        for chunk in input.chunks(7) {
+            output.extend(mode.update(chunk));
+        }
+        output.extend(mode.finish().unwrap());
+        assert!(output == expected);
+
+        let expected = ::process(::Cfb::new(aes(), &iv, Direction::Encrypt), &input[.. 61]).unwrap();
+        let mut mode = ::Cfb::new(aes(), &iv, Direction::Encrypt);
+        let mut output : Vec<u8> = Vec::new();
+        for chunk in input[.. 61].chunks(5) {
+            output.extend(mode.update(chunk));
+        }
+        assert!(output == expected);
+    }
+
+    #[test]
+    fn test_toy_cipher() {
+        let cipher = || ToyCipher { key : String::from("toy!").into_bytes() };
+        let iv = String::from("four").into_bytes();
+        let input = String::from("Any block cipher will do, even a silly one.").into_bytes();
+        let blocks = input[.. 40].to_vec();
+
+        let encrypted = ::process(::Ecb::new(cipher(), Direction::Encrypt), &blocks).unwrap();
+        assert!(::process(::Ecb::new(cipher(), Direction::Decrypt), &encrypted) == Ok(blocks.clone()));
+        let encrypted = ::process(::Cbc::new(cipher(), &iv, Direction::Encrypt), &blocks).unwrap();
+        assert!(::process(::Cbc::new(cipher(), &iv, Direction::Decrypt), &encrypted) == Ok(blocks.clone()));
+        let encrypted = ::process(::Cfb::new(cipher(), &iv, Direction::Encrypt), &input).unwrap();
+        assert!(::process(::Cfb::new(cipher(), &iv, Direction::Decrypt), &encrypted) == Ok(input.clone()));
+        let encrypted = ::process(::Ofb::new(cipher(), &iv), &input).unwrap();
+        assert!(::process(::Ofb::new(cipher(), &iv), &encrypted) == Ok(input.clone()));
+        let encrypted = ::process(::Ctr::new(cipher(), &iv), &input).unwrap();
+        assert!(encrypted.len() == input.len());
+        assert!(::process(::Ctr::new(cipher(), &iv), &encrypted) == Ok(input.clone()));
+    }
+}
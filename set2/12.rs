@@ -0,0 +1,441 @@
+use std::fs::File;
+use std::io::Read;
+
+fn base64decode(input : &[u8]) -> Vec<u8> {
+    let mut octets : Vec<u8> = Vec::new();
+
+    let mut input_octets = input.iter().map(|c| match *c {
+        0x41u8 ... 0x5Au8 => c - 0x41u8,
+        0x61u8 ... 0x7Au8 => c - 0x47u8,
+        0x30u8 ... 0x39u8 => c + 0x04u8,
+        0x2Bu8 => 0x3Eu8,
+        0x2Fu8 => 0x3Fu8,
+        0x3Du8 => 0xFFu8,
+        i => panic!("{}", i),
+    }).collect::<Vec<u8>>();
+    input_octets.retain(|c| *c != 0xFFu8);
+    for chunk in input_octets.chunks(4) {
+        let groups = match chunk.len() {
+            2 => vec![((chunk[0] << 2) & 0xFCu8) + ((chunk[1] >> 4) & 0x03u8)],
+            3 => vec![((chunk[0] << 2) & 0xFCu8) + ((chunk[1] >> 4) & 0x03u8), ((chunk[1] << 4) & 0xF0u8) + ((chunk[2] >> 2) & 0x0F)],
+            4 => vec![((chunk[0] << 2) & 0xFCu8) + ((chunk[1] >> 4) & 0x03u8), ((chunk[1] << 4) & 0xF0u8) + ((chunk[2] >> 2) & 0x0F), ((chunk[2] << 6) & 0xC0u8) + (chunk[3] & 0x3Fu8)],
+            _ => panic!(),
+        };
+        octets.reserve(groups.len());
+        for group in groups {
+            octets.push(group)
+        }
+    }
+
+    octets
+}
+
+fn random_octets(count : usize) -> Vec<u8> {
+    let mut f = File::open("/dev/urandom").unwrap();
+    let mut octets = vec![0u8; count];
+    match f.read_exact(&mut octets) {
+        Ok(_) => (),
+        Err(_) => panic!(),
+    };
+    octets
+}
+
+fn pkcs7_pad(input : &[u8], block_size : usize) -> Vec<u8> {
+    let count = block_size - input.len() % block_size;
+    let mut padded : Vec<u8> = Vec::with_capacity(input.len() + count);
+    padded.extend_from_slice(input);
+    padded.extend(vec![count as u8; count]);
+    padded
+}
+
+fn gf_multiply(left : u8, right : u8) -> u8 {
+    let mut a = left;
+    let mut b = right;
+    let mut product = 0u8;
+    while b != 0 {
+        if b & 1 == 1 {
+            product ^= a;
+        }
+        a = (a << 1) ^ match a & 0x80u8 {
+            0 => 0x00u8,
+            _ => 0x1Bu8,
+        };
+        b >>= 1;
+    }
+    product
+}
+
+fn gf_inverse(octet : u8) -> u8 {
+    if octet == 0 {
+        return 0;
+    }
+    let mut inverse = 1u8;
+    while gf_multiply(octet, inverse) != 1 {
+        inverse += 1;
+    }
+    inverse
+}
+
+fn sboxes() -> ([u8; 256], [u8; 256]) {
+    let mut sbox = [0u8; 256];
+    let mut inverse_sbox = [0u8; 256];
+    for i in 0 .. 256 {
+        let b = gf_inverse(i as u8);
+        let s = b ^ b.rotate_left(1) ^ b.rotate_left(2) ^ b.rotate_left(3) ^ b.rotate_left(4) ^ 0x63u8;
+        sbox[i] = s;
+        inverse_sbox[s as usize] = i as u8;
+    }
+    (sbox, inverse_sbox)
+}
+
+struct Aes {
+    sbox : [u8; 256],
+    inverse_sbox : [u8; 256],
+    round_keys : Vec<[u8; 16]>,
+}
+
+impl Aes {
+    fn new(key : &[u8]) -> Result<Aes, ()> {
+        let key_words = match key.len() {
+            16 | 24 | 32 => key.len() / 4,
+            _ => return Err(()),
+        };
+        let rounds = key_words + 6;
+        let (sbox, inverse_sbox) = sboxes();
+
+        let mut words : Vec<[u8; 4]> = Vec::with_capacity(4 * (rounds + 1));
+        for chunk in key.chunks(4) {
+            words.push([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        }
+        let mut round_constant = 0x01u8;
+        for i in key_words .. 4 * (rounds + 1) {
+            let mut word = words[i - 1];
+            if i % key_words == 0 {
+                word = [sbox[word[1] as usize] ^ round_constant, sbox[word[2] as usize], sbox[word[3] as usize], sbox[word[0] as usize]];
+                round_constant = gf_multiply(round_constant, 0x02u8);
+            } else if key_words > 6 && i % key_words == 4 {
+                word = [sbox[word[0] as usize], sbox[word[1] as usize], sbox[word[2] as usize], sbox[word[3] as usize]];
+            }
+            let previous = words[i - key_words];
+            words.push([previous[0] ^ word[0], previous[1] ^ word[1], previous[2] ^ word[2], previous[3] ^ word[3]]);
+        }
+
+        let mut round_keys : Vec<[u8; 16]> = Vec::with_capacity(rounds + 1);
+        for chunk in words.chunks(4) {
+            let mut round_key = [0u8; 16];
+            for (i, word) in chunk.iter().enumerate() {
+                round_key[4 * i .. 4 * i + 4].copy_from_slice(word);
+            }
+            round_keys.push(round_key);
+        }
+
+        Ok(Aes {
+            sbox : sbox,
+            inverse_sbox : inverse_sbox,
+            round_keys : round_keys,
+        })
+    }
+
+    fn rounds(&self) -> usize {
+        self.round_keys.len() - 1
+    }
+
+    fn add_round_key(state : &mut [u8; 16], round_key : &[u8; 16]) {
+        for (octet, key) in state.iter_mut().zip(round_key.iter()) {
+            *octet ^= *key;
+        }
+    }
+
+    fn substitute_bytes(state : &mut [u8; 16], sbox : &[u8; 256]) {
+        for octet in state.iter_mut() {
+            *octet = sbox[*octet as usize];
+        }
+    }
+
+    fn shift_rows(state : &mut [u8; 16]) {
+        let original = *state;
+        for row in 1 .. 4 {
+            for column in 0 .. 4 {
+                state[row + 4 * column] = original[row + 4 * ((column + row) % 4)];
+            }
+        }
+    }
+
+    fn inverse_shift_rows(state : &mut [u8; 16]) {
+        let original = *state;
+        for row in 1 .. 4 {
+            for column in 0 .. 4 {
+                state[row + 4 * ((column + row) % 4)] = original[row + 4 * column];
+            }
+        }
+    }
+
+    fn mix_columns(state : &mut [u8; 16], coefficients : &[u8; 4]) {
+        for column in state.chunks_mut(4) {
+            let original = [column[0], column[1], column[2], column[3]];
+            for row in 0 .. 4 {
+                column[row] = 0;
+                for i in 0 .. 4 {
+                    column[row] ^= gf_multiply(coefficients[(4 + i - row) % 4], original[i]);
+                }
+            }
+        }
+    }
+
+    fn encrypt_block(&self, block : &[u8]) -> Vec<u8> {
+        assert!(block.len() == 16);
+        let mut state = [0u8; 16];
+        state.copy_from_slice(block);
+
+        Aes::add_round_key(&mut state, &self.round_keys[0]);
+        for round in 1 .. self.rounds() + 1 {
+            Aes::substitute_bytes(&mut state, &self.sbox);
+            Aes::shift_rows(&mut state);
+            if round != self.rounds() {
+                Aes::mix_columns(&mut state, &[0x02u8, 0x03u8, 0x01u8, 0x01u8]);
+            }
+            Aes::add_round_key(&mut state, &self.round_keys[round]);
+        }
+
+        state.to_vec()
+    }
+
+    fn decrypt_block(&self, block : &[u8]) -> Vec<u8> {
+        assert!(block.len() == 16);
+        let mut state = [0u8; 16];
+        state.copy_from_slice(block);
+
+        for round in (1 .. self.rounds() + 1).rev() {
+            Aes::add_round_key(&mut state, &self.round_keys[round]);
+            if round != self.rounds() {
+                Aes::mix_columns(&mut state, &[0x0Eu8, 0x0Bu8, 0x0Du8, 0x09u8]);
+            }
+            Aes::inverse_shift_rows(&mut state);
+            Aes::substitute_bytes(&mut state, &self.inverse_sbox);
+        }
+        Aes::add_round_key(&mut state, &self.round_keys[0]);
+
+        state.to_vec()
+    }
+}
+
+fn ecb_encrypt(aes : &Aes, input : &[u8]) -> Vec<u8> {
+    let mut output : Vec<u8> = Vec::with_capacity(input.len());
+    for block in input.chunks(16) {
+        output.extend(aes.encrypt_block(block));
+    }
+    output
+}
+
+trait EncryptionOracle {
+    fn encrypt(&mut self, input : &[u8]) -> Vec<u8>;
+}
+
+struct EcbOracle {
+    aes : Aes,
+    prefix : Vec<u8>,
+    suffix : Vec<u8>,
+}
+
+impl EcbOracle {
+    fn new(suffix : &[u8]) -> EcbOracle {
+        EcbOracle {
+            aes : Aes::new(&random_octets(16)).unwrap(),
+            prefix : Vec::new(),
+            suffix : suffix.to_vec(),
+        }
+    }
+
+    fn with_random_prefix(suffix : &[u8]) -> EcbOracle {
+        let length = random_octets(1)[0] as usize % 64 + 1;
+        EcbOracle {
+            aes : Aes::new(&random_octets(16)).unwrap(),
+            prefix : random_octets(length),
+            suffix : suffix.to_vec(),
+        }
+    }
+}
+
+impl EncryptionOracle for EcbOracle {
+    fn encrypt(&mut self, input : &[u8]) -> Vec<u8> {
+        let mut plaintext : Vec<u8> = Vec::with_capacity(self.prefix.len() + input.len() + self.suffix.len());
+        plaintext.extend_from_slice(&self.prefix);
+        plaintext.extend_from_slice(input);
+        plaintext.extend_from_slice(&self.suffix);
+        ecb_encrypt(&self.aes, &pkcs7_pad(&plaintext, 16))
+    }
+}
+
+fn detect_block_size<O : EncryptionOracle>(oracle : &mut O) -> Result<(usize, usize), ()> {
+    let base_length = oracle.encrypt(&[]).len();
+    let mut input : Vec<u8> = Vec::new();
+    while input.len() < 256 {
+        input.push(0x41u8);
+        let length = oracle.encrypt(&input).len();
+        if length > base_length {
+            if base_length < input.len() {
+                return Err(());
+            }
+            return Ok((length - base_length, base_length - input.len()));
+        }
+    }
+    Err(())
+}
+
+fn has_repeated_block(ciphertext : &[u8], block_size : usize) -> Option<usize> {
+    let blocks : Vec<&[u8]> = ciphertext.chunks(block_size).collect();
+    for i in 1 .. blocks.len() {
+        if blocks[i - 1] == blocks[i] {
+            return Some(i - 1);
+        }
+    }
+    None
+}
+
+fn detect_ecb<O : EncryptionOracle>(oracle : &mut O, block_size : usize) -> bool {
+    has_repeated_block(&oracle.encrypt(&vec![0x41u8; 3 * block_size]), block_size).is_some()
+}
+
+fn first_different_block(left : &[u8], right : &[u8], block_size : usize) -> Option<usize> {
+    left.chunks(block_size).zip(right.chunks(block_size)).position(|(a, b)| a != b)
+}
+
+fn find_prefix_length<O : EncryptionOracle>(oracle : &mut O, block_size : usize) -> usize {
+    let block = first_different_block(&oracle.encrypt(&[0x00u8]), &oracle.encrypt(&[0xFFu8]), block_size).unwrap();
+    for filled in 1 .. block_size + 1 {
+        let mut input = vec![0x41u8; filled];
+        input.push(0x00u8);
+        let left = oracle.encrypt(&input);
+        input[filled] = 0xFFu8;
+        let right = oracle.encrypt(&input);
+        if first_different_block(&left, &right, block_size) != Some(block) {
+            return block * block_size + block_size - filled;
+        }
+    }
+    unreachable!()
+}
+
+fn byte_at_a_time<O : EncryptionOracle, F : FnMut(usize, usize)>(oracle : &mut O, mut progress : F) -> Result<Vec<u8>, ()> {
+    let (block_size, hidden_length) = detect_block_size(oracle)?;
+    if block_size < 2 || !detect_ecb(oracle, block_size) {
+        return Err(());
+    }
+    let prefix_length = find_prefix_length(oracle, block_size);
+    let alignment = (block_size - prefix_length % block_size) % block_size;
+    let skip = prefix_length + alignment;
+    let suffix_length = hidden_length - prefix_length;
+
+    let mut recovered : Vec<u8> = Vec::with_capacity(suffix_length);
+    let mut known = vec![0x41u8; block_size - 1];
+    for i in 0 .. suffix_length {
+        let filler = vec![0x41u8; alignment + block_size - 1 - i % block_size];
+        let offset = skip + (i / block_size) * block_size;
+        let target = oracle.encrypt(&filler)[offset .. offset + block_size].to_vec();
+
+        let mut input = vec![0x41u8; alignment];
+        for candidate in 0u16 .. 256u16 {
+            input.extend_from_slice(&known);
+            input.push(candidate as u8);
+        }
+        let dictionary = oracle.encrypt(&input);
+        match dictionary[skip ..].chunks(block_size).take(256).position(|block| block == &target[..]) {
+            Some(candidate) => {
+                recovered.push(candidate as u8);
+                known.remove(0);
+                known.push(candidate as u8);
+            },
+            None => return Err(()),
+        }
+        progress(recovered.len(), suffix_length);
+    }
+    Ok(recovered)
+}
+
+fn main() {
+    let suffix = base64decode(&String::from("Um9sbGluJyBpbiBteSA1LjAKV2l0aCBteSByYWctdG9wIGRvd24gc28gbXkgaGFpciBjYW4gYmxvdwpUaGUgZ2lybGllcyBvbiBzdGFuZGJ5IHdhdmluZyBqdXN0IHRvIHNheSBoaQpEaWQgeW91IHN0b3A/IE5vLCBJIGp1c3QgZHJvdmUgYnkK").into_bytes());
+
+    let mut oracle = EcbOracle::new(&suffix);
+    let (block_size, _) = detect_block_size(&mut oracle).unwrap();
+    println!("Block size: {}", block_size);
+    println!("ECB: {}", detect_ecb(&mut oracle, block_size));
+    let recovered = byte_at_a_time(&mut oracle, |done, total| if done == total { println!("Recovered {} of {} bytes", done, total) }).unwrap();
+    println!("{}", String::from_utf8(recovered).unwrap());
+
+    let mut oracle = EcbOracle::with_random_prefix(&suffix);
+    println!("Prefix length: {}", find_prefix_length(&mut oracle, block_size));
+    let recovered = byte_at_a_time(&mut oracle, |done, total| if done == total { println!("Recovered {} of {} bytes", done, total) }).unwrap();
+    println!("{}", String::from_utf8(recovered).unwrap());
+}
+
+#[cfg(test)]
+mod tests {
+
+    use EncryptionOracle;
+
+    fn suffix() -> Vec<u8> {
+        ::base64decode(&String::from("Um9sbGluJyBpbiBteSA1LjAKV2l0aCBteSByYWctdG9wIGRvd24gc28gbXkgaGFpciBjYW4gYmxvdwpUaGUgZ2lybGllcyBvbiBzdGFuZGJ5IHdhdmluZyBqdXN0IHRvIHNheSBoaQpEaWQgeW91IHN0b3A/IE5vLCBJIGp1c3QgZHJvdmUgYnkK").into_bytes())
+    }
+
+    struct PlainOracle {
+        queries : usize,
+    }
+
+    impl EncryptionOracle for PlainOracle {
+        fn encrypt(&mut self, input : &[u8]) -> Vec<u8> {
+            self.queries += 1;
+            let mut output = input.to_vec();
+            output.extend_from_slice(b"not a block cipher");
+            output
+        }
+    }
+
+    struct FixedOracle;
+
+    impl EncryptionOracle for FixedOracle {
+        fn encrypt(&mut self, _ : &[u8]) -> Vec<u8> {
+            vec![0x00u8; 32]
+        }
+    }
+
+    #[test]
+    fn test_detection() {
+        let mut oracle = ::EcbOracle::new(&suffix());
+        assert!(::detect_block_size(&mut oracle) == Ok((16, suffix().len())));
+        assert!(::detect_ecb(&mut oracle, 16));
+        assert!(::find_prefix_length(&mut oracle, 16) == 0);
+
+        for length in vec![1usize, 15, 16, 17, 33, 48] {
+            let mut oracle = ::EcbOracle::new(&suffix());
+            oracle.prefix = vec![0xFFu8; length];
+            assert!(::find_prefix_length(&mut oracle, 16) == length);
+            oracle.prefix = vec![0x00u8; length];
+            assert!(::find_prefix_length(&mut oracle, 16) == length);
+        }
+    }
+
+    #[test]
+    fn test_byte_at_a_time() {
+        let mut oracle = ::EcbOracle::new(&suffix());
+        let mut reported = 0usize;
+        assert!(::byte_at_a_time(&mut oracle, |done, _| reported = done) == Ok(suffix()));
+        assert!(reported == suffix().len());
+    }
+
+    #[test]
+    fn test_byte_at_a_time_with_prefix() {
+        for _ in 0 .. 2 {
+            let mut oracle = ::EcbOracle::with_random_prefix(&suffix());
+            assert!(::byte_at_a_time(&mut oracle, |_, _| ()) == Ok(suffix()));
+        }
+    }
+
+    #[test]
+    fn test_not_ecb() {
+        let mut oracle = PlainOracle { queries : 0 };
+        assert!(::byte_at_a_time(&mut oracle, |_, _| ()) == Err(()));
+        assert!(oracle.queries > 0);
+
+        assert!(::detect_block_size(&mut FixedOracle) == Err(()));
+        assert!(::byte_at_a_time(&mut FixedOracle, |_, _| ()) == Err(()));
+    }
+}
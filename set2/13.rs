@@ -0,0 +1,487 @@
+use std::fs::File;
+use std::io::Read;
+
+fn random_octets(count : usize) -> Vec<u8> {
+    let mut f = File::open("/dev/urandom").unwrap();
+    let mut octets = vec![0u8; count];
+    match f.read_exact(&mut octets) {
+        Ok(_) => (),
+        Err(_) => panic!(),
+    };
+    octets
+}
+
+fn pkcs7_pad(input : &[u8], block_size : usize) -> Vec<u8> {
+    let count = block_size - input.len() % block_size;
+    let mut padded : Vec<u8> = Vec::with_capacity(input.len() + count);
+    padded.extend_from_slice(input);
+    padded.extend(vec![count as u8; count]);
+    padded
+}
+
+fn pkcs7_unpad(input : &[u8], block_size : usize) -> Result<Vec<u8>, ()> {
+    if input.is_empty() || input.len() % block_size != 0 {
+        return Err(());
+    }
+    let count = input[input.len() - 1] as usize;
+    if count == 0 || count > block_size || input[input.len() - count ..].iter().any(|octet| *octet as usize != count) {
+        return Err(());
+    }
+    Ok(input[.. input.len() - count].to_vec())
+}
+
+fn gf_multiply(left : u8, right : u8) -> u8 {
+    let mut a = left;
+    let mut b = right;
+    let mut product = 0u8;
+    while b != 0 {
+        if b & 1 == 1 {
+            product ^= a;
+        }
+        a = (a << 1) ^ match a & 0x80u8 {
+            0 => 0x00u8,
+            _ => 0x1Bu8,
+        };
+        b >>= 1;
+    }
+    product
+}
+
+fn gf_inverse(octet : u8) -> u8 {
+    if octet == 0 {
+        return 0;
+    }
+    let mut inverse = 1u8;
+    while gf_multiply(octet, inverse) != 1 {
+        inverse += 1;
+    }
+    inverse
+}
+
+fn sboxes() -> ([u8; 256], [u8; 256]) {
+    let mut sbox = [0u8; 256];
+    let mut inverse_sbox = [0u8; 256];
+    for i in 0 .. 256 {
+        let b = gf_inverse(i as u8);
+        let s = b ^ b.rotate_left(1) ^ b.rotate_left(2) ^ b.rotate_left(3) ^ b.rotate_left(4) ^ 0x63u8;
+        sbox[i] = s;
+        inverse_sbox[s as usize] = i as u8;
+    }
+    (sbox, inverse_sbox)
+}
+
+struct Aes {
+    sbox : [u8; 256],
+    inverse_sbox : [u8; 256],
+    round_keys : Vec<[u8; 16]>,
+}
+
+impl Aes {
+    fn new(key : &[u8]) -> Result<Aes, ()> {
+        let key_words = match key.len() {
+            16 | 24 | 32 => key.len() / 4,
+            _ => return Err(()),
+        };
+        let rounds = key_words + 6;
+        let (sbox, inverse_sbox) = sboxes();
+
+        let mut words : Vec<[u8; 4]> = Vec::with_capacity(4 * (rounds + 1));
+        for chunk in key.chunks(4) {
+            words.push([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        }
+        let mut round_constant = 0x01u8;
+        for i in key_words .. 4 * (rounds + 1) {
+            let mut word = words[i - 1];
+            if i % key_words == 0 {
+                word = [sbox[word[1] as usize] ^ round_constant, sbox[word[2] as usize], sbox[word[3] as usize], sbox[word[0] as usize]];
+                round_constant = gf_multiply(round_constant, 0x02u8);
+            } else if key_words > 6 && i % key_words == 4 {
+                word = [sbox[word[0] as usize], sbox[word[1] as usize], sbox[word[2] as usize], sbox[word[3] as usize]];
+            }
+            let previous = words[i - key_words];
+            words.push([previous[0] ^ word[0], previous[1] ^ word[1], previous[2] ^ word[2], previous[3] ^ word[3]]);
+        }
+
+        let mut round_keys : Vec<[u8; 16]> = Vec::with_capacity(rounds + 1);
+        for chunk in words.chunks(4) {
+            let mut round_key = [0u8; 16];
+            for (i, word) in chunk.iter().enumerate() {
+                round_key[4 * i .. 4 * i + 4].copy_from_slice(word);
+            }
+            round_keys.push(round_key);
+        }
+
+        Ok(Aes {
+            sbox : sbox,
+            inverse_sbox : inverse_sbox,
+            round_keys : round_keys,
+        })
+    }
+
+    fn rounds(&self) -> usize {
+        self.round_keys.len() - 1
+    }
+
+    fn add_round_key(state : &mut [u8; 16], round_key : &[u8; 16]) {
+        for (octet, key) in state.iter_mut().zip(round_key.iter()) {
+            *octet ^= *key;
+        }
+    }
+
+    fn substitute_bytes(state : &mut [u8; 16], sbox : &[u8; 256]) {
+        for octet in state.iter_mut() {
+            *octet = sbox[*octet as usize];
+        }
+    }
+
+    fn shift_rows(state : &mut [u8; 16]) {
+        let original = *state;
+        for row in 1 .. 4 {
+            for column in 0 .. 4 {
+                state[row + 4 * column] = original[row + 4 * ((column + row) % 4)];
+            }
+        }
+    }
+
+    fn inverse_shift_rows(state : &mut [u8; 16]) {
+        let original = *state;
+        for row in 1 .. 4 {
+            for column in 0 .. 4 {
+                state[row + 4 * ((column + row) % 4)] = original[row + 4 * column];
+            }
+        }
+    }
+
+    fn mix_columns(state : &mut [u8; 16], coefficients : &[u8; 4]) {
+        for column in state.chunks_mut(4) {
+            let original = [column[0], column[1], column[2], column[3]];
+            for row in 0 .. 4 {
+                column[row] = 0;
+                for i in 0 .. 4 {
+                    column[row] ^= gf_multiply(coefficients[(4 + i - row) % 4], original[i]);
+                }
+            }
+        }
+    }
+
+    fn encrypt_block(&self, block : &[u8]) -> Vec<u8> {
+        assert!(block.len() == 16);
+        let mut state = [0u8; 16];
+        state.copy_from_slice(block);
+
+        Aes::add_round_key(&mut state, &self.round_keys[0]);
+        for round in 1 .. self.rounds() + 1 {
+            Aes::substitute_bytes(&mut state, &self.sbox);
+            Aes::shift_rows(&mut state);
+            if round != self.rounds() {
+                Aes::mix_columns(&mut state, &[0x02u8, 0x03u8, 0x01u8, 0x01u8]);
+            }
+            Aes::add_round_key(&mut state, &self.round_keys[round]);
+        }
+
+        state.to_vec()
+    }
+
+    fn decrypt_block(&self, block : &[u8]) -> Vec<u8> {
+        assert!(block.len() == 16);
+        let mut state = [0u8; 16];
+        state.copy_from_slice(block);
+
+        for round in (1 .. self.rounds() + 1).rev() {
+            Aes::add_round_key(&mut state, &self.round_keys[round]);
+            if round != self.rounds() {
+                Aes::mix_columns(&mut state, &[0x0Eu8, 0x0Bu8, 0x0Du8, 0x09u8]);
+            }
+            Aes::inverse_shift_rows(&mut state);
+            Aes::substitute_bytes(&mut state, &self.inverse_sbox);
+        }
+        Aes::add_round_key(&mut state, &self.round_keys[0]);
+
+        state.to_vec()
+    }
+}
+
+fn ecb_encrypt(aes : &Aes, input : &[u8]) -> Vec<u8> {
+    let mut output : Vec<u8> = Vec::with_capacity(input.len());
+    for block in input.chunks(16) {
+        output.extend(aes.encrypt_block(block));
+    }
+    output
+}
+
+fn ecb_decrypt(aes : &Aes, input : &[u8]) -> Vec<u8> {
+    let mut output : Vec<u8> = Vec::with_capacity(input.len());
+    for block in input.chunks(16) {
+        output.extend(aes.decrypt_block(block));
+    }
+    output
+}
+
+fn nibble2hex(nibble : u8) -> Result<u8, ()> {
+    match nibble {
+        0u8 ... 9u8 => Ok(nibble + 0x30u8),
+        10u8 ... 15u8 => Ok(nibble + 0x57u8),
+        _ => Err(()),
+    }
+}
+
+fn hex2nibble(hex : u8) -> Result<u8, ()> {
+    match hex {
+        0x30u8 ... 0x39u8 => Ok(hex - 0x30u8),
+        0x41u8 ... 0x46u8 | 0x61u8 ... 0x66u8 => Ok((hex & !0x20u8) - 0x37),
+        _ => Err(()),
+    }
+}
+
+fn escape(input : &[u8]) -> Vec<u8> {
+    let mut escaped : Vec<u8> = Vec::with_capacity(input.len());
+    for octet in input {
+        match *octet {
+            0x25u8 | 0x26u8 | 0x3Du8 => {
+                escaped.push(0x25u8);
+                escaped.push(nibble2hex((octet & 0xF0u8) >> 4).unwrap());
+                escaped.push(nibble2hex(octet & 0x0Fu8).unwrap());
+            },
+            _ => escaped.push(*octet),
+        }
+    }
+    escaped
+}
+
+fn unescape(input : &[u8]) -> Result<Vec<u8>, ()> {
+    let mut unescaped : Vec<u8> = Vec::with_capacity(input.len());
+    let mut octets = input.iter();
+    while let Some(octet) = octets.next() {
+        match *octet {
+            0x25u8 => {
+                let high = hex2nibble(*octets.next().ok_or(())?)?;
+                let low = hex2nibble(*octets.next().ok_or(())?)?;
+                unescaped.push((high << 4) + low);
+            },
+            _ => unescaped.push(*octet),
+        }
+    }
+    Ok(unescaped)
+}
+
+fn encode_kv(pairs : &[(Vec<u8>, Vec<u8>)]) -> Vec<u8> {
+    let mut encoded : Vec<u8> = Vec::new();
+    for (i, &(ref key, ref value)) in pairs.iter().enumerate() {
+        if i > 0 {
+            encoded.push(0x26u8);
+        }
+        encoded.extend(escape(key));
+        encoded.push(0x3Du8);
+        encoded.extend(escape(value));
+    }
+    encoded
+}
+
+fn parse_kv(input : &[u8]) -> Result<Vec<(Vec<u8>, Vec<u8>)>, ()> {
+    let mut pairs : Vec<(Vec<u8>, Vec<u8>)> = Vec::new();
+    if input.is_empty() {
+        return Ok(pairs);
+    }
+    for pair in input.split(|octet| *octet == 0x26u8) {
+        let mut parts = pair.splitn(2, |octet| *octet == 0x3Du8);
+        let key = unescape(parts.next().unwrap())?;
+        let value = unescape(parts.next().ok_or(())?)?;
+        pairs.push((key, value));
+    }
+    Ok(pairs)
+}
+
+fn lookup<'a>(pairs : &'a [(Vec<u8>, Vec<u8>)], key : &[u8]) -> Option<&'a [u8]> {
+    pairs.iter().find(|&&(ref k, _)| &k[..] == key).map(|&(_, ref v)| &v[..])
+}
+
+trait EncryptionOracle {
+    fn encrypt(&mut self, input : &[u8]) -> Vec<u8>;
+}
+
+struct ProfileOracle {
+    aes : Aes,
+    field : Vec<u8>,
+    trailing : Vec<(Vec<u8>, Vec<u8>)>,
+}
+
+impl ProfileOracle {
+    fn new(field : &[u8], trailing : Vec<(Vec<u8>, Vec<u8>)>) -> ProfileOracle {
+        ProfileOracle {
+            aes : Aes::new(&random_octets(16)).unwrap(),
+            field : field.to_vec(),
+            trailing : trailing,
+        }
+    }
+
+    fn profile_for(&self, value : &[u8]) -> Vec<u8> {
+        let mut pairs = vec![(self.field.clone(), value.to_vec())];
+        pairs.extend(self.trailing.iter().cloned());
+        encode_kv(&pairs)
+    }
+
+    fn decrypt_profile(&self, ciphertext : &[u8]) -> Result<Vec<(Vec<u8>, Vec<u8>)>, ()> {
+        parse_kv(&pkcs7_unpad(&ecb_decrypt(&self.aes, ciphertext), 16)?)
+    }
+}
+
+impl EncryptionOracle for ProfileOracle {
+    fn encrypt(&mut self, input : &[u8]) -> Vec<u8> {
+        ecb_encrypt(&self.aes, &pkcs7_pad(&self.profile_for(input), 16))
+    }
+}
+
+fn detect_block_size<O : EncryptionOracle>(oracle : &mut O) -> Result<(usize, usize), ()> {
+    let base_length = oracle.encrypt(&[]).len();
+    let mut input : Vec<u8> = Vec::new();
+    while input.len() < 256 {
+        input.push(0x41u8);
+        let length = oracle.encrypt(&input).len();
+        if length > base_length {
+            if base_length < input.len() {
+                return Err(());
+            }
+            return Ok((length - base_length, base_length - input.len()));
+        }
+    }
+    Err(())
+}
+
+fn has_repeated_block(ciphertext : &[u8], block_size : usize) -> Option<usize> {
+    let blocks : Vec<&[u8]> = ciphertext.chunks(block_size).collect();
+    for i in 1 .. blocks.len() {
+        if blocks[i - 1] == blocks[i] {
+            return Some(i - 1);
+        }
+    }
+    None
+}
+
+fn detect_ecb<O : EncryptionOracle>(oracle : &mut O, block_size : usize) -> bool {
+    has_repeated_block(&oracle.encrypt(&vec![0x41u8; 3 * block_size]), block_size).is_some()
+}
+
+fn first_different_block(left : &[u8], right : &[u8], block_size : usize) -> Option<usize> {
+    left.chunks(block_size).zip(right.chunks(block_size)).position(|(a, b)| a != b)
+}
+
+fn find_prefix_length<O : EncryptionOracle>(oracle : &mut O, block_size : usize) -> usize {
+    let block = first_different_block(&oracle.encrypt(&[0x00u8]), &oracle.encrypt(&[0xFFu8]), block_size).unwrap();
+    for filled in 1 .. block_size + 1 {
+        let mut input = vec![0x41u8; filled];
+        input.push(0x00u8);
+        let left = oracle.encrypt(&input);
+        input[filled] = 0xFFu8;
+        let right = oracle.encrypt(&input);
+        if first_different_block(&left, &right, block_size) != Some(block) {
+            return block * block_size + block_size - filled;
+        }
+    }
+    unreachable!()
+}
+
+fn cut_and_paste<O : EncryptionOracle>(oracle : &mut O, replaced : usize, value : &[u8]) -> Result<Vec<u8>, ()> {
+    let (block_size, hidden_length) = detect_block_size(oracle)?;
+    if block_size < 2 || !detect_ecb(oracle, block_size) {
+        return Err(());
+    }
+    if replaced > hidden_length || value.len() >= block_size {
+        return Err(());
+    }
+    let prefix_length = find_prefix_length(oracle, block_size);
+    let alignment = (block_size - prefix_length % block_size) % block_size;
+    let skip = prefix_length + alignment;
+
+    let mut input = vec![0x41u8; alignment];
+    input.extend(pkcs7_pad(value, block_size));
+    let pasted = oracle.encrypt(&input)[skip .. skip + block_size].to_vec();
+
+    let kept = hidden_length - replaced;
+    let filler = (block_size - kept % block_size) % block_size;
+    let mut forged = oracle.encrypt(&vec![0x41u8; filler]);
+    forged.truncate(kept + filler);
+    forged.extend(pasted);
+    Ok(forged)
+}
+
+fn main() {
+    let mut oracle = ProfileOracle::new(b"email", vec![(b"uid".to_vec(), b"10".to_vec()), (b"role".to_vec(), b"user".to_vec())]);
+    println!("{}", String::from_utf8(oracle.profile_for(b"foo@bar.com&role=admin")).unwrap());
+
+    let forged = cut_and_paste(&mut oracle, 4, b"admin").unwrap();
+    let profile = oracle.decrypt_profile(&forged).unwrap();
+    println!("{}", String::from_utf8(encode_kv(&profile)).unwrap());
+    println!("Role: {}", String::from_utf8(lookup(&profile, b"role").unwrap().to_vec()).unwrap());
+}
+
+#[cfg(test)]
+mod tests {
+
+    use EncryptionOracle;
+
+    struct FixedOracle;
+
+    impl EncryptionOracle for FixedOracle {
+        fn encrypt(&mut self, _ : &[u8]) -> Vec<u8> {
+            vec![0x00u8; 32]
+        }
+    }
+
+    fn pairs(input : &[(&str, &str)]) -> Vec<(Vec<u8>, Vec<u8>)> {
+        input.iter().map(|&(k, v)| (k.as_bytes().to_vec(), v.as_bytes().to_vec())).collect()
+    }
+
+    #[test]
+    fn test_kv() {
+        let parsed = ::parse_kv(b"foo=bar&baz=qux&zap=zazzle").unwrap();
+        assert!(parsed == pairs(&[("foo", "bar"), ("baz", "qux"), ("zap", "zazzle")]));
+        assert!(::encode_kv(&parsed) == b"foo=bar&baz=qux&zap=zazzle".to_vec());
+        assert!(::parse_kv(b"").unwrap().is_empty());
+        assert!(::parse_kv(b"foo").is_err());
+        assert!(::parse_kv(b"foo=%2").is_err());
+        assert!(::parse_kv(b"foo=%zz").is_err());
+    }
+
+    #[test]
+    fn test_escaping() {
+        let oracle = ::ProfileOracle::new(b"email", pairs(&[("uid", "10"), ("role", "user")]));
+        let profile = oracle.profile_for(b"foo@bar.com&role=admin");
+        assert!(profile == b"email=foo@bar.com%26role%3dadmin&uid=10&role=user".to_vec());
+        let parsed = ::parse_kv(&profile).unwrap();
+        assert!(::lookup(&parsed, b"email") == Some(&b"foo@bar.com&role=admin"[..]));
+        assert!(::lookup(&parsed, b"role") == Some(&b"user"[..]));
+        assert!(::unescape(&::escape(b"100%=&")) == Ok(b"100%=&".to_vec()));
+    }
+
+    #[test]
+    fn test_cut_and_paste() {
+        let mut oracle = ::ProfileOracle::new(b"email", pairs(&[("uid", "10"), ("role", "user")]));
+        let forged = ::cut_and_paste(&mut oracle, 4, b"admin").unwrap();
+        let profile = oracle.decrypt_profile(&forged).unwrap();
+        assert!(::lookup(&profile, b"role") == Some(&b"admin"[..]));
+        assert!(::lookup(&profile, b"uid") == Some(&b"10"[..]));
+    }
+
+    #[test]
+    fn test_cut_and_paste_rejects() {
+        let mut oracle = ::ProfileOracle::new(b"email", pairs(&[("uid", "10"), ("role", "user")]));
+        let hidden_length = ::detect_block_size(&mut oracle).unwrap().1;
+        assert!(::cut_and_paste(&mut oracle, hidden_length + 1, b"admin").is_err());
+        assert!(::cut_and_paste(&mut oracle, hidden_length, b"admin").is_ok());
+        assert!(::cut_and_paste(&mut oracle, 4, b"administrator").is_ok());
+        assert!(::cut_and_paste(&mut oracle, 4, b"superadministrator").is_err());
+        assert!(::cut_and_paste(&mut oracle, 4, &[0x41u8; 16]).is_err());
+
+        assert!(::detect_block_size(&mut FixedOracle) == Err(()));
+        assert!(::cut_and_paste(&mut FixedOracle, 4, b"admin").is_err());
+    }
+
+    #[test]
+    fn test_cut_and_paste_other_service() {
+        let mut oracle = ::ProfileOracle::new(b"username", pairs(&[("account", "31337"), ("team", "blue"), ("role", "guest")]));
+        let forged = ::cut_and_paste(&mut oracle, 5, b"admin").unwrap();
+        let profile = oracle.decrypt_profile(&forged).unwrap();
+        assert!(::lookup(&profile, b"role") == Some(&b"admin"[..]));
+        assert!(::lookup(&profile, b"team") == Some(&b"blue"[..]));
+    }
+}
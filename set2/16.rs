@@ -0,0 +1,497 @@
+use std::fs::File;
+use std::io::Read;
+
+fn random_octets(count : usize) -> Vec<u8> {
+    let mut f = File::open("/dev/urandom").unwrap();
+    let mut octets = vec![0u8; count];
+    match f.read_exact(&mut octets) {
+        Ok(_) => (),
+        Err(_) => panic!(),
+    };
+    octets
+}
+
+fn pkcs7_pad(input : &[u8], block_size : usize) -> Vec<u8> {
+    let count = block_size - input.len() % block_size;
+    let mut padded : Vec<u8> = Vec::with_capacity(input.len() + count);
+    padded.extend_from_slice(input);
+    padded.extend(vec![count as u8; count]);
+    padded
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum PaddingError {
+    InvalidBlockSize,
+    InvalidLength,
+    InvalidPadding,
+}
+
+fn mask_less_than(left : usize, right : usize) -> u8 {
+    (((left as u32).wrapping_sub(right as u32) >> 31) as u8).wrapping_neg()
+}
+
+fn mask_zero(octet : u8) -> u8 {
+    mask_less_than(octet as usize, 1)
+}
+
+fn validate(input : &[u8], block_size : usize) -> Result<usize, PaddingError> {
+    if block_size == 0 || block_size > 255 {
+        return Err(PaddingError::InvalidBlockSize);
+    }
+    if input.is_empty() || input.len() % block_size != 0 {
+        return Err(PaddingError::InvalidLength);
+    }
+    let last = input[input.len() - 1];
+    let mut invalid = mask_zero(last) | !mask_less_than(last as usize, block_size + 1);
+    for (i, octet) in input[input.len() - block_size ..].iter().rev().enumerate().skip(1) {
+        invalid |= mask_less_than(i, last as usize) & (octet ^ last);
+    }
+    match invalid {
+        0 => Ok(last as usize),
+        _ => Err(PaddingError::InvalidPadding),
+    }
+}
+
+fn pkcs7_unpad(input : &[u8], block_size : usize) -> Result<Vec<u8>, PaddingError> {
+    let count = validate(input, block_size)?;
+    Ok(input[.. input.len() - count].to_vec())
+}
+
+fn fixed_xor(left: &[u8], right: &[u8]) -> Vec<u8> {
+    assert!(left.len() == right.len());
+    let mut xor : Vec<u8> = Vec::with_capacity(left.len());
+    for i in 0 .. left.len() {
+        xor.push(left[i] ^ right[i])
+    }
+    xor
+}
+
+fn gf_multiply(left : u8, right : u8) -> u8 {
+    let mut a = left;
+    let mut b = right;
+    let mut product = 0u8;
+    while b != 0 {
+        if b & 1 == 1 {
+            product ^= a;
+        }
+        a = (a << 1) ^ match a & 0x80u8 {
+            0 => 0x00u8,
+            _ => 0x1Bu8,
+        };
+        b >>= 1;
+    }
+    product
+}
+
+fn gf_inverse(octet : u8) -> u8 {
+    if octet == 0 {
+        return 0;
+    }
+    let mut inverse = 1u8;
+    while gf_multiply(octet, inverse) != 1 {
+        inverse += 1;
+    }
+    inverse
+}
+
+fn sboxes() -> ([u8; 256], [u8; 256]) {
+    let mut sbox = [0u8; 256];
+    let mut inverse_sbox = [0u8; 256];
+    for i in 0 .. 256 {
+        let b = gf_inverse(i as u8);
+        let s = b ^ b.rotate_left(1) ^ b.rotate_left(2) ^ b.rotate_left(3) ^ b.rotate_left(4) ^ 0x63u8;
+        sbox[i] = s;
+        inverse_sbox[s as usize] = i as u8;
+    }
+    (sbox, inverse_sbox)
+}
+
+struct Aes {
+    sbox : [u8; 256],
+    inverse_sbox : [u8; 256],
+    round_keys : Vec<[u8; 16]>,
+}
+
+impl Aes {
+    fn new(key : &[u8]) -> Result<Aes, ()> {
+        let key_words = match key.len() {
+            16 | 24 | 32 => key.len() / 4,
+            _ => return Err(()),
+        };
+        let rounds = key_words + 6;
+        let (sbox, inverse_sbox) = sboxes();
+
+        let mut words : Vec<[u8; 4]> = Vec::with_capacity(4 * (rounds + 1));
+        for chunk in key.chunks(4) {
+            words.push([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        }
+        let mut round_constant = 0x01u8;
+        for i in key_words .. 4 * (rounds + 1) {
+            let mut word = words[i - 1];
+            if i % key_words == 0 {
+                word = [sbox[word[1] as usize] ^ round_constant, sbox[word[2] as usize], sbox[word[3] as usize], sbox[word[0] as usize]];
+                round_constant = gf_multiply(round_constant, 0x02u8);
+            } else if key_words > 6 && i % key_words == 4 {
+                word = [sbox[word[0] as usize], sbox[word[1] as usize], sbox[word[2] as usize], sbox[word[3] as usize]];
+            }
+            let previous = words[i - key_words];
+            words.push([previous[0] ^ word[0], previous[1] ^ word[1], previous[2] ^ word[2], previous[3] ^ word[3]]);
+        }
+
+        let mut round_keys : Vec<[u8; 16]> = Vec::with_capacity(rounds + 1);
+        for chunk in words.chunks(4) {
+            let mut round_key = [0u8; 16];
+            for (i, word) in chunk.iter().enumerate() {
+                round_key[4 * i .. 4 * i + 4].copy_from_slice(word);
+            }
+            round_keys.push(round_key);
+        }
+
+        Ok(Aes {
+            sbox : sbox,
+            inverse_sbox : inverse_sbox,
+            round_keys : round_keys,
+        })
+    }
+
+    fn rounds(&self) -> usize {
+        self.round_keys.len() - 1
+    }
+
+    fn add_round_key(state : &mut [u8; 16], round_key : &[u8; 16]) {
+        for (octet, key) in state.iter_mut().zip(round_key.iter()) {
+            *octet ^= *key;
+        }
+    }
+
+    fn substitute_bytes(state : &mut [u8; 16], sbox : &[u8; 256]) {
+        for octet in state.iter_mut() {
+            *octet = sbox[*octet as usize];
+        }
+    }
+
+    fn shift_rows(state : &mut [u8; 16]) {
+        let original = *state;
+        for row in 1 .. 4 {
+            for column in 0 .. 4 {
+                state[row + 4 * column] = original[row + 4 * ((column + row) % 4)];
+            }
+        }
+    }
+
+    fn inverse_shift_rows(state : &mut [u8; 16]) {
+        let original = *state;
+        for row in 1 .. 4 {
+            for column in 0 .. 4 {
+                state[row + 4 * ((column + row) % 4)] = original[row + 4 * column];
+            }
+        }
+    }
+
+    fn mix_columns(state : &mut [u8; 16], coefficients : &[u8; 4]) {
+        for column in state.chunks_mut(4) {
+            let original = [column[0], column[1], column[2], column[3]];
+            for row in 0 .. 4 {
+                column[row] = 0;
+                for i in 0 .. 4 {
+                    column[row] ^= gf_multiply(coefficients[(4 + i - row) % 4], original[i]);
+                }
+            }
+        }
+    }
+
+    fn encrypt_block(&self, block : &[u8]) -> Vec<u8> {
+        assert!(block.len() == 16);
+        let mut state = [0u8; 16];
+        state.copy_from_slice(block);
+
+        Aes::add_round_key(&mut state, &self.round_keys[0]);
+        for round in 1 .. self.rounds() + 1 {
+            Aes::substitute_bytes(&mut state, &self.sbox);
+            Aes::shift_rows(&mut state);
+            if round != self.rounds() {
+                Aes::mix_columns(&mut state, &[0x02u8, 0x03u8, 0x01u8, 0x01u8]);
+            }
+            Aes::add_round_key(&mut state, &self.round_keys[round]);
+        }
+
+        state.to_vec()
+    }
+
+    fn decrypt_block(&self, block : &[u8]) -> Vec<u8> {
+        assert!(block.len() == 16);
+        let mut state = [0u8; 16];
+        state.copy_from_slice(block);
+
+        for round in (1 .. self.rounds() + 1).rev() {
+            Aes::add_round_key(&mut state, &self.round_keys[round]);
+            if round != self.rounds() {
+                Aes::mix_columns(&mut state, &[0x0Eu8, 0x0Bu8, 0x0Du8, 0x09u8]);
+            }
+            Aes::inverse_shift_rows(&mut state);
+            Aes::substitute_bytes(&mut state, &self.inverse_sbox);
+        }
+        Aes::add_round_key(&mut state, &self.round_keys[0]);
+
+        state.to_vec()
+    }
+}
+
+trait BlockCipher {
+    fn block_size(&self) -> usize;
+    fn encrypt_block(&self, block : &[u8]) -> Vec<u8>;
+    fn decrypt_block(&self, block : &[u8]) -> Vec<u8>;
+}
+
+impl BlockCipher for Aes {
+    fn block_size(&self) -> usize {
+        16
+    }
+
+    fn encrypt_block(&self, block : &[u8]) -> Vec<u8> {
+        Aes::encrypt_block(self, block)
+    }
+
+    fn decrypt_block(&self, block : &[u8]) -> Vec<u8> {
+        Aes::decrypt_block(self, block)
+    }
+}
+
+impl<'a, C : BlockCipher> BlockCipher for &'a C {
+    fn block_size(&self) -> usize {
+        (*self).block_size()
+    }
+
+    fn encrypt_block(&self, block : &[u8]) -> Vec<u8> {
+        (*self).encrypt_block(block)
+    }
+
+    fn decrypt_block(&self, block : &[u8]) -> Vec<u8> {
+        (*self).decrypt_block(block)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Direction {
+    Encrypt,
+    Decrypt,
+}
+
+trait Mode {
+    fn update(&mut self, input : &[u8]) -> Vec<u8>;
+    fn finish(self) -> Result<Vec<u8>, ()>;
+}
+
+fn process<M : Mode>(mut mode : M, input : &[u8]) -> Result<Vec<u8>, ()> {
+    let mut output = mode.update(input);
+    output.extend(mode.finish()?);
+    Ok(output)
+}
+
+fn take_blocks(buffer : &mut Vec<u8>, input : &[u8], block_size : usize) -> Vec<u8> {
+    buffer.extend_from_slice(input);
+    let full = buffer.len() - buffer.len() % block_size;
+    buffer.drain(.. full).collect()
+}
+
+struct Cbc<C : BlockCipher> {
+    cipher : C,
+    direction : Direction,
+    previous : Vec<u8>,
+    buffer : Vec<u8>,
+}
+
+impl<C : BlockCipher> Cbc<C> {
+    fn new(cipher : C, iv : &[u8], direction : Direction) -> Cbc<C> {
+        assert!(iv.len() == cipher.block_size());
+        Cbc {
+            cipher : cipher,
+            direction : direction,
+            previous : iv.to_vec(),
+            buffer : Vec::new(),
+        }
+    }
+}
+
+impl<C : BlockCipher> Mode for Cbc<C> {
+    fn update(&mut self, input : &[u8]) -> Vec<u8> {
+        let blocks = take_blocks(&mut self.buffer, input, self.cipher.block_size());
+        let mut output : Vec<u8> = Vec::with_capacity(blocks.len());
+        for block in blocks.chunks(self.cipher.block_size()) {
+            match self.direction {
+                Direction::Encrypt => {
+                    self.previous = self.cipher.encrypt_block(&fixed_xor(block, &self.previous));
+                    output.extend_from_slice(&self.previous);
+                },
+                Direction::Decrypt => {
+                    output.extend(fixed_xor(&self.cipher.decrypt_block(block), &self.previous));
+                    self.previous = block.to_vec();
+                },
+            }
+        }
+        output
+    }
+
+    fn finish(self) -> Result<Vec<u8>, ()> {
+        match self.buffer.is_empty() {
+            true => Ok(Vec::new()),
+            false => Err(()),
+        }
+    }
+}
+
+trait EncryptionOracle {
+    fn encrypt(&mut self, input : &[u8]) -> Vec<u8>;
+}
+
+fn first_different_block(left : &[u8], right : &[u8], block_size : usize) -> Option<usize> {
+    left.chunks(block_size).zip(right.chunks(block_size)).position(|(a, b)| a != b)
+}
+
+fn find_prefix_length<O : EncryptionOracle>(oracle : &mut O, block_size : usize) -> usize {
+    let block = first_different_block(&oracle.encrypt(&[0x00u8]), &oracle.encrypt(&[0xFFu8]), block_size).unwrap();
+    for filled in 1 .. block_size + 1 {
+        let mut input = vec![0x41u8; filled];
+        input.push(0x00u8);
+        let left = oracle.encrypt(&input);
+        input[filled] = 0xFFu8;
+        let right = oracle.encrypt(&input);
+        if first_different_block(&left, &right, block_size) != Some(block) {
+            return block * block_size + block_size - filled;
+        }
+    }
+    unreachable!()
+}
+
+fn bitflip(ciphertext : &[u8], block_size : usize, known : &[u8], target : usize, desired : &[u8]) -> Result<Vec<u8>, ()> {
+    if target == 0 || known.len() != desired.len() || known.len() > block_size || (target + 1) * block_size > ciphertext.len() {
+        return Err(());
+    }
+    let start = (target - 1) * block_size;
+    let mut flipped = ciphertext.to_vec();
+    let delta = fixed_xor(known, desired);
+    let previous = fixed_xor(&ciphertext[start .. start + known.len()], &delta);
+    flipped[start .. start + known.len()].copy_from_slice(&previous);
+    Ok(flipped)
+}
+
+fn quote(input : &[u8]) -> Vec<u8> {
+    let mut quoted : Vec<u8> = Vec::with_capacity(input.len());
+    for octet in input {
+        match *octet {
+            0x3Bu8 => quoted.extend_from_slice(b"%3B"),
+            0x3Du8 => quoted.extend_from_slice(b"%3D"),
+            _ => quoted.push(*octet),
+        }
+    }
+    quoted
+}
+
+struct CommentOracle {
+    aes : Aes,
+    iv : Vec<u8>,
+}
+
+impl CommentOracle {
+    fn new() -> CommentOracle {
+        CommentOracle {
+            aes : Aes::new(&random_octets(16)).unwrap(),
+            iv : random_octets(16),
+        }
+    }
+
+    fn decrypt(&self, ciphertext : &[u8]) -> Result<Vec<u8>, ()> {
+        pkcs7_unpad(&process(Cbc::new(&self.aes, &self.iv, Direction::Decrypt), ciphertext)?, 16).map_err(|_| ())
+    }
+
+    fn is_admin(&self, ciphertext : &[u8]) -> bool {
+        match self.decrypt(ciphertext) {
+            Ok(plaintext) => plaintext.windows(12).any(|window| window == b";admin=true;"),
+            Err(_) => false,
+        }
+    }
+}
+
+impl EncryptionOracle for CommentOracle {
+    fn encrypt(&mut self, input : &[u8]) -> Vec<u8> {
+        let mut plaintext = b"comment1=cooking%20MCs;userdata=".to_vec();
+        plaintext.extend(quote(input));
+        plaintext.extend_from_slice(b";comment2=%20like%20a%20pound%20of%20bacon");
+        process(Cbc::new(&self.aes, &self.iv, Direction::Encrypt), &pkcs7_pad(&plaintext, 16)).unwrap()
+    }
+}
+
+fn inject<O : EncryptionOracle>(oracle : &mut O, block_size : usize, desired : &[u8]) -> Result<Vec<u8>, ()> {
+    if desired.len() > block_size {
+        return Err(());
+    }
+    let prefix_length = find_prefix_length(oracle, block_size);
+    let alignment = (block_size - prefix_length % block_size) % block_size;
+    let target = (prefix_length + alignment) / block_size + 1;
+
+    let input = vec![0x41u8; alignment + 2 * block_size];
+    let ciphertext = oracle.encrypt(&input);
+    bitflip(&ciphertext, block_size, &input[.. desired.len()], target, desired)
+}
+
+fn main() {
+    let mut oracle = CommentOracle::new();
+    let honest = oracle.encrypt(b";admin=true;");
+    println!("{}", String::from_utf8_lossy(&oracle.decrypt(&honest).unwrap()));
+    println!("Admin: {}", oracle.is_admin(&honest));
+
+    let forged = inject(&mut oracle, 16, b";admin=true;").unwrap();
+    println!("{}", String::from_utf8_lossy(&oracle.decrypt(&forged).unwrap()));
+    println!("Admin: {}", oracle.is_admin(&forged));
+}
+
+#[cfg(test)]
+mod tests {
+
+    use EncryptionOracle;
+    use PaddingError;
+
+    #[test]
+    fn test_pkcs7_unpad() {
+        assert!(::pkcs7_unpad(b"ICE ICE BABY\x04\x04\x04\x04", 16) == Ok(String::from("ICE ICE BABY").into_bytes()));
+        assert!(::pkcs7_unpad(b"ICE ICE BABY\x05\x05\x05\x05", 16) == Err(PaddingError::InvalidPadding));
+        assert!(::pkcs7_unpad(b"ICE ICE BABY\x01\x02\x03\x04", 16) == Err(PaddingError::InvalidPadding));
+        assert!(::pkcs7_unpad(b"ICE ICE BABY\x00", 13) == Err(PaddingError::InvalidPadding));
+        assert!(::pkcs7_unpad(b"ICE ICE BABY", 16) == Err(PaddingError::InvalidLength));
+    }
+
+    #[test]
+    fn test_quote() {
+        assert!(::quote(b";admin=true;") == b"%3Badmin%3Dtrue%3B".to_vec());
+        assert!(::quote(b"userdata") == b"userdata".to_vec());
+    }
+
+    #[test]
+    fn test_bitflip() {
+        let ciphertext = vec![0u8; 48];
+        assert!(::bitflip(&ciphertext, 16, b"AAAA", 0, b"BBBB") == Err(()));
+        assert!(::bitflip(&ciphertext, 16, b"AAAA", 3, b"BBBB") == Err(()));
+        assert!(::bitflip(&ciphertext, 16, b"AAAA", 1, b"BBB") == Err(()));
+        let flipped = ::bitflip(&ciphertext, 16, b"AAAA", 2, b"ABCD").unwrap();
+        assert!(flipped[16 .. 20] == [0x00u8, 0x03u8, 0x02u8, 0x05u8]);
+        assert!(flipped[.. 16] == ciphertext[.. 16]);
+        assert!(flipped[20 ..] == ciphertext[20 ..]);
+    }
+
+    #[test]
+    fn test_quoted_input() {
+        let mut oracle = ::CommentOracle::new();
+        let ciphertext = oracle.encrypt(b";admin=true;");
+        assert!(!oracle.is_admin(&ciphertext));
+    }
+
+    #[test]
+    fn test_inject() {
+        let mut oracle = ::CommentOracle::new();
+        let forged = ::inject(&mut oracle, 16, b";admin=true;").unwrap();
+        assert!(oracle.is_admin(&forged));
+        let plaintext = oracle.decrypt(&forged).unwrap();
+        assert!(plaintext[.. 32] == b"comment1=cooking%20MCs;userdata="[..]);
+        assert!(plaintext[48 .. 60] == b";admin=true;"[..]);
+    }
+}
@@ -0,0 +1,194 @@
+use std::fs::File;
+use std::io::Read;
+
+fn nibble2hex(nibble : u8) -> Result<u8, ()> {
+    match nibble {
+        0u8 ... 9u8 => Ok(nibble + 0x30u8),
+        10u8 ... 15u8 => Ok(nibble + 0x57u8),
+        _ => Err(()),
+    }
+}
+
+fn octets2hex(octets: &[u8]) -> Vec<u8> {
+    let mut hex : Vec<u8> = Vec::with_capacity(octets.len() * 2);
+    for octet in octets {
+        hex.push(nibble2hex((octet & 0xF0u8) >> 4).unwrap());
+        hex.push(nibble2hex(octet & 0x0Fu8).unwrap());
+    }
+    hex
+}
+
+fn random_octets(count : usize) -> Vec<u8> {
+    let mut f = File::open("/dev/urandom").unwrap();
+    let mut octets = vec![0u8; count];
+    match f.read_exact(&mut octets) {
+        Ok(_) => (),
+        Err(_) => panic!(),
+    };
+    octets
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Padding {
+    Pkcs7,
+    AnsiX923,
+    Iso10126,
+    Zero,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum PaddingError {
+    InvalidBlockSize,
+    InvalidLength,
+    InvalidPadding,
+}
+
+fn pad(input : &[u8], block_size : usize, padding : Padding) -> Result<Vec<u8>, PaddingError> {
+    if block_size == 0 || block_size > 255 {
+        return Err(PaddingError::InvalidBlockSize);
+    }
+    let count = match padding {
+        Padding::Zero => (block_size - input.len() % block_size) % block_size,
+        _ => block_size - input.len() % block_size,
+    };
+    let mut padded : Vec<u8> = Vec::with_capacity(input.len() + count);
+    padded.extend_from_slice(input);
+    match padding {
+        Padding::Pkcs7 => padded.extend(vec![count as u8; count]),
+        Padding::AnsiX923 => {
+            padded.extend(vec![0u8; count - 1]);
+            padded.push(count as u8);
+        },
+        Padding::Iso10126 => {
+            padded.extend(random_octets(count - 1));
+            padded.push(count as u8);
+        },
+        Padding::Zero => padded.extend(vec![0u8; count]),
+    }
+    Ok(padded)
+}
+
+fn mask_less_than(left : usize, right : usize) -> u8 {
+    (((left as u32).wrapping_sub(right as u32) >> 31) as u8).wrapping_neg()
+}
+
+fn mask_zero(octet : u8) -> u8 {
+    mask_less_than(octet as usize, 1)
+}
+
+fn validate(input : &[u8], block_size : usize, padding : Padding) -> Result<usize, PaddingError> {
+    if block_size == 0 || block_size > 255 {
+        return Err(PaddingError::InvalidBlockSize);
+    }
+    if input.is_empty() || input.len() % block_size != 0 {
+        return Err(PaddingError::InvalidLength);
+    }
+    let last = input[input.len() - 1];
+    if padding == Padding::Zero {
+        let mut count = 0usize;
+        let mut trailing = 0xFFu8;
+        for octet in input[input.len() - block_size ..].iter().rev() {
+            trailing &= mask_zero(*octet);
+            count += (trailing & 1) as usize;
+        }
+        return Ok(count);
+    }
+
+    let mut invalid = mask_zero(last) | !mask_less_than(last as usize, block_size + 1);
+    for (i, octet) in input[input.len() - block_size ..].iter().rev().enumerate().skip(1) {
+        let in_padding = mask_less_than(i, last as usize);
+        invalid |= in_padding & match padding {
+            Padding::Pkcs7 => octet ^ last,
+            Padding::AnsiX923 => *octet,
+            _ => 0u8,
+        };
+    }
+    match invalid {
+        0 => Ok(last as usize),
+        _ => Err(PaddingError::InvalidPadding),
+    }
+}
+
+fn unpad(input : &[u8], block_size : usize, padding : Padding) -> Result<Vec<u8>, PaddingError> {
+    let count = validate(input, block_size, padding)?;
+    Ok(input[.. input.len() - count].to_vec())
+}
+
+fn pkcs7_pad(input : &[u8], block_size : usize) -> Vec<u8> {
+    pad(input, block_size, Padding::Pkcs7).unwrap()
+}
+
+fn pkcs7_unpad(input : &[u8], block_size : usize) -> Result<Vec<u8>, PaddingError> {
+    unpad(input, block_size, Padding::Pkcs7)
+}
+
+fn main() {
+    let padded = pkcs7_pad(&String::from("YELLOW SUBMARINE").into_bytes(), 20);
+    println!("{:?}", String::from_utf8(padded.clone()).unwrap());
+    println!("{}", String::from_utf8(octets2hex(&padded)).unwrap());
+    println!("{:?}", pkcs7_unpad(&padded, 20).map(|octets| String::from_utf8(octets).unwrap()));
+}
+
+#[cfg(test)]
+mod tests {
+
+    use Padding;
+    use PaddingError;
+
+    #[test]
+    fn test_pkcs7() {
+        let input = String::from("YELLOW SUBMARINE").into_bytes();
+        let padded = ::pkcs7_pad(&input, 20);
+        assert!(padded == String::from("YELLOW SUBMARINE\x04\x04\x04\x04").into_bytes());
+        assert!(::pkcs7_unpad(&padded, 20) == Ok(input.clone()));
+        assert!(::pkcs7_pad(&input, 16).len() == 32);
+        assert!(::pkcs7_unpad(&::pkcs7_pad(&input, 16), 16) == Ok(input.clone()));
+        assert!(::pkcs7_unpad(&::pkcs7_pad(&[], 16), 16) == Ok(Vec::new()));
+
+        assert!(::pkcs7_unpad(b"ICE ICE BABY\x04\x04\x04\x04", 16) == Ok(String::from("ICE ICE BABY").into_bytes()));
+        assert!(::pkcs7_unpad(b"ICE ICE BABY\x05\x05\x05\x05", 16) == Err(PaddingError::InvalidPadding));
+        assert!(::pkcs7_unpad(b"ICE ICE BABY\x01\x02\x03\x04", 16) == Err(PaddingError::InvalidPadding));
+        assert!(::pkcs7_unpad(b"ICE ICE BABY\x04\x04\x04\x00", 16) == Err(PaddingError::InvalidPadding));
+        assert!(::pkcs7_unpad(b"ICE ICE BABY\x04\x04\x04\x11", 16) == Err(PaddingError::InvalidPadding));
+        assert!(::pkcs7_unpad(b"ICE ICE BABY\x04\x04\x04", 16) == Err(PaddingError::InvalidLength));
+        assert!(::pkcs7_unpad(&[], 16) == Err(PaddingError::InvalidLength));
+        assert!(::pad(&[], 0, Padding::Pkcs7) == Err(PaddingError::InvalidBlockSize));
+        assert!(::pad(&[], 256, Padding::Pkcs7) == Err(PaddingError::InvalidBlockSize));
+    }
+
+    #[test]
+    fn test_ansi_x923() {
+        let padded = ::pad(b"ICE ICE BABY", 16, Padding::AnsiX923).unwrap();
+        assert!(padded == b"ICE ICE BABY\x00\x00\x00\x04".to_vec());
+        assert!(::unpad(&padded, 16, Padding::AnsiX923) == Ok(b"ICE ICE BABY".to_vec()));
+        assert!(::unpad(b"ICE ICE BABY\x00\x01\x00\x04", 16, Padding::AnsiX923) == Err(PaddingError::InvalidPadding));
+        assert!(::unpad(b"ICE ICE BABY\x04\x04\x04\x04", 16, Padding::AnsiX923) == Err(PaddingError::InvalidPadding));
+    }
+
+    #[test]
+    fn test_iso10126() {
+        let padded = ::pad(b"ICE ICE BABY", 16, Padding::Iso10126).unwrap();
+        assert!(padded.len() == 16);
+        assert!(padded[15] == 4u8);
+        assert!(::unpad(&padded, 16, Padding::Iso10126) == Ok(b"ICE ICE BABY".to_vec()));
+        assert!(::unpad(b"ICE ICE BABY\x13\x37\x42\x11", 16, Padding::Iso10126) == Err(PaddingError::InvalidPadding));
+    }
+
+    #[test]
+    fn test_zero() {
+        let padded = ::pad(b"ICE ICE BABY", 16, Padding::Zero).unwrap();
+        assert!(padded == b"ICE ICE BABY\x00\x00\x00\x00".to_vec());
+        assert!(::pad(b"YELLOW SUBMARINE", 16, Padding::Zero) == Ok(b"YELLOW SUBMARINE".to_vec()));
+        assert!(::unpad(&padded, 16, Padding::Zero) == Ok(b"ICE ICE BABY".to_vec()));
+        assert!(::unpad(b"YELLOW SUBMARINE", 16, Padding::Zero) == Ok(b"YELLOW SUBMARINE".to_vec()));
+    }
+
+    #[test]
+    fn test_masks() {
+        assert!(::mask_less_than(0, 1) == 0xFFu8);
+        assert!(::mask_less_than(1, 1) == 0x00u8);
+        assert!(::mask_less_than(2, 1) == 0x00u8);
+        assert!(::mask_zero(0) == 0xFFu8);
+        assert!(::mask_zero(0x80) == 0x00u8);
+    }
+}
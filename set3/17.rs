@@ -0,0 +1,748 @@
+use std::fs::File;
+use std::io;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::io::Read;
+use std::io::Write;
+use std::net::SocketAddr;
+use std::net::TcpListener;
+use std::net::TcpStream;
+use std::thread;
+
+fn base64decode(input : &[u8]) -> Vec<u8> {
+    let mut octets : Vec<u8> = Vec::new();
+
+    let mut input_octets = input.iter().map(|c| match *c {
+        0x41u8 ... 0x5Au8 => c - 0x41u8,
+        0x61u8 ... 0x7Au8 => c - 0x47u8,
+        0x30u8 ... 0x39u8 => c + 0x04u8,
+        0x2Bu8 => 0x3Eu8,
+        0x2Fu8 => 0x3Fu8,
+        0x3Du8 => 0xFFu8,
+        i => panic!("{}", i),
+    }).collect::<Vec<u8>>();
+    input_octets.retain(|c| *c != 0xFFu8);
+    for chunk in input_octets.chunks(4) {
+        let groups = match chunk.len() {
+            2 => vec![((chunk[0] << 2) & 0xFCu8) + ((chunk[1] >> 4) & 0x03u8)],
+            3 => vec![((chunk[0] << 2) & 0xFCu8) + ((chunk[1] >> 4) & 0x03u8), ((chunk[1] << 4) & 0xF0u8) + ((chunk[2] >> 2) & 0x0F)],
+            4 => vec![((chunk[0] << 2) & 0xFCu8) + ((chunk[1] >> 4) & 0x03u8), ((chunk[1] << 4) & 0xF0u8) + ((chunk[2] >> 2) & 0x0F), ((chunk[2] << 6) & 0xC0u8) + (chunk[3] & 0x3Fu8)],
+            _ => panic!(),
+        };
+        octets.reserve(groups.len());
+        for group in groups {
+            octets.push(group)
+        }
+    }
+
+    octets
+}
+
+fn nibble2hex(nibble : u8) -> Result<u8, ()> {
+    match nibble {
+        0u8 ... 9u8 => Ok(nibble + 0x30u8),
+        10u8 ... 15u8 => Ok(nibble + 0x57u8),
+        _ => Err(()),
+    }
+}
+
+fn hex2nibble(hex : u8) -> Result<u8, ()> {
+    match hex {
+        0x30u8 ... 0x39u8 => Ok(hex - 0x30u8),
+        0x41u8 ... 0x46u8 | 0x61u8 ... 0x66u8 => Ok((hex & !0x20u8) - 0x37),
+        _ => Err(()),
+    }
+}
+
+fn hex2octets(hex: &[u8]) -> Result<Vec<u8>, ()> {
+    let mut octets : Vec<u8> = Vec::with_capacity(&hex.len() / 2 + &hex.len() % 2);
+    if hex.len() % 2 == 1 {
+        octets.push(hex2nibble(hex[0])?)
+    }
+    for octet in hex[&hex.len() % 2 ..].chunks(2) {
+        octets.push((hex2nibble(octet[0])? << 4) + hex2nibble(octet[1])?)
+    }
+    Ok(octets)
+}
+
+fn octets2hex(octets: &[u8]) -> Vec<u8> {
+    let mut hex : Vec<u8> = Vec::with_capacity(octets.len() * 2);
+    for octet in octets {
+        hex.push(nibble2hex((octet & 0xF0u8) >> 4).unwrap());
+        hex.push(nibble2hex(octet & 0x0Fu8).unwrap());
+    }
+    hex
+}
+
+fn random_octets(count : usize) -> Vec<u8> {
+    let mut f = File::open("/dev/urandom").unwrap();
+    let mut octets = vec![0u8; count];
+    match f.read_exact(&mut octets) {
+        Ok(_) => (),
+        Err(_) => panic!(),
+    };
+    octets
+}
+
+fn pkcs7_pad(input : &[u8], block_size : usize) -> Vec<u8> {
+    let count = block_size - input.len() % block_size;
+    let mut padded : Vec<u8> = Vec::with_capacity(input.len() + count);
+    padded.extend_from_slice(input);
+    padded.extend(vec![count as u8; count]);
+    padded
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum PaddingError {
+    InvalidBlockSize,
+    InvalidLength,
+    InvalidPadding,
+}
+
+fn mask_less_than(left : usize, right : usize) -> u8 {
+    (((left as u32).wrapping_sub(right as u32) >> 31) as u8).wrapping_neg()
+}
+
+fn mask_zero(octet : u8) -> u8 {
+    mask_less_than(octet as usize, 1)
+}
+
+fn validate(input : &[u8], block_size : usize) -> Result<usize, PaddingError> {
+    if block_size == 0 || block_size > 255 {
+        return Err(PaddingError::InvalidBlockSize);
+    }
+    if input.is_empty() || input.len() % block_size != 0 {
+        return Err(PaddingError::InvalidLength);
+    }
+    let last = input[input.len() - 1];
+    let mut invalid = mask_zero(last) | !mask_less_than(last as usize, block_size + 1);
+    for (i, octet) in input[input.len() - block_size ..].iter().rev().enumerate().skip(1) {
+        invalid |= mask_less_than(i, last as usize) & (octet ^ last);
+    }
+    match invalid {
+        0 => Ok(last as usize),
+        _ => Err(PaddingError::InvalidPadding),
+    }
+}
+
+fn pkcs7_unpad(input : &[u8], block_size : usize) -> Result<Vec<u8>, PaddingError> {
+    let count = validate(input, block_size)?;
+    Ok(input[.. input.len() - count].to_vec())
+}
+
+fn fixed_xor(left: &[u8], right: &[u8]) -> Vec<u8> {
+    assert!(left.len() == right.len());
+    let mut xor : Vec<u8> = Vec::with_capacity(left.len());
+    for i in 0 .. left.len() {
+        xor.push(left[i] ^ right[i])
+    }
+    xor
+}
+
+fn gf_multiply(left : u8, right : u8) -> u8 {
+    let mut a = left;
+    let mut b = right;
+    let mut product = 0u8;
+    while b != 0 {
+        if b & 1 == 1 {
+            product ^= a;
+        }
+        a = (a << 1) ^ match a & 0x80u8 {
+            0 => 0x00u8,
+            _ => 0x1Bu8,
+        };
+        b >>= 1;
+    }
+    product
+}
+
+fn gf_inverse(octet : u8) -> u8 {
+    if octet == 0 {
+        return 0;
+    }
+    let mut inverse = 1u8;
+    while gf_multiply(octet, inverse) != 1 {
+        inverse += 1;
+    }
+    inverse
+}
+
+fn sboxes() -> ([u8; 256], [u8; 256]) {
+    let mut sbox = [0u8; 256];
+    let mut inverse_sbox = [0u8; 256];
+    for i in 0 .. 256 {
+        let b = gf_inverse(i as u8);
+        let s = b ^ b.rotate_left(1) ^ b.rotate_left(2) ^ b.rotate_left(3) ^ b.rotate_left(4) ^ 0x63u8;
+        sbox[i] = s;
+        inverse_sbox[s as usize] = i as u8;
+    }
+    (sbox, inverse_sbox)
+}
+
+struct Aes {
+    sbox : [u8; 256],
+    inverse_sbox : [u8; 256],
+    round_keys : Vec<[u8; 16]>,
+}
+
+impl Aes {
+    fn new(key : &[u8]) -> Result<Aes, ()> {
+        let key_words = match key.len() {
+            16 | 24 | 32 => key.len() / 4,
+            _ => return Err(()),
+        };
+        let rounds = key_words + 6;
+        let (sbox, inverse_sbox) = sboxes();
+
+        let mut words : Vec<[u8; 4]> = Vec::with_capacity(4 * (rounds + 1));
+        for chunk in key.chunks(4) {
+            words.push([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        }
+        let mut round_constant = 0x01u8;
+        for i in key_words .. 4 * (rounds + 1) {
+            let mut word = words[i - 1];
+            if i % key_words == 0 {
+                word = [sbox[word[1] as usize] ^ round_constant, sbox[word[2] as usize], sbox[word[3] as usize], sbox[word[0] as usize]];
+                round_constant = gf_multiply(round_constant, 0x02u8);
+            } else if key_words > 6 && i % key_words == 4 {
+                word = [sbox[word[0] as usize], sbox[word[1] as usize], sbox[word[2] as usize], sbox[word[3] as usize]];
+            }
+            let previous = words[i - key_words];
+            words.push([previous[0] ^ word[0], previous[1] ^ word[1], previous[2] ^ word[2], previous[3] ^ word[3]]);
+        }
+
+        let mut round_keys : Vec<[u8; 16]> = Vec::with_capacity(rounds + 1);
+        for chunk in words.chunks(4) {
+            let mut round_key = [0u8; 16];
+            for (i, word) in chunk.iter().enumerate() {
+                round_key[4 * i .. 4 * i + 4].copy_from_slice(word);
+            }
+            round_keys.push(round_key);
+        }
+
+        Ok(Aes {
+            sbox : sbox,
+            inverse_sbox : inverse_sbox,
+            round_keys : round_keys,
+        })
+    }
+
+    fn rounds(&self) -> usize {
+        self.round_keys.len() - 1
+    }
+
+    fn add_round_key(state : &mut [u8; 16], round_key : &[u8; 16]) {
+        for (octet, key) in state.iter_mut().zip(round_key.iter()) {
+            *octet ^= *key;
+        }
+    }
+
+    fn substitute_bytes(state : &mut [u8; 16], sbox : &[u8; 256]) {
+        for octet in state.iter_mut() {
+            *octet = sbox[*octet as usize];
+        }
+    }
+
+    fn shift_rows(state : &mut [u8; 16]) {
+        let original = *state;
+        for row in 1 .. 4 {
+            for column in 0 .. 4 {
+                state[row + 4 * column] = original[row + 4 * ((column + row) % 4)];
+            }
+        }
+    }
+
+    fn inverse_shift_rows(state : &mut [u8; 16]) {
+        let original = *state;
+        for row in 1 .. 4 {
+            for column in 0 .. 4 {
+                state[row + 4 * ((column + row) % 4)] = original[row + 4 * column];
+            }
+        }
+    }
+
+    fn mix_columns(state : &mut [u8; 16], coefficients : &[u8; 4]) {
+        for column in state.chunks_mut(4) {
+            let original = [column[0], column[1], column[2], column[3]];
+            for row in 0 .. 4 {
+                column[row] = 0;
+                for i in 0 .. 4 {
+                    column[row] ^= gf_multiply(coefficients[(4 + i - row) % 4], original[i]);
+                }
+            }
+        }
+    }
+
+    fn encrypt_block(&self, block : &[u8]) -> Vec<u8> {
+        assert!(block.len() == 16);
+        let mut state = [0u8; 16];
+        state.copy_from_slice(block);
+
+        Aes::add_round_key(&mut state, &self.round_keys[0]);
+        for round in 1 .. self.rounds() + 1 {
+            Aes::substitute_bytes(&mut state, &self.sbox);
+            Aes::shift_rows(&mut state);
+            if round != self.rounds() {
+                Aes::mix_columns(&mut state, &[0x02u8, 0x03u8, 0x01u8, 0x01u8]);
+            }
+            Aes::add_round_key(&mut state, &self.round_keys[round]);
+        }
+
+        state.to_vec()
+    }
+
+    fn decrypt_block(&self, block : &[u8]) -> Vec<u8> {
+        assert!(block.len() == 16);
+        let mut state = [0u8; 16];
+        state.copy_from_slice(block);
+
+        for round in (1 .. self.rounds() + 1).rev() {
+            Aes::add_round_key(&mut state, &self.round_keys[round]);
+            if round != self.rounds() {
+                Aes::mix_columns(&mut state, &[0x0Eu8, 0x0Bu8, 0x0Du8, 0x09u8]);
+            }
+            Aes::inverse_shift_rows(&mut state);
+            Aes::substitute_bytes(&mut state, &self.inverse_sbox);
+        }
+        Aes::add_round_key(&mut state, &self.round_keys[0]);
+
+        state.to_vec()
+    }
+}
+
+trait BlockCipher {
+    fn block_size(&self) -> usize;
+    fn encrypt_block(&self, block : &[u8]) -> Vec<u8>;
+    fn decrypt_block(&self, block : &[u8]) -> Vec<u8>;
+}
+
+impl BlockCipher for Aes {
+    fn block_size(&self) -> usize {
+        16
+    }
+
+    fn encrypt_block(&self, block : &[u8]) -> Vec<u8> {
+        Aes::encrypt_block(self, block)
+    }
+
+    fn decrypt_block(&self, block : &[u8]) -> Vec<u8> {
+        Aes::decrypt_block(self, block)
+    }
+}
+
+impl<'a, C : BlockCipher> BlockCipher for &'a C {
+    fn block_size(&self) -> usize {
+        (*self).block_size()
+    }
+
+    fn encrypt_block(&self, block : &[u8]) -> Vec<u8> {
+        (*self).encrypt_block(block)
+    }
+
+    fn decrypt_block(&self, block : &[u8]) -> Vec<u8> {
+        (*self).decrypt_block(block)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Direction {
+    Encrypt,
+    Decrypt,
+}
+
+trait Mode {
+    fn update(&mut self, input : &[u8]) -> Vec<u8>;
+    fn finish(self) -> Result<Vec<u8>, ()>;
+}
+
+fn process<M : Mode>(mut mode : M, input : &[u8]) -> Result<Vec<u8>, ()> {
+    let mut output = mode.update(input);
+    output.extend(mode.finish()?);
+    Ok(output)
+}
+
+fn take_blocks(buffer : &mut Vec<u8>, input : &[u8], block_size : usize) -> Vec<u8> {
+    buffer.extend_from_slice(input);
+    let full = buffer.len() - buffer.len() % block_size;
+    buffer.drain(.. full).collect()
+}
+
+struct Cbc<C : BlockCipher> {
+    cipher : C,
+    direction : Direction,
+    previous : Vec<u8>,
+    buffer : Vec<u8>,
+}
+
+impl<C : BlockCipher> Cbc<C> {
+    fn new(cipher : C, iv : &[u8], direction : Direction) -> Cbc<C> {
+        assert!(iv.len() == cipher.block_size());
+        Cbc {
+            cipher : cipher,
+            direction : direction,
+            previous : iv.to_vec(),
+            buffer : Vec::new(),
+        }
+    }
+}
+
+impl<C : BlockCipher> Mode for Cbc<C> {
+    fn update(&mut self, input : &[u8]) -> Vec<u8> {
+        let blocks = take_blocks(&mut self.buffer, input, self.cipher.block_size());
+        let mut output : Vec<u8> = Vec::with_capacity(blocks.len());
+        for block in blocks.chunks(self.cipher.block_size()) {
+            match self.direction {
+                Direction::Encrypt => {
+                    self.previous = self.cipher.encrypt_block(&fixed_xor(block, &self.previous));
+                    output.extend_from_slice(&self.previous);
+                },
+                Direction::Decrypt => {
+                    output.extend(fixed_xor(&self.cipher.decrypt_block(block), &self.previous));
+                    self.previous = block.to_vec();
+                },
+            }
+        }
+        output
+    }
+
+    fn finish(self) -> Result<Vec<u8>, ()> {
+        match self.buffer.is_empty() {
+            true => Ok(Vec::new()),
+            false => Err(()),
+        }
+    }
+}
+
+trait PaddingOracle {
+    fn padding_valid(&mut self, iv : &[u8], ciphertext : &[u8]) -> io::Result<bool>;
+}
+
+struct CbcPaddingOracle {
+    aes : Aes,
+}
+
+impl CbcPaddingOracle {
+    fn new() -> CbcPaddingOracle {
+        CbcPaddingOracle {
+            aes : Aes::new(&random_octets(16)).unwrap(),
+        }
+    }
+
+    fn encrypt(&self, plaintext : &[u8]) -> (Vec<u8>, Vec<u8>) {
+        let iv = random_octets(16);
+        let ciphertext = process(Cbc::new(&self.aes, &iv, Direction::Encrypt), &pkcs7_pad(plaintext, 16)).unwrap();
+        (iv, ciphertext)
+    }
+}
+
+impl PaddingOracle for CbcPaddingOracle {
+    fn padding_valid(&mut self, iv : &[u8], ciphertext : &[u8]) -> io::Result<bool> {
+        if iv.len() != 16 {
+            return Ok(false);
+        }
+        Ok(match process(Cbc::new(&self.aes, iv, Direction::Decrypt), ciphertext) {
+            Ok(plaintext) => pkcs7_unpad(&plaintext, 16).is_ok(),
+            Err(_) => false,
+        })
+    }
+}
+
+struct CountingOracle<O : PaddingOracle> {
+    oracle : O,
+    queries : usize,
+}
+
+impl<O : PaddingOracle> CountingOracle<O> {
+    fn new(oracle : O) -> CountingOracle<O> {
+        CountingOracle {
+            oracle : oracle,
+            queries : 0,
+        }
+    }
+}
+
+impl<O : PaddingOracle> PaddingOracle for CountingOracle<O> {
+    fn padding_valid(&mut self, iv : &[u8], ciphertext : &[u8]) -> io::Result<bool> {
+        self.queries += 1;
+        self.oracle.padding_valid(iv, ciphertext)
+    }
+}
+
+fn query_parameter<'a>(query : &'a str, name : &str) -> Option<&'a str> {
+    query.split('&').filter_map(|pair| {
+        let mut parts = pair.splitn(2, '=');
+        match (parts.next(), parts.next()) {
+            (Some(key), Some(value)) if key == name => Some(value),
+            _ => None,
+        }
+    }).next()
+}
+
+fn handle_request<O : PaddingOracle>(oracle : &mut O, request : &str) -> &'static str {
+    let target = match request.split(' ').nth(1) {
+        Some(target) => target,
+        None => return "400 Bad Request",
+    };
+    let query = target.splitn(2, '?').nth(1).unwrap_or("");
+    let iv = query_parameter(query, "iv").ok_or(()).and_then(|iv| hex2octets(iv.as_bytes()));
+    let ciphertext = query_parameter(query, "ciphertext").ok_or(()).and_then(|ciphertext| hex2octets(ciphertext.as_bytes()));
+    match (iv, ciphertext) {
+        (Ok(iv), Ok(ciphertext)) => match oracle.padding_valid(&iv, &ciphertext) {
+            Ok(true) => "200 OK",
+            Ok(false) => "500 Internal Server Error",
+            Err(_) => "503 Service Unavailable",
+        },
+        _ => "400 Bad Request",
+    }
+}
+
+fn skip_headers<R : BufRead>(reader : &mut R) -> io::Result<()> {
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 || header.trim().is_empty() {
+            return Ok(());
+        }
+    }
+}
+
+fn serve_connection<O : PaddingOracle>(oracle : &mut O, stream : TcpStream) -> io::Result<()> {
+    stream.set_nodelay(true)?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut writer = stream;
+    loop {
+        let mut request = String::new();
+        if reader.read_line(&mut request)? == 0 {
+            return Ok(());
+        }
+        skip_headers(&mut reader)?;
+        let status = handle_request(oracle, request.trim());
+        writer.write_all(format!("HTTP/1.1 {}\r\nContent-Length: 0\r\n\r\n", status).as_bytes())?;
+    }
+}
+
+fn serve<O : PaddingOracle + Send + 'static>(mut oracle : O) -> io::Result<SocketAddr> {
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    let address = listener.local_addr()?;
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            if let Ok(stream) = stream {
+                let _ = serve_connection(&mut oracle, stream);
+            }
+        }
+    });
+    Ok(address)
+}
+
+struct HttpPaddingOracle {
+    reader : BufReader<TcpStream>,
+    writer : TcpStream,
+}
+
+impl HttpPaddingOracle {
+    fn connect(address : SocketAddr) -> io::Result<HttpPaddingOracle> {
+        let stream = TcpStream::connect(address)?;
+        stream.set_nodelay(true)?;
+        Ok(HttpPaddingOracle {
+            reader : BufReader::new(stream.try_clone()?),
+            writer : stream,
+        })
+    }
+}
+
+impl PaddingOracle for HttpPaddingOracle {
+    fn padding_valid(&mut self, iv : &[u8], ciphertext : &[u8]) -> io::Result<bool> {
+        let request = format!("GET /?iv={}&ciphertext={} HTTP/1.1\r\nHost: localhost\r\n\r\n", String::from_utf8(octets2hex(iv)).unwrap(), String::from_utf8(octets2hex(ciphertext)).unwrap());
+        self.writer.write_all(request.as_bytes())?;
+
+        let mut status = String::new();
+        self.reader.read_line(&mut status)?;
+        skip_headers(&mut self.reader)?;
+        match status.split(' ').nth(1) {
+            Some("200") => Ok(true),
+            Some("500") => Ok(false),
+            _ => Err(io::Error::new(io::ErrorKind::InvalidData, status.trim().to_string())),
+        }
+    }
+}
+
+fn decrypt_block<O : PaddingOracle>(oracle : &mut O, previous : &[u8], block : &[u8]) -> io::Result<Vec<u8>> {
+    let block_size = block.len();
+    let mut intermediate = vec![0u8; block_size];
+    let mut forged = vec![0u8; block_size];
+    for padding in 1 .. block_size + 1 {
+        let position = block_size - padding;
+        for i in position + 1 .. block_size {
+            forged[i] = intermediate[i] ^ padding as u8;
+        }
+        let mut found = false;
+        for guess in 0u16 .. 256u16 {
+            forged[position] = guess as u8;
+            if !oracle.padding_valid(&forged, block)? {
+                continue;
+            }
+            if padding == 1 && position > 0 {
+                forged[position - 1] ^= 0xFFu8;
+                let unambiguous = oracle.padding_valid(&forged, block)?;
+                forged[position - 1] ^= 0xFFu8;
+                if !unambiguous {
+                    continue;
+                }
+            }
+            intermediate[position] = guess as u8 ^ padding as u8;
+            found = true;
+            break;
+        }
+        if !found {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "no valid padding found"));
+        }
+    }
+    Ok(fixed_xor(&intermediate, previous))
+}
+
+fn padding_oracle_attack<O : PaddingOracle>(oracle : &mut O, iv : &[u8], ciphertext : &[u8]) -> io::Result<Vec<u8>> {
+    let block_size = iv.len();
+    if block_size == 0 || ciphertext.is_empty() || ciphertext.len() % block_size != 0 {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "ciphertext is not a whole number of blocks"));
+    }
+    let mut plaintext : Vec<u8> = Vec::with_capacity(ciphertext.len());
+    let mut previous = iv;
+    for block in ciphertext.chunks(block_size) {
+        plaintext.extend(decrypt_block(oracle, previous, block)?);
+        previous = block;
+    }
+    pkcs7_unpad(&plaintext, block_size).map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "recovered plaintext is not padded"))
+}
+
+fn main() {
+    let strings = vec![
+        "MDAwMDAwTm93IHRoYXQgdGhlIHBhcnR5IGlzIGp1bXBpbmc=",
+        "MDAwMDAxV2l0aCB0aGUgYmFzcyBraWNrZWQgaW4gYW5kIHRoZSBWZWdhJ3MgYXJlIHB1bXBpbic=",
+        "MDAwMDAyUXVpY2sgdG8gdGhlIHBvaW50LCB0byB0aGUgcG9pbnQsIG5vIGZha2luZw==",
+        "MDAwMDAzQ29va2luZyBNQydzIGxpa2UgYSBwb3VuZCBvZiBiYWNvbg==",
+        "MDAwMDA0QnVybmluZyAnZW0sIGlmIHlvdSBhaW4ndCBxdWljayBhbmQgbmltYmxl",
+        "MDAwMDA1SSBnbyBjcmF6eSB3aGVuIEkgaGVhciBhIGN5bWJhbA==",
+        "MDAwMDA2QW5kIGEgaGlnaCBoYXQgd2l0aCBhIHNvdXBlZCB1cCB0ZW1wbw==",
+        "MDAwMDA3SSdtIG9uIGEgcm9sbCwgaXQncyB0aW1lIHRvIGdvIHNvbG8=",
+        "MDAwMDA4b2xsaW4nIGluIG15IGZpdmUgcG9pbnQgb2g=",
+        "MDAwMDA5aXRoIG15IHJhZy10b3AgZG93biBzbyBteSBoYWlyIGNhbiBibG93",
+    ];
+    let oracle = CbcPaddingOracle::new();
+    let ciphertexts : Vec<(Vec<u8>, Vec<u8>)> = strings.iter().map(|string| oracle.encrypt(&base64decode(string.as_bytes()))).collect();
+    let address = serve(oracle).unwrap();
+    println!("Oracle listening on http://{}/", address);
+
+    let mut client = CountingOracle::new(HttpPaddingOracle::connect(address).unwrap());
+    for (iv, ciphertext) in ciphertexts {
+        let plaintext = padding_oracle_attack(&mut client, &iv, &ciphertext).unwrap();
+        println!("{}", String::from_utf8(plaintext).unwrap());
+    }
+    println!("Queries: {}", client.queries);
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::io;
+    use PaddingError;
+    use PaddingOracle;
+
+    struct ScriptedOracle {
+        intermediate : Vec<u8>,
+    }
+
+    impl PaddingOracle for ScriptedOracle {
+        fn padding_valid(&mut self, iv : &[u8], _ : &[u8]) -> io::Result<bool> {
+            Ok(::validate(&::fixed_xor(iv, &self.intermediate), 16).is_ok())
+        }
+    }
+
+    #[test]
+    fn test_pkcs7_unpad() {
+        assert!(::pkcs7_unpad(b"ICE ICE BABY\x04\x04\x04\x04", 16) == Ok(String::from("ICE ICE BABY").into_bytes()));
+        assert!(::pkcs7_unpad(b"ICE ICE BABY\x05\x05\x05\x05", 16) == Err(PaddingError::InvalidPadding));
+        assert!(::pkcs7_unpad(b"ICE ICE BABY\x01\x02\x03\x04", 16) == Err(PaddingError::InvalidPadding));
+        assert!(::pkcs7_unpad(b"ICE ICE BABY\x00", 13) == Err(PaddingError::InvalidPadding));
+        assert!(::pkcs7_unpad(b"ICE ICE BABY", 16) == Err(PaddingError::InvalidLength));
+    }
+
+    #[test]
+    fn test_oracle() {
+        let mut oracle = ::CbcPaddingOracle::new();
+        let (iv, ciphertext) = oracle.encrypt(b"YELLOW SUBMARINE");
+        assert!(ciphertext.len() == 32);
+        assert!(oracle.padding_valid(&iv, &ciphertext).unwrap());
+        assert!(!oracle.padding_valid(&iv, &ciphertext[.. 31]).unwrap());
+        assert!(!oracle.padding_valid(&iv[.. 8], &ciphertext).unwrap());
+    }
+
+    #[test]
+    fn test_attack() {
+        let mut oracle = ::CountingOracle::new(::CbcPaddingOracle::new());
+        let plaintexts : Vec<Vec<u8>> = vec![
+            ::base64decode(b"MDAwMDAzQ29va2luZyBNQydzIGxpa2UgYSBwb3VuZCBvZiBiYWNvbg=="),
+            b"YELLOW SUBMARINE".to_vec(),
+            b"".to_vec(),
+        ];
+        for plaintext in plaintexts {
+            let (iv, ciphertext) = oracle.oracle.encrypt(&plaintext);
+            assert!(::padding_oracle_attack(&mut oracle, &iv, &ciphertext).unwrap() == plaintext);
+        }
+        assert!(oracle.queries > 0);
+        assert!(oracle.queries < 7 * 16 * 257);
+    }
+
+    #[test]
+    fn test_ambiguous_padding() {
+        let mut oracle = ::CbcPaddingOracle::new();
+        for length in 1 .. 16 {
+            let mut plaintext = vec![0x02u8; 16];
+            plaintext.extend(vec![0x41u8; length]);
+            plaintext[14] = 0x02u8;
+            plaintext[13] = 0x03u8;
+            let (iv, ciphertext) = oracle.encrypt(&plaintext);
+            assert!(::padding_oracle_attack(&mut oracle, &iv, &ciphertext).unwrap() == plaintext);
+        }
+
+        let mut intermediate : Vec<u8> = (0x30u8 .. 0x40u8).collect();
+        intermediate[14] = 0x02u8;
+        intermediate[15] = 0x47u8;
+        let mut oracle = ScriptedOracle { intermediate : intermediate.clone() };
+        let mut forged = vec![0u8; 16];
+        forged[15] = 0x45u8;
+        assert!(oracle.padding_valid(&forged, &[0u8; 16]).unwrap());
+        forged[15] = 0x46u8;
+        assert!(oracle.padding_valid(&forged, &[0u8; 16]).unwrap());
+        let first_valid = (0u16 .. 256u16).position(|guess| {
+            forged[15] = guess as u8;
+            oracle.padding_valid(&forged, &[0u8; 16]).unwrap()
+        });
+        assert!(first_valid == Some(0x45));
+        assert!(0x45u8 ^ 0x01u8 != intermediate[15]);
+
+        let previous : Vec<u8> = (0u8 .. 16u8).collect();
+        let recovered = ::decrypt_block(&mut oracle, &previous, &[0u8; 16]).unwrap();
+        assert!(recovered == ::fixed_xor(&intermediate, &previous));
+        assert!(recovered[15] == 0x47u8 ^ 0x0Fu8);
+    }
+
+    #[test]
+    fn test_attack_over_http() {
+        let oracle = ::CbcPaddingOracle::new();
+        let (iv, ciphertext) = oracle.encrypt(b"Cooking MC's like a pound of bacon");
+        let address = ::serve(oracle).unwrap();
+        let mut client = ::CountingOracle::new(::HttpPaddingOracle::connect(address).unwrap());
+        assert!(client.padding_valid(&iv, &ciphertext).unwrap());
+        assert!(::padding_oracle_attack(&mut client, &iv, &ciphertext).unwrap() == b"Cooking MC's like a pound of bacon".to_vec());
+        assert!(client.queries > 3 * 16);
+    }
+
+    #[test]
+    fn test_handle_request() {
+        let mut oracle = ::CbcPaddingOracle::new();
+        assert!(::handle_request(&mut oracle, "GET / HTTP/1.1") == "400 Bad Request");
+        assert!(::handle_request(&mut oracle, "GET /?iv=zz&ciphertext=00 HTTP/1.1") == "400 Bad Request");
+        assert!(::handle_request(&mut oracle, "GET /?iv=00&ciphertext=00 HTTP/1.1") == "500 Internal Server Error");
+        assert!(::query_parameter("a=1&b=2", "b") == Some("2"));
+        assert!(::query_parameter("a=1&b=2", "c") == None);
+    }
+}
@@ -0,0 +1,455 @@
+use std::io;
+use std::io::Read;
+use std::io::Seek;
+use std::io::SeekFrom;
+
+fn base64decode(input : &[u8]) -> Vec<u8> {
+    let mut octets : Vec<u8> = Vec::new();
+
+    let mut input_octets = input.iter().map(|c| match *c {
+        0x41u8 ... 0x5Au8 => c - 0x41u8,
+        0x61u8 ... 0x7Au8 => c - 0x47u8,
+        0x30u8 ... 0x39u8 => c + 0x04u8,
+        0x2Bu8 => 0x3Eu8,
+        0x2Fu8 => 0x3Fu8,
+        0x3Du8 => 0xFFu8,
+        i => panic!("{}", i),
+    }).collect::<Vec<u8>>();
+    input_octets.retain(|c| *c != 0xFFu8);
+    for chunk in input_octets.chunks(4) {
+        let groups = match chunk.len() {
+            2 => vec![((chunk[0] << 2) & 0xFCu8) + ((chunk[1] >> 4) & 0x03u8)],
+            3 => vec![((chunk[0] << 2) & 0xFCu8) + ((chunk[1] >> 4) & 0x03u8), ((chunk[1] << 4) & 0xF0u8) + ((chunk[2] >> 2) & 0x0F)],
+            4 => vec![((chunk[0] << 2) & 0xFCu8) + ((chunk[1] >> 4) & 0x03u8), ((chunk[1] << 4) & 0xF0u8) + ((chunk[2] >> 2) & 0x0F), ((chunk[2] << 6) & 0xC0u8) + (chunk[3] & 0x3Fu8)],
+            _ => panic!(),
+        };
+        octets.reserve(groups.len());
+        for group in groups {
+            octets.push(group)
+        }
+    }
+
+    octets
+}
+
+fn fixed_xor(left: &[u8], right: &[u8]) -> Vec<u8> {
+    assert!(left.len() == right.len());
+    let mut xor : Vec<u8> = Vec::with_capacity(left.len());
+    for i in 0 .. left.len() {
+        xor.push(left[i] ^ right[i])
+    }
+    xor
+}
+
+fn gf_multiply(left : u8, right : u8) -> u8 {
+    let mut a = left;
+    let mut b = right;
+    let mut product = 0u8;
+    while b != 0 {
+        if b & 1 == 1 {
+            product ^= a;
+        }
+        a = (a << 1) ^ match a & 0x80u8 {
+            0 => 0x00u8,
+            _ => 0x1Bu8,
+        };
+        b >>= 1;
+    }
+    product
+}
+
+fn gf_inverse(octet : u8) -> u8 {
+    if octet == 0 {
+        return 0;
+    }
+    let mut inverse = 1u8;
+    while gf_multiply(octet, inverse) != 1 {
+        inverse += 1;
+    }
+    inverse
+}
+
+fn sboxes() -> ([u8; 256], [u8; 256]) {
+    let mut sbox = [0u8; 256];
+    let mut inverse_sbox = [0u8; 256];
+    for i in 0 .. 256 {
+        let b = gf_inverse(i as u8);
+        let s = b ^ b.rotate_left(1) ^ b.rotate_left(2) ^ b.rotate_left(3) ^ b.rotate_left(4) ^ 0x63u8;
+        sbox[i] = s;
+        inverse_sbox[s as usize] = i as u8;
+    }
+    (sbox, inverse_sbox)
+}
+
+struct Aes {
+    sbox : [u8; 256],
+    inverse_sbox : [u8; 256],
+    round_keys : Vec<[u8; 16]>,
+}
+
+impl Aes {
+    fn new(key : &[u8]) -> Result<Aes, ()> {
+        let key_words = match key.len() {
+            16 | 24 | 32 => key.len() / 4,
+            _ => return Err(()),
+        };
+        let rounds = key_words + 6;
+        let (sbox, inverse_sbox) = sboxes();
+
+        let mut words : Vec<[u8; 4]> = Vec::with_capacity(4 * (rounds + 1));
+        for chunk in key.chunks(4) {
+            words.push([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        }
+        let mut round_constant = 0x01u8;
+        for i in key_words .. 4 * (rounds + 1) {
+            let mut word = words[i - 1];
+            if i % key_words == 0 {
+                word = [sbox[word[1] as usize] ^ round_constant, sbox[word[2] as usize], sbox[word[3] as usize], sbox[word[0] as usize]];
+                round_constant = gf_multiply(round_constant, 0x02u8);
+            } else if key_words > 6 && i % key_words == 4 {
+                word = [sbox[word[0] as usize], sbox[word[1] as usize], sbox[word[2] as usize], sbox[word[3] as usize]];
+            }
+            let previous = words[i - key_words];
+            words.push([previous[0] ^ word[0], previous[1] ^ word[1], previous[2] ^ word[2], previous[3] ^ word[3]]);
+        }
+
+        let mut round_keys : Vec<[u8; 16]> = Vec::with_capacity(rounds + 1);
+        for chunk in words.chunks(4) {
+            let mut round_key = [0u8; 16];
+            for (i, word) in chunk.iter().enumerate() {
+                round_key[4 * i .. 4 * i + 4].copy_from_slice(word);
+            }
+            round_keys.push(round_key);
+        }
+
+        Ok(Aes {
+            sbox : sbox,
+            inverse_sbox : inverse_sbox,
+            round_keys : round_keys,
+        })
+    }
+
+    fn rounds(&self) -> usize {
+        self.round_keys.len() - 1
+    }
+
+    fn add_round_key(state : &mut [u8; 16], round_key : &[u8; 16]) {
+        for (octet, key) in state.iter_mut().zip(round_key.iter()) {
+            *octet ^= *key;
+        }
+    }
+
+    fn substitute_bytes(state : &mut [u8; 16], sbox : &[u8; 256]) {
+        for octet in state.iter_mut() {
+            *octet = sbox[*octet as usize];
+        }
+    }
+
+    fn shift_rows(state : &mut [u8; 16]) {
+        let original = *state;
+        for row in 1 .. 4 {
+            for column in 0 .. 4 {
+                state[row + 4 * column] = original[row + 4 * ((column + row) % 4)];
+            }
+        }
+    }
+
+    fn inverse_shift_rows(state : &mut [u8; 16]) {
+        let original = *state;
+        for row in 1 .. 4 {
+            for column in 0 .. 4 {
+                state[row + 4 * ((column + row) % 4)] = original[row + 4 * column];
+            }
+        }
+    }
+
+    fn mix_columns(state : &mut [u8; 16], coefficients : &[u8; 4]) {
+        for column in state.chunks_mut(4) {
+            let original = [column[0], column[1], column[2], column[3]];
+            for row in 0 .. 4 {
+                column[row] = 0;
+                for i in 0 .. 4 {
+                    column[row] ^= gf_multiply(coefficients[(4 + i - row) % 4], original[i]);
+                }
+            }
+        }
+    }
+
+    fn encrypt_block(&self, block : &[u8]) -> Vec<u8> {
+        assert!(block.len() == 16);
+        let mut state = [0u8; 16];
+        state.copy_from_slice(block);
+
+        Aes::add_round_key(&mut state, &self.round_keys[0]);
+        for round in 1 .. self.rounds() + 1 {
+            Aes::substitute_bytes(&mut state, &self.sbox);
+            Aes::shift_rows(&mut state);
+            if round != self.rounds() {
+                Aes::mix_columns(&mut state, &[0x02u8, 0x03u8, 0x01u8, 0x01u8]);
+            }
+            Aes::add_round_key(&mut state, &self.round_keys[round]);
+        }
+
+        state.to_vec()
+    }
+
+    fn decrypt_block(&self, block : &[u8]) -> Vec<u8> {
+        assert!(block.len() == 16);
+        let mut state = [0u8; 16];
+        state.copy_from_slice(block);
+
+        for round in (1 .. self.rounds() + 1).rev() {
+            Aes::add_round_key(&mut state, &self.round_keys[round]);
+            if round != self.rounds() {
+                Aes::mix_columns(&mut state, &[0x0Eu8, 0x0Bu8, 0x0Du8, 0x09u8]);
+            }
+            Aes::inverse_shift_rows(&mut state);
+            Aes::substitute_bytes(&mut state, &self.inverse_sbox);
+        }
+        Aes::add_round_key(&mut state, &self.round_keys[0]);
+
+        state.to_vec()
+    }
+}
+
+trait BlockCipher {
+    fn block_size(&self) -> usize;
+    fn encrypt_block(&self, block : &[u8]) -> Vec<u8>;
+    fn decrypt_block(&self, block : &[u8]) -> Vec<u8>;
+}
+
+impl BlockCipher for Aes {
+    fn block_size(&self) -> usize {
+        16
+    }
+
+    fn encrypt_block(&self, block : &[u8]) -> Vec<u8> {
+        Aes::encrypt_block(self, block)
+    }
+
+    fn decrypt_block(&self, block : &[u8]) -> Vec<u8> {
+        Aes::decrypt_block(self, block)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum CounterLayout {
+    LittleEndian64,
+    BigEndian32,
+}
+
+impl CounterLayout {
+    fn counter_size(&self) -> usize {
+        match *self {
+            CounterLayout::LittleEndian64 => 8,
+            CounterLayout::BigEndian32 => 4,
+        }
+    }
+}
+
+struct Ctr<C : BlockCipher> {
+    cipher : C,
+    nonce : Vec<u8>,
+    layout : CounterLayout,
+    initial_counter : u64,
+}
+
+impl<C : BlockCipher> Ctr<C> {
+    fn new(cipher : C, nonce : &[u8], layout : CounterLayout, initial_counter : u64) -> Result<Ctr<C>, ()> {
+        if nonce.len() + layout.counter_size() != cipher.block_size() {
+            return Err(());
+        }
+        Ok(Ctr {
+            cipher : cipher,
+            nonce : nonce.to_vec(),
+            layout : layout,
+            initial_counter : initial_counter,
+        })
+    }
+
+    fn counter_block(&self, index : u64) -> Vec<u8> {
+        let counter = self.initial_counter.wrapping_add(index);
+        let mut block = self.nonce.clone();
+        match self.layout {
+            CounterLayout::LittleEndian64 => block.extend_from_slice(&counter.to_le_bytes()),
+            CounterLayout::BigEndian32 => block.extend_from_slice(&(counter as u32).to_be_bytes()),
+        }
+        block
+    }
+
+    fn keystream(&self, offset : u64, length : usize) -> Vec<u8> {
+        let block_size = self.cipher.block_size() as u64;
+        let skip = (offset % block_size) as usize;
+        let mut keystream : Vec<u8> = Vec::with_capacity(skip + length + block_size as usize);
+        let mut index = offset / block_size;
+        while keystream.len() < skip + length {
+            keystream.extend(self.cipher.encrypt_block(&self.counter_block(index)));
+            index += 1;
+        }
+        keystream.drain(.. skip);
+        keystream.truncate(length);
+        keystream
+    }
+
+    fn apply(&self, offset : u64, input : &[u8]) -> Vec<u8> {
+        fixed_xor(input, &self.keystream(offset, input.len()))
+    }
+
+    fn edit(&self, ciphertext : &mut Vec<u8>, offset : usize, newtext : &[u8]) {
+        if ciphertext.len() < offset {
+            let gap = self.apply(ciphertext.len() as u64, &vec![0u8; offset - ciphertext.len()]);
+            ciphertext.extend(gap);
+        }
+        if ciphertext.len() < offset + newtext.len() {
+            ciphertext.resize(offset + newtext.len(), 0u8);
+        }
+        let edited = self.apply(offset as u64, newtext);
+        ciphertext[offset .. offset + newtext.len()].copy_from_slice(&edited);
+    }
+}
+
+struct CtrReader<R : Read + Seek, C : BlockCipher> {
+    inner : R,
+    ctr : Ctr<C>,
+    position : u64,
+}
+
+impl<R : Read + Seek, C : BlockCipher> CtrReader<R, C> {
+    fn new(inner : R, ctr : Ctr<C>) -> CtrReader<R, C> {
+        CtrReader {
+            inner : inner,
+            ctr : ctr,
+            position : 0,
+        }
+    }
+}
+
+impl<R : Read + Seek, C : BlockCipher> Read for CtrReader<R, C> {
+    fn read(&mut self, buffer : &mut [u8]) -> io::Result<usize> {
+        let count = self.inner.read(buffer)?;
+        let plaintext = self.ctr.apply(self.position, &buffer[.. count]);
+        buffer[.. count].copy_from_slice(&plaintext);
+        self.position += count as u64;
+        Ok(count)
+    }
+}
+
+impl<R : Read + Seek, C : BlockCipher> Seek for CtrReader<R, C> {
+    fn seek(&mut self, position : SeekFrom) -> io::Result<u64> {
+        self.position = self.inner.seek(position)?;
+        Ok(self.position)
+    }
+}
+
+fn main() {
+    let ciphertext = base64decode(b"L77na/nrFsKvynd6HzOoG7GHTLXsTVu9qvY/2syLXzhPweyyMTJULu/6/kXX0KSvoOLSFQ==");
+    let aes = Aes::new(b"YELLOW SUBMARINE").unwrap();
+    let ctr = Ctr::new(aes, &[0u8; 8], CounterLayout::LittleEndian64, 0).unwrap();
+    println!("{}", String::from_utf8(ctr.apply(0, &ciphertext)).unwrap());
+
+    let mut reader = CtrReader::new(io::Cursor::new(ciphertext), ctr);
+    reader.seek(SeekFrom::Start(37)).unwrap();
+    let mut tail = String::new();
+    reader.read_to_string(&mut tail).unwrap();
+    println!("{}", tail);
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::io::Cursor;
+    use std::io::Read;
+    use std::io::Seek;
+    use std::io::SeekFrom;
+    use CounterLayout;
+
+    fn hex2nibble(hex : u8) -> Result<u8, ()> {
+        match hex {
+            0x30u8 ... 0x39u8 => Ok(hex - 0x30u8),
+            0x41u8 ... 0x46u8 | 0x61u8 ... 0x66u8 => Ok((hex & !0x20u8) - 0x37),
+            _ => Err(()),
+        }
+    }
+
+    fn hex2octets(hex: &[u8]) -> Vec<u8> {
+        let mut octets : Vec<u8> = Vec::with_capacity(&hex.len() / 2 + &hex.len() % 2);
+        if hex.len() % 2 == 1 {
+            octets.push(hex2nibble(hex[0]).unwrap())
+        }
+        for octet in hex[&hex.len() % 2 ..].chunks(2) {
+            octets.push((hex2nibble(octet[0]).unwrap() << 4) + hex2nibble(octet[1]).unwrap())
+        }
+        octets
+    }
+
+    fn challenge() -> ::Ctr<::Aes> {
+        ::Ctr::new(::Aes::new(b"YELLOW SUBMARINE").unwrap(), &[0u8; 8], CounterLayout::LittleEndian64, 0).unwrap()
+    }
+
+    fn ciphertext() -> Vec<u8> {
+        ::base64decode(b"L77na/nrFsKvynd6HzOoG7GHTLXsTVu9qvY/2syLXzhPweyyMTJULu/6/kXX0KSvoOLSFQ==")
+    }
+
+    #[test]
+    fn test_counter_layout() {
+        let aes = || ::Aes::new(b"YELLOW SUBMARINE").unwrap();
+        assert!(::Ctr::new(aes(), &[0u8; 12], CounterLayout::LittleEndian64, 0).is_err());
+        assert!(::Ctr::new(aes(), &[0u8; 8], CounterLayout::BigEndian32, 0).is_err());
+        let ctr = ::Ctr::new(aes(), &[0xAAu8; 8], CounterLayout::LittleEndian64, 0).unwrap();
+        assert!(ctr.counter_block(0x0102) == vec![0xAAu8, 0xAA, 0xAA, 0xAA, 0xAA, 0xAA, 0xAA, 0xAA, 0x02, 0x01, 0, 0, 0, 0, 0, 0]);
+        let ctr = ::Ctr::new(aes(), &[0xBBu8; 12], CounterLayout::BigEndian32, 0xFFFFFFFF).unwrap();
+        assert!(ctr.counter_block(0) == vec![0xBBu8, 0xBB, 0xBB, 0xBB, 0xBB, 0xBB, 0xBB, 0xBB, 0xBB, 0xBB, 0xBB, 0xBB, 0xFF, 0xFF, 0xFF, 0xFF]);
+        assert!(ctr.counter_block(1)[12 ..] == [0u8, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_challenge() {
+        assert!(challenge().apply(0, &ciphertext()) == b"Yo, VIP Let's kick it Ice, Ice, baby Ice, Ice, baby ".to_vec());
+    }
+
+    #[test]
+    fn test_gcm_layout() {
+        let ctr = ::Ctr::new(::Aes::new(&[0u8; 16]).unwrap(), &[0u8; 12], CounterLayout::BigEndian32, 2).unwrap();
+        assert!(ctr.apply(0, &[0u8; 16]) == hex2octets(b"0388dace60b6a392f328c2b971b2fe78"));
+    }
+
+    #[test]
+    fn test_random_access() {
+        let ctr = challenge();
+        let ciphertext = ciphertext();
+        let plaintext = ctr.apply(0, &ciphertext);
+        for start in vec![0usize, 1, 15, 16, 17, 40] {
+            for end in start .. ciphertext.len() + 1 {
+                assert!(ctr.apply(start as u64, &ciphertext[start .. end]) == plaintext[start .. end].to_vec());
+            }
+        }
+    }
+
+    #[test]
+    fn test_edit() {
+        let ctr = challenge();
+        let mut ciphertext = ciphertext();
+        ctr.edit(&mut ciphertext, 4, b"MC!");
+        assert!(ctr.apply(0, &ciphertext) == b"Yo, MC! Let's kick it Ice, Ice, baby Ice, Ice, baby ".to_vec());
+        ctr.edit(&mut ciphertext, 53, b"Yo!");
+        assert!(ctr.apply(0, &ciphertext)[50 ..] == b"y \0Yo!"[..]);
+    }
+
+    #[test]
+    fn test_reader() {
+        let mut reader = ::CtrReader::new(Cursor::new(ciphertext()), challenge());
+        let mut buffer = [0u8; 5];
+        reader.read_exact(&mut buffer).unwrap();
+        assert!(&buffer == b"Yo, V");
+        assert!(reader.seek(SeekFrom::Start(22)).unwrap() == 22);
+        reader.read_exact(&mut buffer).unwrap();
+        assert!(&buffer == b"Ice, ");
+        assert!(reader.seek(SeekFrom::Current(-10)).unwrap() == 17);
+        reader.read_exact(&mut buffer).unwrap();
+        assert!(&buffer == b"k it ");
+        reader.seek(SeekFrom::End(-5)).unwrap();
+        let mut tail = Vec::new();
+        reader.read_to_end(&mut tail).unwrap();
+        assert!(tail == b"baby ".to_vec());
+    }
+}
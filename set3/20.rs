@@ -0,0 +1,626 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::io::Read;
+
+fn base64decode(input : &[u8]) -> Vec<u8> {
+    let mut octets : Vec<u8> = Vec::new();
+
+    let mut input_octets = input.iter().map(|c| match *c {
+        0x41u8 ... 0x5Au8 => c - 0x41u8,
+        0x61u8 ... 0x7Au8 => c - 0x47u8,
+        0x30u8 ... 0x39u8 => c + 0x04u8,
+        0x2Bu8 => 0x3Eu8,
+        0x2Fu8 => 0x3Fu8,
+        0x3Du8 => 0xFFu8,
+        i => panic!("{}", i),
+    }).collect::<Vec<u8>>();
+    input_octets.retain(|c| *c != 0xFFu8);
+    for chunk in input_octets.chunks(4) {
+        let groups = match chunk.len() {
+            2 => vec![((chunk[0] << 2) & 0xFCu8) + ((chunk[1] >> 4) & 0x03u8)],
+            3 => vec![((chunk[0] << 2) & 0xFCu8) + ((chunk[1] >> 4) & 0x03u8), ((chunk[1] << 4) & 0xF0u8) + ((chunk[2] >> 2) & 0x0F)],
+            4 => vec![((chunk[0] << 2) & 0xFCu8) + ((chunk[1] >> 4) & 0x03u8), ((chunk[1] << 4) & 0xF0u8) + ((chunk[2] >> 2) & 0x0F), ((chunk[2] << 6) & 0xC0u8) + (chunk[3] & 0x3Fu8)],
+            _ => panic!(),
+        };
+        octets.reserve(groups.len());
+        for group in groups {
+            octets.push(group)
+        }
+    }
+
+    octets
+}
+
+fn random_octets(count : usize) -> Vec<u8> {
+    let mut f = File::open("/dev/urandom").unwrap();
+    let mut octets = vec![0u8; count];
+    match f.read_exact(&mut octets) {
+        Ok(_) => (),
+        Err(_) => panic!(),
+    };
+    octets
+}
+
+fn fixed_xor(input: &[u8], key: u8) -> Vec<u8> {
+    let mut xor : Vec<u8> = Vec::with_capacity(input.len());
+    for octet in input {
+        xor.push(octet ^ key)
+    }
+    xor
+}
+
+fn score(output : &[u8]) -> f32 {
+    let letter_frequencies = vec![0.08167f32, 0.01492f32, 0.02782f32, 0.04253f32, 0.12702f32, 0.02228f32, 0.02015f32, 0.06094f32, 0.06966f32, 0.00153f32, 0.00772f32, 0.04025f32, 0.02406f32, 0.06749f32, 0.07507f32, 0.01929f32, 0.00095f32, 0.05987f32, 0.06327f32, 0.09056f32, 0.02758f32, 0.00978f32, 0.02361f32, 0.00150f32, 0.01974f32, 0.00074f32];
+    assert!(letter_frequencies.len() == 26);
+
+    let mut letter_count : Vec<f32> = Vec::with_capacity(letter_frequencies.len());
+    for _ in 0 .. letter_frequencies.len() {
+        letter_count.push(0f32);
+    }
+    let mut nonletter_count = 0f32;
+    let mut cumulative_delta = 0f32;
+    for octet in output {
+        match *octet {
+            0x41u8 ... 0x5Au8 | 0x61u8 ... 0x7Au8 => { letter_count[((octet | 0x20u8) - 0x61u8) as usize] += 1f32; },
+            0x20 => (),
+            0x80u8 ... 0xFFu8 => { cumulative_delta = std::f32::INFINITY; }
+            _ => { nonletter_count += 1f32; },
+        }
+    }
+
+    for i in 0 .. letter_frequencies.len() {
+        cumulative_delta += (letter_frequencies[i] - (letter_count[i] / output.len() as f32)).abs();
+    }
+    let ratio = (output.len() as f32 - nonletter_count) / output.len() as f32;
+    cumulative_delta / (ratio * ratio)
+}
+
+fn decrypt_column<F>(input : &[u8], decrypt : F) -> (u8, f32) where F : Fn(&[u8], u8) -> Vec<u8> {
+
+    let mut best_key = 0u8;
+    let mut best_score = std::f32::INFINITY;
+
+    for candidate in 0u16 .. 256u16 {
+        let key = candidate as u8;
+        let score = score(&decrypt(input, key));
+        if score < best_score {
+            best_key = key;
+            best_score = score;
+        }
+    }
+
+    (best_key, best_score)
+}
+
+fn decrypt_fixed_xor(input : &[u8]) -> (u8, f32) {
+    decrypt_column(input, fixed_xor)
+}
+
+fn column_len(len : usize, columns : usize, column : usize) -> usize {
+    len / columns + match column < len % columns {
+        true => 1,
+        false => 0,
+    }
+}
+
+fn transpose(input : &[u8], columns : usize) -> Vec<Vec<u8>> {
+    assert!(columns > 0);
+    let mut data : Vec<Vec<u8>> = Vec::with_capacity(columns);
+    for i in 0 .. columns {
+        data.push(Vec::with_capacity(column_len(input.len(), columns, i)));
+    }
+    for chunk in input.chunks(columns) {
+        for (datum, vec) in chunk.iter().zip(data.iter_mut()) {
+            vec.push(*datum);
+        }
+    }
+    data
+}
+
+fn rotating_xor(input: &[u8], key: &[u8]) -> Vec<u8> {
+    let mut xor : Vec<u8> = Vec::with_capacity(input.len());
+    for (octet, byte) in input.iter().zip(key.iter().cycle()) {
+        xor.push(octet ^ byte)
+    }
+    xor
+}
+
+fn gf_multiply(left : u8, right : u8) -> u8 {
+    let mut a = left;
+    let mut b = right;
+    let mut product = 0u8;
+    while b != 0 {
+        if b & 1 == 1 {
+            product ^= a;
+        }
+        a = (a << 1) ^ match a & 0x80u8 {
+            0 => 0x00u8,
+            _ => 0x1Bu8,
+        };
+        b >>= 1;
+    }
+    product
+}
+
+fn gf_inverse(octet : u8) -> u8 {
+    if octet == 0 {
+        return 0;
+    }
+    let mut inverse = 1u8;
+    while gf_multiply(octet, inverse) != 1 {
+        inverse += 1;
+    }
+    inverse
+}
+
+fn sboxes() -> ([u8; 256], [u8; 256]) {
+    let mut sbox = [0u8; 256];
+    let mut inverse_sbox = [0u8; 256];
+    for i in 0 .. 256 {
+        let b = gf_inverse(i as u8);
+        let s = b ^ b.rotate_left(1) ^ b.rotate_left(2) ^ b.rotate_left(3) ^ b.rotate_left(4) ^ 0x63u8;
+        sbox[i] = s;
+        inverse_sbox[s as usize] = i as u8;
+    }
+    (sbox, inverse_sbox)
+}
+
+struct Aes {
+    sbox : [u8; 256],
+    inverse_sbox : [u8; 256],
+    round_keys : Vec<[u8; 16]>,
+}
+
+impl Aes {
+    fn new(key : &[u8]) -> Result<Aes, ()> {
+        let key_words = match key.len() {
+            16 | 24 | 32 => key.len() / 4,
+            _ => return Err(()),
+        };
+        let rounds = key_words + 6;
+        let (sbox, inverse_sbox) = sboxes();
+
+        let mut words : Vec<[u8; 4]> = Vec::with_capacity(4 * (rounds + 1));
+        for chunk in key.chunks(4) {
+            words.push([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        }
+        let mut round_constant = 0x01u8;
+        for i in key_words .. 4 * (rounds + 1) {
+            let mut word = words[i - 1];
+            if i % key_words == 0 {
+                word = [sbox[word[1] as usize] ^ round_constant, sbox[word[2] as usize], sbox[word[3] as usize], sbox[word[0] as usize]];
+                round_constant = gf_multiply(round_constant, 0x02u8);
+            } else if key_words > 6 && i % key_words == 4 {
+                word = [sbox[word[0] as usize], sbox[word[1] as usize], sbox[word[2] as usize], sbox[word[3] as usize]];
+            }
+            let previous = words[i - key_words];
+            words.push([previous[0] ^ word[0], previous[1] ^ word[1], previous[2] ^ word[2], previous[3] ^ word[3]]);
+        }
+
+        let mut round_keys : Vec<[u8; 16]> = Vec::with_capacity(rounds + 1);
+        for chunk in words.chunks(4) {
+            let mut round_key = [0u8; 16];
+            for (i, word) in chunk.iter().enumerate() {
+                round_key[4 * i .. 4 * i + 4].copy_from_slice(word);
+            }
+            round_keys.push(round_key);
+        }
+
+        Ok(Aes {
+            sbox : sbox,
+            inverse_sbox : inverse_sbox,
+            round_keys : round_keys,
+        })
+    }
+
+    fn rounds(&self) -> usize {
+        self.round_keys.len() - 1
+    }
+
+    fn add_round_key(state : &mut [u8; 16], round_key : &[u8; 16]) {
+        for (octet, key) in state.iter_mut().zip(round_key.iter()) {
+            *octet ^= *key;
+        }
+    }
+
+    fn substitute_bytes(state : &mut [u8; 16], sbox : &[u8; 256]) {
+        for octet in state.iter_mut() {
+            *octet = sbox[*octet as usize];
+        }
+    }
+
+    fn shift_rows(state : &mut [u8; 16]) {
+        let original = *state;
+        for row in 1 .. 4 {
+            for column in 0 .. 4 {
+                state[row + 4 * column] = original[row + 4 * ((column + row) % 4)];
+            }
+        }
+    }
+
+    fn inverse_shift_rows(state : &mut [u8; 16]) {
+        let original = *state;
+        for row in 1 .. 4 {
+            for column in 0 .. 4 {
+                state[row + 4 * ((column + row) % 4)] = original[row + 4 * column];
+            }
+        }
+    }
+
+    fn mix_columns(state : &mut [u8; 16], coefficients : &[u8; 4]) {
+        for column in state.chunks_mut(4) {
+            let original = [column[0], column[1], column[2], column[3]];
+            for row in 0 .. 4 {
+                column[row] = 0;
+                for i in 0 .. 4 {
+                    column[row] ^= gf_multiply(coefficients[(4 + i - row) % 4], original[i]);
+                }
+            }
+        }
+    }
+
+    fn encrypt_block(&self, block : &[u8]) -> Vec<u8> {
+        assert!(block.len() == 16);
+        let mut state = [0u8; 16];
+        state.copy_from_slice(block);
+
+        Aes::add_round_key(&mut state, &self.round_keys[0]);
+        for round in 1 .. self.rounds() + 1 {
+            Aes::substitute_bytes(&mut state, &self.sbox);
+            Aes::shift_rows(&mut state);
+            if round != self.rounds() {
+                Aes::mix_columns(&mut state, &[0x02u8, 0x03u8, 0x01u8, 0x01u8]);
+            }
+            Aes::add_round_key(&mut state, &self.round_keys[round]);
+        }
+
+        state.to_vec()
+    }
+
+    fn decrypt_block(&self, block : &[u8]) -> Vec<u8> {
+        assert!(block.len() == 16);
+        let mut state = [0u8; 16];
+        state.copy_from_slice(block);
+
+        for round in (1 .. self.rounds() + 1).rev() {
+            Aes::add_round_key(&mut state, &self.round_keys[round]);
+            if round != self.rounds() {
+                Aes::mix_columns(&mut state, &[0x0Eu8, 0x0Bu8, 0x0Du8, 0x09u8]);
+            }
+            Aes::inverse_shift_rows(&mut state);
+            Aes::substitute_bytes(&mut state, &self.inverse_sbox);
+        }
+        Aes::add_round_key(&mut state, &self.round_keys[0]);
+
+        state.to_vec()
+    }
+}
+
+trait BlockCipher {
+    fn block_size(&self) -> usize;
+    fn encrypt_block(&self, block : &[u8]) -> Vec<u8>;
+    fn decrypt_block(&self, block : &[u8]) -> Vec<u8>;
+}
+
+impl BlockCipher for Aes {
+    fn block_size(&self) -> usize {
+        16
+    }
+
+    fn encrypt_block(&self, block : &[u8]) -> Vec<u8> {
+        Aes::encrypt_block(self, block)
+    }
+
+    fn decrypt_block(&self, block : &[u8]) -> Vec<u8> {
+        Aes::decrypt_block(self, block)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum CounterLayout {
+    LittleEndian64,
+    BigEndian32,
+}
+
+impl CounterLayout {
+    fn counter_size(&self) -> usize {
+        match *self {
+            CounterLayout::LittleEndian64 => 8,
+            CounterLayout::BigEndian32 => 4,
+        }
+    }
+}
+
+struct Ctr<C : BlockCipher> {
+    cipher : C,
+    nonce : Vec<u8>,
+    layout : CounterLayout,
+    initial_counter : u64,
+}
+
+impl<C : BlockCipher> Ctr<C> {
+    fn new(cipher : C, nonce : &[u8], layout : CounterLayout, initial_counter : u64) -> Result<Ctr<C>, ()> {
+        if nonce.len() + layout.counter_size() != cipher.block_size() {
+            return Err(());
+        }
+        Ok(Ctr {
+            cipher : cipher,
+            nonce : nonce.to_vec(),
+            layout : layout,
+            initial_counter : initial_counter,
+        })
+    }
+
+    fn counter_block(&self, index : u64) -> Vec<u8> {
+        let counter = self.initial_counter.wrapping_add(index);
+        let mut block = self.nonce.clone();
+        match self.layout {
+            CounterLayout::LittleEndian64 => block.extend_from_slice(&counter.to_le_bytes()),
+            CounterLayout::BigEndian32 => block.extend_from_slice(&(counter as u32).to_be_bytes()),
+        }
+        block
+    }
+
+    fn keystream(&self, offset : u64, length : usize) -> Vec<u8> {
+        let block_size = self.cipher.block_size() as u64;
+        let skip = (offset % block_size) as usize;
+        let mut keystream : Vec<u8> = Vec::with_capacity(skip + length + block_size as usize);
+        let mut index = offset / block_size;
+        while keystream.len() < skip + length {
+            keystream.extend(self.cipher.encrypt_block(&self.counter_block(index)));
+            index += 1;
+        }
+        keystream.drain(.. skip);
+        keystream.truncate(length);
+        keystream
+    }
+}
+
+struct Ngrams {
+    n : usize,
+    log_probabilities : HashMap<Vec<u8>, f32>,
+    floor : f32,
+}
+
+impl Ngrams {
+    fn train(corpus : &[u8], n : usize) -> Result<Ngrams, ()> {
+        if n == 0 {
+            return Err(());
+        }
+        let octets = corpus.to_ascii_lowercase();
+        let mut counts : HashMap<Vec<u8>, u32> = HashMap::new();
+        for window in octets.windows(n) {
+            *counts.entry(window.to_vec()).or_insert(0) += 1;
+        }
+        let total = octets.len().saturating_sub(n - 1).max(1) as f32;
+        let log_probabilities = counts.into_iter().map(|(ngram, count)| (ngram, (count as f32 / total).log10())).collect();
+        Ok(Ngrams {
+            n : n,
+            log_probabilities : log_probabilities,
+            floor : (0.01f32 / total).log10(),
+        })
+    }
+
+    fn score(&self, octets : &[u8]) -> f32 {
+        let octets = octets.to_ascii_lowercase();
+        let mut score = 0f32;
+        for window in octets.windows(self.n) {
+            score += *self.log_probabilities.get(window).unwrap_or(&self.floor);
+        }
+        score
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Alignment {
+    Truncate,
+    Align,
+}
+
+fn fixed_nonce_columns(ciphertexts : &[Vec<u8>], alignment : Alignment) -> Vec<Vec<u8>> {
+    match alignment {
+        Alignment::Truncate => {
+            let length = ciphertexts.iter().map(|ciphertext| ciphertext.len()).min().unwrap_or(0);
+            if length == 0 {
+                return Vec::new();
+            }
+            let mut joined : Vec<u8> = Vec::with_capacity(length * ciphertexts.len());
+            for ciphertext in ciphertexts {
+                joined.extend_from_slice(&ciphertext[.. length]);
+            }
+            transpose(&joined, length)
+        },
+        Alignment::Align => {
+            let length = ciphertexts.iter().map(|ciphertext| ciphertext.len()).max().unwrap_or(0);
+            let mut columns : Vec<Vec<u8>> = vec![Vec::new(); length];
+            for ciphertext in ciphertexts {
+                for (column, octet) in columns.iter_mut().zip(ciphertext.iter()) {
+                    column.push(*octet);
+                }
+            }
+            columns
+        },
+    }
+}
+
+fn break_fixed_nonce(ciphertexts : &[Vec<u8>], alignment : Alignment) -> Vec<u8> {
+    fixed_nonce_columns(ciphertexts, alignment).iter().map(|column| decrypt_fixed_xor(column).0).collect()
+}
+
+fn printable(octet : u8) -> bool {
+    octet >= 0x20u8 && octet < 0x7Fu8
+}
+
+fn extend_keystream(ciphertexts : &[Vec<u8>], keystream : &[u8], models : &[Ngrams]) -> Vec<u8> {
+    let length = ciphertexts.iter().map(|ciphertext| ciphertext.len()).max().unwrap_or(0);
+    let longest = models.iter().map(|model| model.n).max().unwrap_or(1);
+    let mut extended = keystream.to_vec();
+    while extended.len() < length {
+        let position = extended.len();
+        let context = std::cmp::min(position, longest - 1);
+        let mut best_key = 0u8;
+        let mut best_score = std::f32::NEG_INFINITY;
+        for key in 0u16 .. 256u16 {
+            let mut score = 0f32;
+            for ciphertext in ciphertexts.iter().filter(|ciphertext| ciphertext.len() > position) {
+                let mut window = rotating_xor(&ciphertext[position - context .. position], &extended[position - context ..]);
+                window.push(ciphertext[position] ^ key as u8);
+                if !printable(window[context]) {
+                    score = std::f32::NEG_INFINITY;
+                    break;
+                }
+                for model in models.iter().filter(|model| model.n <= window.len()) {
+                    score += model.score(&window[window.len() - model.n ..]);
+                }
+            }
+            if score > best_score {
+                best_key = key as u8;
+                best_score = score;
+            }
+        }
+        extended.push(best_key);
+    }
+    extended
+}
+
+fn break_fixed_nonce_with_ngrams(ciphertexts : &[Vec<u8>]) -> Vec<u8> {
+    let keystream = break_fixed_nonce(ciphertexts, Alignment::Truncate);
+    let mut corpus : Vec<u8> = Vec::new();
+    for ciphertext in ciphertexts {
+        corpus.extend(rotating_xor(&ciphertext[.. keystream.len()], &keystream));
+        corpus.push(0x20u8);
+    }
+    let models : Vec<Ngrams> = (1 .. 4).map(|n| Ngrams::train(&corpus, n).unwrap()).collect();
+    extend_keystream(ciphertexts, &keystream, &models)
+}
+
+fn main() {
+    let f = File::open("20.txt").unwrap();
+    let b = BufReader::new(&f);
+    let ctr = Ctr::new(Aes::new(&random_octets(16)).unwrap(), &[0u8; 8], CounterLayout::LittleEndian64, 0).unwrap();
+    let mut ciphertexts : Vec<Vec<u8>> = Vec::new();
+    for line in b.lines() {
+        let plaintext = base64decode(&line.unwrap().into_bytes());
+        ciphertexts.push(rotating_xor(&plaintext, &ctr.keystream(0, plaintext.len())));
+    }
+
+    let keystream = break_fixed_nonce(&ciphertexts, Alignment::Truncate);
+    for ciphertext in &ciphertexts {
+        println!("{}", String::from_utf8_lossy(&rotating_xor(&ciphertext[.. keystream.len()], &keystream)));
+    }
+    println!();
+
+    let keystream = break_fixed_nonce_with_ngrams(&ciphertexts);
+    for ciphertext in &ciphertexts {
+        println!("{}", String::from_utf8_lossy(&rotating_xor(ciphertext, &keystream)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use Alignment;
+    use CounterLayout;
+
+    fn plaintexts() -> Vec<Vec<u8>> {
+        vec![
+            "I'm back and I'm ringin' the bell",
+            "A rockin' on the mike while the fly girls yell",
+            "In ecstasy in the back of me",
+            "Well that's my DJ Deshay cuttin' all them Z's",
+            "Hittin' hard and the girlies goin' crazy",
+            "Vanilla's on the mike, man I'm not lazy.",
+            "I'm lettin' my drug kick in",
+            "It controls my mouth and I begin",
+            "To just let it flow, let my concepts go",
+            "My posse's to the side yellin', Go Vanilla Go!",
+            "Smooth 'cause that's the way I will be",
+            "And if you don't give a damn, then",
+            "Why you starin' at me",
+            "So get off 'cause I control the stage",
+            "There's no dissin' allowed",
+            "I'm in my own phase",
+            "The girlies sa y they love me and that is ok",
+            "And I can dance better than any kid n' play",
+            "Stage 2 -- Yea the one ya' wanna listen to",
+            "It's off my head so let the beat play through",
+            "So I can funk it up and make it sound good",
+            "1-2-3 Yo -- Knock on some wood",
+            "For good luck, I like my rhymes atrocious",
+            "Supercalafragilisticexpialidocious",
+            "I'm an effect and that you can bet",
+            "I can take a fly girl and make her wet.",
+            "I'm like Samson -- Samson to Delilah",
+            "There's no denyin', You can try to hang",
+            "But you'll keep tryin' to get my style",
+            "Over and over, practice makes perfect",
+            "But not if you're a loafer.",
+            "You'll get nowhere, no place, no time, no girls",
+            "Soon -- Oh my God, homebody, you probably eat",
+            "Spaghetti with a spoon! Come on and say it!",
+            "VIP. Vanilla Ice yep, yep, I'm comin' hard like a rhino",
+            "Intoxicating so you stagger like a wino",
+            "So punks stop trying and girl stop cryin'",
+            "Vanilla Ice is sellin' and you people are buyin'",
+            "'Cause why the freaks are jockin' like Crazy Glue",
+            "Movin' and groovin' trying to sing along",
+        ].into_iter().map(|line| line.as_bytes().to_vec()).collect()
+    }
+
+    fn encrypt(plaintexts : &[Vec<u8>]) -> Vec<Vec<u8>> {
+        let ctr = ::Ctr::new(::Aes::new(b"YELLOW SUBMARINE").unwrap(), &[0u8; 8], CounterLayout::LittleEndian64, 0).unwrap();
+        plaintexts.iter().map(|plaintext| ::rotating_xor(plaintext, &ctr.keystream(0, plaintext.len()))).collect()
+    }
+
+    fn accuracy(plaintexts : &[Vec<u8>], ciphertexts : &[Vec<u8>], keystream : &[u8]) -> f32 {
+        tail_accuracy(plaintexts, ciphertexts, keystream, 0)
+    }
+
+    fn tail_accuracy(plaintexts : &[Vec<u8>], ciphertexts : &[Vec<u8>], keystream : &[u8], skip : usize) -> f32 {
+        let mut correct = 0usize;
+        let mut total = 0usize;
+        for (plaintext, ciphertext) in plaintexts.iter().zip(ciphertexts.iter()) {
+            let decrypted = ::rotating_xor(ciphertext, keystream);
+            correct += decrypted.iter().zip(plaintext.iter()).skip(skip).filter(|&(a, b)| a == b).count();
+            total += std::cmp::min(plaintext.len(), keystream.len()).saturating_sub(skip);
+        }
+        correct as f32 / total as f32
+    }
+
+    #[test]
+    fn test_columns() {
+        let ciphertexts = vec![b"abcd".to_vec(), b"efg".to_vec(), b"hijkl".to_vec()];
+        assert!(::fixed_nonce_columns(&ciphertexts, Alignment::Truncate) == vec![b"aeh".to_vec(), b"bfi".to_vec(), b"cgj".to_vec()]);
+        assert!(::fixed_nonce_columns(&ciphertexts, Alignment::Align) == vec![b"aeh".to_vec(), b"bfi".to_vec(), b"cgj".to_vec(), b"dk".to_vec(), b"l".to_vec()]);
+        assert!(::fixed_nonce_columns(&[], Alignment::Truncate).is_empty());
+    }
+
+    #[test]
+    fn test_truncate() {
+        let plaintexts = plaintexts();
+        let ciphertexts = encrypt(&plaintexts);
+        let keystream = ::break_fixed_nonce(&ciphertexts, Alignment::Truncate);
+        assert!(keystream.len() == plaintexts.iter().map(|plaintext| plaintext.len()).min().unwrap());
+        assert!(accuracy(&plaintexts, &ciphertexts, &keystream) > 0.9f32);
+    }
+
+    #[test]
+    fn test_ngrams() {
+        let plaintexts = plaintexts();
+        let ciphertexts = encrypt(&plaintexts);
+        let aligned = ::break_fixed_nonce(&ciphertexts, Alignment::Align);
+        let extended = ::break_fixed_nonce_with_ngrams(&ciphertexts);
+        assert!(extended.len() == aligned.len());
+        assert!(accuracy(&plaintexts, &ciphertexts, &extended) > 0.9f32);
+        assert!(accuracy(&plaintexts, &ciphertexts, &extended) >= accuracy(&plaintexts, &ciphertexts, &aligned));
+
+        let shortest = plaintexts.iter().map(|plaintext| plaintext.len()).min().unwrap();
+        assert!(extended[.. shortest] == aligned[.. shortest]);
+        let extended_tail = tail_accuracy(&plaintexts, &ciphertexts, &extended, shortest);
+        let aligned_tail = tail_accuracy(&plaintexts, &ciphertexts, &aligned, shortest);
+        assert!(extended_tail > aligned_tail);
+        assert!(::Ngrams::train(b"abc", 0).is_err());
+    }
+}
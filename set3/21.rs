@@ -0,0 +1,300 @@
+const N : usize = 624;
+const M : usize = 397;
+const MATRIX_A : u32 = 0x9908B0DFu32;
+const UPPER_MASK : u32 = 0x80000000u32;
+const LOWER_MASK : u32 = 0x7FFFFFFFu32;
+
+const NN : usize = 312;
+const MM : usize = 156;
+const MATRIX_A_64 : u64 = 0xB5026F5AA96619E9u64;
+const UPPER_MASK_64 : u64 = 0xFFFFFFFF80000000u64;
+const LOWER_MASK_64 : u64 = 0x000000007FFFFFFFu64;
+
+fn temper(word : u32) -> u32 {
+    let mut y = word;
+    y ^= y >> 11;
+    y ^= (y << 7) & 0x9D2C5680u32;
+    y ^= (y << 15) & 0xEFC60000u32;
+    y ^= y >> 18;
+    y
+}
+
+fn temper_64(word : u64) -> u64 {
+    let mut y = word;
+    y ^= (y >> 29) & 0x5555555555555555u64;
+    y ^= (y << 17) & 0x71D67FFFEDA60000u64;
+    y ^= (y << 37) & 0xFFF7EEE000000000u64;
+    y ^= y >> 43;
+    y
+}
+
+#[derive(Clone, PartialEq, Debug)]
+struct Mt19937State {
+    state : Vec<u32>,
+    index : usize,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+struct Mt19937 {
+    state : Vec<u32>,
+    index : usize,
+}
+
+impl Mt19937 {
+    fn new(seed : u32) -> Mt19937 {
+        let mut state : Vec<u32> = Vec::with_capacity(N);
+        state.push(seed);
+        for i in 1 .. N {
+            let previous = state[i - 1];
+            state.push(1812433253u32.wrapping_mul(previous ^ (previous >> 30)).wrapping_add(i as u32));
+        }
+        Mt19937 {
+            state : state,
+            index : N,
+        }
+    }
+
+    fn from_array(key : &[u32]) -> Result<Mt19937, ()> {
+        if key.is_empty() {
+            return Err(());
+        }
+        let mut mt = Mt19937::new(19650218u32);
+        let mut i = 1usize;
+        let mut j = 0usize;
+        for _ in 0 .. std::cmp::max(N, key.len()) {
+            let previous = mt.state[i - 1];
+            mt.state[i] = (mt.state[i] ^ (previous ^ (previous >> 30)).wrapping_mul(1664525u32)).wrapping_add(key[j]).wrapping_add(j as u32);
+            i += 1;
+            j += 1;
+            if i >= N {
+                mt.state[0] = mt.state[N - 1];
+                i = 1;
+            }
+            if j >= key.len() {
+                j = 0;
+            }
+        }
+        for _ in 0 .. N - 1 {
+            let previous = mt.state[i - 1];
+            mt.state[i] = (mt.state[i] ^ (previous ^ (previous >> 30)).wrapping_mul(1566083941u32)).wrapping_sub(i as u32);
+            i += 1;
+            if i >= N {
+                mt.state[0] = mt.state[N - 1];
+                i = 1;
+            }
+        }
+        mt.state[0] = 0x80000000u32;
+        Ok(mt)
+    }
+
+    fn snapshot(&self) -> Mt19937State {
+        Mt19937State {
+            state : self.state.clone(),
+            index : self.index,
+        }
+    }
+
+    fn restore(snapshot : &Mt19937State) -> Result<Mt19937, ()> {
+        if snapshot.state.len() != N || snapshot.index > N {
+            return Err(());
+        }
+        Ok(Mt19937 {
+            state : snapshot.state.clone(),
+            index : snapshot.index,
+        })
+    }
+
+    fn twist(&mut self) {
+        for i in 0 .. N {
+            let y = (self.state[i] & UPPER_MASK) | (self.state[(i + 1) % N] & LOWER_MASK);
+            self.state[i] = self.state[(i + M) % N] ^ (y >> 1) ^ match y & 1 {
+                0 => 0u32,
+                _ => MATRIX_A,
+            };
+        }
+        self.index = 0;
+    }
+
+    fn next_u32(&mut self) -> u32 {
+        if self.index >= N {
+            self.twist();
+        }
+        let y = self.state[self.index];
+        self.index += 1;
+        temper(y)
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+struct Mt19937_64State {
+    state : Vec<u64>,
+    index : usize,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+struct Mt19937_64 {
+    state : Vec<u64>,
+    index : usize,
+}
+
+impl Mt19937_64 {
+    fn new(seed : u64) -> Mt19937_64 {
+        let mut state : Vec<u64> = Vec::with_capacity(NN);
+        state.push(seed);
+        for i in 1 .. NN {
+            let previous = state[i - 1];
+            state.push(6364136223846793005u64.wrapping_mul(previous ^ (previous >> 62)).wrapping_add(i as u64));
+        }
+        Mt19937_64 {
+            state : state,
+            index : NN,
+        }
+    }
+
+    fn from_array(key : &[u64]) -> Result<Mt19937_64, ()> {
+        if key.is_empty() {
+            return Err(());
+        }
+        let mut mt = Mt19937_64::new(19650218u64);
+        let mut i = 1usize;
+        let mut j = 0usize;
+        for _ in 0 .. std::cmp::max(NN, key.len()) {
+            let previous = mt.state[i - 1];
+            mt.state[i] = (mt.state[i] ^ (previous ^ (previous >> 62)).wrapping_mul(3935559000370003845u64)).wrapping_add(key[j]).wrapping_add(j as u64);
+            i += 1;
+            j += 1;
+            if i >= NN {
+                mt.state[0] = mt.state[NN - 1];
+                i = 1;
+            }
+            if j >= key.len() {
+                j = 0;
+            }
+        }
+        for _ in 0 .. NN - 1 {
+            let previous = mt.state[i - 1];
+            mt.state[i] = (mt.state[i] ^ (previous ^ (previous >> 62)).wrapping_mul(2862933555777941757u64)).wrapping_sub(i as u64);
+            i += 1;
+            if i >= NN {
+                mt.state[0] = mt.state[NN - 1];
+                i = 1;
+            }
+        }
+        mt.state[0] = 1u64 << 63;
+        Ok(mt)
+    }
+
+    fn snapshot(&self) -> Mt19937_64State {
+        Mt19937_64State {
+            state : self.state.clone(),
+            index : self.index,
+        }
+    }
+
+    fn restore(snapshot : &Mt19937_64State) -> Result<Mt19937_64, ()> {
+        if snapshot.state.len() != NN || snapshot.index > NN {
+            return Err(());
+        }
+        Ok(Mt19937_64 {
+            state : snapshot.state.clone(),
+            index : snapshot.index,
+        })
+    }
+
+    fn twist(&mut self) {
+        for i in 0 .. NN {
+            let y = (self.state[i] & UPPER_MASK_64) | (self.state[(i + 1) % NN] & LOWER_MASK_64);
+            self.state[i] = self.state[(i + MM) % NN] ^ (y >> 1) ^ match y & 1 {
+                0 => 0u64,
+                _ => MATRIX_A_64,
+            };
+        }
+        self.index = 0;
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        if self.index >= NN {
+            self.twist();
+        }
+        let y = self.state[self.index];
+        self.index += 1;
+        temper_64(y)
+    }
+}
+
+fn main() {
+    let mut mt = Mt19937::new(5489);
+    for _ in 0 .. 5 {
+        println!("{}", mt.next_u32());
+    }
+    let mut mt = Mt19937_64::new(5489);
+    for _ in 0 .. 5 {
+        println!("{}", mt.next_u64());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    #[test]
+    fn test_mt19937() {
+        let mut mt = ::Mt19937::new(5489);
+        assert!(mt.next_u32() == 3499211612u32);
+        for _ in 1 .. 9999 {
+            mt.next_u32();
+        }
+        assert!(mt.next_u32() == 4123659995u32);
+    }
+
+    #[test]
+    fn test_mt19937_from_array() {
+        assert!(::Mt19937::from_array(&[]).is_err());
+        let mut mt = ::Mt19937::from_array(&[0x123u32, 0x234, 0x345, 0x456]).unwrap();
+        let expected = vec![1067595299u32, 955945823, 477289528, 4107218783, 4228976476, 3344332714, 3355579695, 227628506, 810200273, 2591290167];
+        for word in expected {
+            assert!(mt.next_u32() == word);
+        }
+    }
+
+    #[test]
+    fn test_mt19937_64() {
+        let mut mt = ::Mt19937_64::new(5489);
+        for _ in 1 .. 10000 {
+            mt.next_u64();
+        }
+        assert!(mt.next_u64() == 9981545732273789042u64);
+    }
+
+    #[test]
+    fn test_mt19937_64_from_array() {
+        assert!(::Mt19937_64::from_array(&[]).is_err());
+        let mut mt = ::Mt19937_64::from_array(&[0x12345u64, 0x23456, 0x34567, 0x45678]).unwrap();
+        let expected = vec![7266447313870364031u64, 4946485549665804864, 16945909448695747420, 16394063075524226720, 4873882236456199058];
+        for word in expected {
+            assert!(mt.next_u64() == word);
+        }
+    }
+
+    #[test]
+    fn test_snapshot() {
+        let mut mt = ::Mt19937::new(1);
+        for _ in 0 .. 1000 {
+            mt.next_u32();
+        }
+        let snapshot = mt.snapshot();
+        let expected : Vec<u32> = (0 .. 1000).map(|_| mt.next_u32()).collect();
+        let mut restored = ::Mt19937::restore(&snapshot).unwrap();
+        assert!((0 .. 1000).map(|_| restored.next_u32()).collect::<Vec<u32>>() == expected);
+        assert!(restored == mt);
+        assert!(::Mt19937::restore(&::Mt19937State { state : vec![0u32; 623], index : 0 }).is_err());
+        assert!(::Mt19937::restore(&::Mt19937State { state : vec![0u32; 624], index : 625 }).is_err());
+
+        let mut mt = ::Mt19937_64::new(1);
+        mt.next_u64();
+        let snapshot = mt.snapshot();
+        let expected : Vec<u64> = (0 .. 500).map(|_| mt.next_u64()).collect();
+        let mut restored = ::Mt19937_64::restore(&snapshot).unwrap();
+        assert!((0 .. 500).map(|_| restored.next_u64()).collect::<Vec<u64>>() == expected);
+        assert!(::Mt19937_64::restore(&::Mt19937_64State { state : vec![0u64; 624], index : 0 }).is_err());
+    }
+}
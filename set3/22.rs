@@ -0,0 +1,175 @@
+use std::fs::File;
+use std::io::Read;
+use std::thread;
+use std::time::Duration;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+fn random_octets(count : usize) -> Vec<u8> {
+    let mut f = File::open("/dev/urandom").unwrap();
+    let mut octets = vec![0u8; count];
+    match f.read_exact(&mut octets) {
+        Ok(_) => (),
+        Err(_) => panic!(),
+    };
+    octets
+}
+
+fn random_range(low : u64, high : u64) -> u64 {
+    let octets = random_octets(8);
+    let mut value = [0u8; 8];
+    value.copy_from_slice(&octets);
+    low + u64::from_le_bytes(value) % (high - low)
+}
+
+const N : usize = 624;
+const M : usize = 397;
+const MATRIX_A : u32 = 0x9908B0DFu32;
+const UPPER_MASK : u32 = 0x80000000u32;
+const LOWER_MASK : u32 = 0x7FFFFFFFu32;
+
+fn temper(word : u32) -> u32 {
+    let mut y = word;
+    y ^= y >> 11;
+    y ^= (y << 7) & 0x9D2C5680u32;
+    y ^= (y << 15) & 0xEFC60000u32;
+    y ^= y >> 18;
+    y
+}
+
+#[derive(Clone, PartialEq, Debug)]
+struct Mt19937 {
+    state : Vec<u32>,
+    index : usize,
+}
+
+impl Mt19937 {
+    fn new(seed : u32) -> Mt19937 {
+        let mut state : Vec<u32> = Vec::with_capacity(N);
+        state.push(seed);
+        for i in 1 .. N {
+            let previous = state[i - 1];
+            state.push(1812433253u32.wrapping_mul(previous ^ (previous >> 30)).wrapping_add(i as u32));
+        }
+        Mt19937 {
+            state : state,
+            index : N,
+        }
+    }
+
+    fn twist(&mut self) {
+        for i in 0 .. N {
+            let y = (self.state[i] & UPPER_MASK) | (self.state[(i + 1) % N] & LOWER_MASK);
+            self.state[i] = self.state[(i + M) % N] ^ (y >> 1) ^ match y & 1 {
+                0 => 0u32,
+                _ => MATRIX_A,
+            };
+        }
+        self.index = 0;
+    }
+
+    fn next_u32(&mut self) -> u32 {
+        if self.index >= N {
+            self.twist();
+        }
+        let y = self.state[self.index];
+        self.index += 1;
+        temper(y)
+    }
+}
+
+trait Clock {
+    fn now(&mut self) -> u64;
+    fn sleep(&mut self, seconds : u64);
+}
+
+struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&mut self) -> u64 {
+        SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs()
+    }
+
+    fn sleep(&mut self, seconds : u64) {
+        thread::sleep(Duration::from_secs(seconds));
+    }
+}
+
+struct MockClock {
+    time : u64,
+}
+
+impl MockClock {
+    fn new(time : u64) -> MockClock {
+        MockClock {
+            time : time,
+        }
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&mut self) -> u64 {
+        self.time
+    }
+
+    fn sleep(&mut self, seconds : u64) {
+        self.time += seconds;
+    }
+}
+
+fn time_seeded_output<C : Clock>(clock : &mut C) -> u32 {
+    let delay = random_range(40, 1001);
+    clock.sleep(delay);
+    let mut mt = Mt19937::new(clock.now() as u32);
+    let delay = random_range(40, 1001);
+    clock.sleep(delay);
+    mt.next_u32()
+}
+
+fn recover_time_seed(output : u32, now : u64, window : u64) -> Option<u32> {
+    for seed in (now.saturating_sub(window) .. now + 1).rev() {
+        if Mt19937::new(seed as u32).next_u32() == output {
+            return Some(seed as u32);
+        }
+    }
+    None
+}
+
+fn main() {
+    let mut clock = MockClock::new(SystemClock.now());
+    let output = time_seeded_output(&mut clock);
+    println!("{}", output);
+    println!("{:?}", recover_time_seed(output, clock.now(), 2000));
+}
+
+#[cfg(test)]
+mod tests {
+
+    use Clock;
+
+    #[test]
+    fn test_mock_clock() {
+        let mut clock = ::MockClock::new(1000);
+        assert!(clock.now() == 1000);
+        clock.sleep(42);
+        assert!(clock.now() == 1042);
+    }
+
+    #[test]
+    fn test_recover_time_seed() {
+        let start = ::SystemClock.now();
+        let mut clock = ::MockClock::new(start);
+        let output = ::time_seeded_output(&mut clock);
+        let seed = ::recover_time_seed(output, clock.now(), 2000).unwrap();
+        assert!(seed as u64 >= start + 40 && seed as u64 <= start + 1000);
+        assert!(::Mt19937::new(seed).next_u32() == output);
+    }
+
+    #[test]
+    fn test_window() {
+        let output = ::Mt19937::new(1500000000).next_u32();
+        assert!(::recover_time_seed(output, 1500000100, 100) == Some(1500000000));
+        assert!(::recover_time_seed(output, 1500000100, 99) == None);
+        assert!(::recover_time_seed(output, 1499999999, 1000) == None);
+    }
+}
@@ -0,0 +1,338 @@
+use std::fs::File;
+use std::io::Read;
+
+const N : usize = 624;
+const M : usize = 397;
+const MATRIX_A : u32 = 0x9908B0DFu32;
+const UPPER_MASK : u32 = 0x80000000u32;
+const LOWER_MASK : u32 = 0x7FFFFFFFu32;
+
+fn temper(word : u32) -> u32 {
+    let mut y = word;
+    y ^= y >> 11;
+    y ^= (y << 7) & 0x9D2C5680u32;
+    y ^= (y << 15) & 0xEFC60000u32;
+    y ^= y >> 18;
+    y
+}
+
+fn undo_right_shift_xor(value : u32, shift : u32) -> u32 {
+    let mut result = value;
+    for _ in 0 .. 32 / shift {
+        result = value ^ (result >> shift);
+    }
+    result
+}
+
+fn undo_left_shift_xor(value : u32, shift : u32, mask : u32) -> u32 {
+    let mut result = value;
+    for _ in 0 .. 32 / shift {
+        result = value ^ ((result << shift) & mask);
+    }
+    result
+}
+
+fn untemper(output : u32) -> u32 {
+    let mut y = output;
+    y = undo_right_shift_xor(y, 18);
+    y = undo_left_shift_xor(y, 15, 0xEFC60000u32);
+    y = undo_left_shift_xor(y, 7, 0x9D2C5680u32);
+    y = undo_right_shift_xor(y, 11);
+    y
+}
+
+#[derive(Clone, PartialEq, Debug)]
+struct Mt19937State {
+    state : Vec<u32>,
+    index : usize,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+struct Mt19937 {
+    state : Vec<u32>,
+    index : usize,
+}
+
+impl Mt19937 {
+    fn new(seed : u32) -> Mt19937 {
+        let mut state : Vec<u32> = Vec::with_capacity(N);
+        state.push(seed);
+        for i in 1 .. N {
+            let previous = state[i - 1];
+            state.push(1812433253u32.wrapping_mul(previous ^ (previous >> 30)).wrapping_add(i as u32));
+        }
+        Mt19937 {
+            state : state,
+            index : N,
+        }
+    }
+
+    fn from_array(key : &[u32]) -> Result<Mt19937, ()> {
+        if key.is_empty() {
+            return Err(());
+        }
+        let mut mt = Mt19937::new(19650218u32);
+        let mut i = 1usize;
+        let mut j = 0usize;
+        for _ in 0 .. std::cmp::max(N, key.len()) {
+            let previous = mt.state[i - 1];
+            mt.state[i] = (mt.state[i] ^ (previous ^ (previous >> 30)).wrapping_mul(1664525u32)).wrapping_add(key[j]).wrapping_add(j as u32);
+            i += 1;
+            j += 1;
+            if i >= N {
+                mt.state[0] = mt.state[N - 1];
+                i = 1;
+            }
+            if j >= key.len() {
+                j = 0;
+            }
+        }
+        for _ in 0 .. N - 1 {
+            let previous = mt.state[i - 1];
+            mt.state[i] = (mt.state[i] ^ (previous ^ (previous >> 30)).wrapping_mul(1566083941u32)).wrapping_sub(i as u32);
+            i += 1;
+            if i >= N {
+                mt.state[0] = mt.state[N - 1];
+                i = 1;
+            }
+        }
+        mt.state[0] = 0x80000000u32;
+        Ok(mt)
+    }
+
+    fn snapshot(&self) -> Mt19937State {
+        Mt19937State {
+            state : self.state.clone(),
+            index : self.index,
+        }
+    }
+
+    fn restore(snapshot : &Mt19937State) -> Result<Mt19937, ()> {
+        if snapshot.state.len() != N || snapshot.index > N {
+            return Err(());
+        }
+        Ok(Mt19937 {
+            state : snapshot.state.clone(),
+            index : snapshot.index,
+        })
+    }
+
+    fn twist(&mut self) {
+        for i in 0 .. N {
+            let y = (self.state[i] & UPPER_MASK) | (self.state[(i + 1) % N] & LOWER_MASK);
+            self.state[i] = self.state[(i + M) % N] ^ (y >> 1) ^ match y & 1 {
+                0 => 0u32,
+                _ => MATRIX_A,
+            };
+        }
+        self.index = 0;
+    }
+
+    fn next_u32(&mut self) -> u32 {
+        if self.index >= N {
+            self.twist();
+        }
+        let y = self.state[self.index];
+        self.index += 1;
+        temper(y)
+    }
+}
+
+fn twist_word(upper : u32, lower : u32) -> u32 {
+    let y = (upper & UPPER_MASK) | (lower & LOWER_MASK);
+    (y >> 1) ^ match y & 1 {
+        0 => 0u32,
+        _ => MATRIX_A,
+    }
+}
+
+fn untwist_word(twisted : u32) -> u32 {
+    match twisted & UPPER_MASK {
+        0 => twisted << 1,
+        _ => ((twisted ^ MATRIX_A) << 1) | 1,
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+struct PartialWord {
+    bits : u32,
+    mask : u32,
+}
+
+impl PartialWord {
+    fn unknown() -> PartialWord {
+        PartialWord {
+            bits : 0,
+            mask : 0,
+        }
+    }
+
+    fn known(word : u32) -> PartialWord {
+        PartialWord {
+            bits : word,
+            mask : 0xFFFFFFFFu32,
+        }
+    }
+
+    fn has(&self, mask : u32) -> bool {
+        self.mask & mask == mask
+    }
+
+    fn learn(&mut self, bits : u32, mask : u32) -> bool {
+        let new = mask & !self.mask;
+        self.bits |= bits & new;
+        self.mask |= new;
+        new != 0
+    }
+}
+
+fn recover_words(observations : &[Option<u32>]) -> Vec<PartialWord> {
+    let mut words : Vec<PartialWord> = observations.iter().map(|observation| match *observation {
+        Some(output) => PartialWord::known(untemper(output)),
+        None => PartialWord::unknown(),
+    }).collect();
+
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for k in 0 .. words.len().saturating_sub(N) {
+            let (current, next, middle, twisted) = (words[k], words[k + 1], words[k + M], words[k + N]);
+            if current.has(UPPER_MASK) && next.has(LOWER_MASK) && middle.has(0xFFFFFFFFu32) {
+                changed |= words[k + N].learn(middle.bits ^ twist_word(current.bits, next.bits), 0xFFFFFFFFu32);
+            }
+            if twisted.has(0xFFFFFFFFu32) && middle.has(0xFFFFFFFFu32) {
+                let y = untwist_word(twisted.bits ^ middle.bits);
+                changed |= words[k].learn(y, UPPER_MASK);
+                changed |= words[k + 1].learn(y, LOWER_MASK);
+            }
+            if twisted.has(0xFFFFFFFFu32) && current.has(UPPER_MASK) && next.has(LOWER_MASK) {
+                changed |= words[k + M].learn(twisted.bits ^ twist_word(current.bits, next.bits), 0xFFFFFFFFu32);
+            }
+        }
+    }
+    words
+}
+
+fn clone_mt19937(observations : &[Option<u32>]) -> Result<Mt19937, ()> {
+    let words = recover_words(observations);
+    let mut run = 0usize;
+    let mut start = None;
+    for (i, word) in words.iter().enumerate() {
+        run = match word.has(0xFFFFFFFFu32) {
+            true => run + 1,
+            false => 0,
+        };
+        if run == N {
+            start = Some(i + 1 - N);
+            break;
+        }
+    }
+    let start = start.ok_or(())?;
+
+    let snapshot = Mt19937State {
+        state : words[start .. start + N].iter().map(|word| word.bits).collect(),
+        index : N,
+    };
+    let mut mt = Mt19937::restore(&snapshot)?;
+    for observation in observations.iter().skip(start + N) {
+        let output = mt.next_u32();
+        match *observation {
+            Some(expected) if expected != output => return Err(()),
+            _ => (),
+        }
+    }
+    Ok(mt)
+}
+
+fn clone_from_outputs(outputs : &[u32]) -> Result<Mt19937, ()> {
+    clone_mt19937(&outputs.iter().map(|output| Some(*output)).collect::<Vec<Option<u32>>>())
+}
+
+fn random_seed() -> u32 {
+    let mut f = File::open("/dev/urandom").unwrap();
+    let mut octets = [0u8; 4];
+    match f.read_exact(&mut octets) {
+        Ok(_) => (),
+        Err(_) => panic!(),
+    };
+    u32::from_le_bytes(octets)
+}
+
+fn main() {
+    let mut mt = Mt19937::new(random_seed());
+    let outputs : Vec<u32> = (0 .. N).map(|_| mt.next_u32()).collect();
+    let mut clone = clone_from_outputs(&outputs).unwrap();
+    for _ in 0 .. 5 {
+        println!("{} {}", mt.next_u32(), clone.next_u32());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    #[test]
+    fn test_untemper() {
+        let mut mt = ::Mt19937::new(5489);
+        for _ in 0 .. 1000 {
+            let output = mt.next_u32();
+            assert!(::temper(::untemper(output)) == output);
+        }
+        for word in vec![0u32, 1, 0x80000000, 0xFFFFFFFF, 0xDEADBEEF] {
+            assert!(::untemper(::temper(word)) == word);
+        }
+    }
+
+    #[test]
+    fn test_untwist() {
+        for word in vec![0u32, 1, 0x7FFFFFFF, 0x80000000, 0xFFFFFFFF, 0x12345679] {
+            assert!(::untwist_word(::twist_word(word, word)) == word);
+        }
+    }
+
+    #[test]
+    fn test_clone() {
+        let mut mt = ::Mt19937::new(::random_seed());
+        for _ in 0 .. 100 {
+            mt.next_u32();
+        }
+        let outputs : Vec<u32> = (0 .. 624).map(|_| mt.next_u32()).collect();
+        let mut clone = ::clone_from_outputs(&outputs).unwrap();
+        for _ in 0 .. 2000 {
+            assert!(clone.next_u32() == mt.next_u32());
+        }
+        assert!(::clone_from_outputs(&outputs[.. 623]).is_err());
+    }
+
+    #[test]
+    fn test_clone_longer() {
+        let mut mt = ::Mt19937::new(::random_seed());
+        let outputs : Vec<u32> = (0 .. 1000).map(|_| mt.next_u32()).collect();
+        let mut clone = ::clone_from_outputs(&outputs).unwrap();
+        for _ in 0 .. 1000 {
+            assert!(clone.next_u32() == mt.next_u32());
+        }
+        let mut tampered = outputs.clone();
+        tampered[900] ^= 1;
+        assert!(::clone_from_outputs(&tampered).is_err());
+    }
+
+    #[test]
+    fn test_clone_with_gaps() {
+        let mut mt = ::Mt19937::new(::random_seed());
+        let observations : Vec<Option<u32>> = (0 .. 1400).map(|i| match i % 50 {
+            7 => { mt.next_u32(); None },
+            _ => Some(mt.next_u32()),
+        }).collect();
+        let mut clone = ::clone_mt19937(&observations).unwrap();
+        for _ in 0 .. 1000 {
+            assert!(clone.next_u32() == mt.next_u32());
+        }
+
+        let mut mt = ::Mt19937::new(::random_seed());
+        let observations : Vec<Option<u32>> = (0 .. 700).map(|i| match i % 50 {
+            7 => { mt.next_u32(); None },
+            _ => Some(mt.next_u32()),
+        }).collect();
+        assert!(::clone_mt19937(&observations).is_err());
+    }
+}
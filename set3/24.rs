@@ -0,0 +1,343 @@
+use std::fs::File;
+use std::io::Read;
+use std::thread;
+use std::time::Duration;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+fn random_octets(count : usize) -> Vec<u8> {
+    let mut f = File::open("/dev/urandom").unwrap();
+    let mut octets = vec![0u8; count];
+    match f.read_exact(&mut octets) {
+        Ok(_) => (),
+        Err(_) => panic!(),
+    };
+    octets
+}
+
+fn nibble2hex(nibble : u8) -> Result<u8, ()> {
+    match nibble {
+        0u8 ... 9u8 => Ok(nibble + 0x30u8),
+        10u8 ... 15u8 => Ok(nibble + 0x57u8),
+        _ => Err(()),
+    }
+}
+
+fn octets2hex(octets: &[u8]) -> Vec<u8> {
+    let mut hex : Vec<u8> = Vec::with_capacity(octets.len() * 2);
+    for octet in octets {
+        hex.push(nibble2hex((octet & 0xF0u8) >> 4).unwrap());
+        hex.push(nibble2hex(octet & 0x0Fu8).unwrap());
+    }
+    hex
+}
+
+fn fixed_xor(left: &[u8], right: &[u8]) -> Vec<u8> {
+    assert!(left.len() == right.len());
+    let mut xor : Vec<u8> = Vec::with_capacity(left.len());
+    for i in 0 .. left.len() {
+        xor.push(left[i] ^ right[i])
+    }
+    xor
+}
+
+const N : usize = 624;
+const M : usize = 397;
+const MATRIX_A : u32 = 0x9908B0DFu32;
+const UPPER_MASK : u32 = 0x80000000u32;
+const LOWER_MASK : u32 = 0x7FFFFFFFu32;
+
+fn temper(word : u32) -> u32 {
+    let mut y = word;
+    y ^= y >> 11;
+    y ^= (y << 7) & 0x9D2C5680u32;
+    y ^= (y << 15) & 0xEFC60000u32;
+    y ^= y >> 18;
+    y
+}
+
+#[derive(Clone, PartialEq, Debug)]
+struct Mt19937 {
+    state : Vec<u32>,
+    index : usize,
+}
+
+impl Mt19937 {
+    fn new(seed : u32) -> Mt19937 {
+        let mut state : Vec<u32> = Vec::with_capacity(N);
+        state.push(seed);
+        for i in 1 .. N {
+            let previous = state[i - 1];
+            state.push(1812433253u32.wrapping_mul(previous ^ (previous >> 30)).wrapping_add(i as u32));
+        }
+        Mt19937 {
+            state : state,
+            index : N,
+        }
+    }
+
+    fn twist(&mut self) {
+        for i in 0 .. N {
+            let y = (self.state[i] & UPPER_MASK) | (self.state[(i + 1) % N] & LOWER_MASK);
+            self.state[i] = self.state[(i + M) % N] ^ (y >> 1) ^ match y & 1 {
+                0 => 0u32,
+                _ => MATRIX_A,
+            };
+        }
+        self.index = 0;
+    }
+
+    fn next_u32(&mut self) -> u32 {
+        if self.index >= N {
+            self.twist();
+        }
+        let y = self.state[self.index];
+        self.index += 1;
+        temper(y)
+    }
+}
+
+trait Clock {
+    fn now(&mut self) -> u64;
+    fn sleep(&mut self, seconds : u64);
+}
+
+struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&mut self) -> u64 {
+        SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs()
+    }
+
+    fn sleep(&mut self, seconds : u64) {
+        thread::sleep(Duration::from_secs(seconds));
+    }
+}
+
+struct MockClock {
+    time : u64,
+}
+
+impl MockClock {
+    fn new(time : u64) -> MockClock {
+        MockClock {
+            time : time,
+        }
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&mut self) -> u64 {
+        self.time
+    }
+
+    fn sleep(&mut self, seconds : u64) {
+        self.time += seconds;
+    }
+}
+
+trait KeystreamGenerator {
+    fn next_block(&mut self) -> Vec<u8>;
+}
+
+impl KeystreamGenerator for Mt19937 {
+    fn next_block(&mut self) -> Vec<u8> {
+        self.next_u32().to_le_bytes().to_vec()
+    }
+}
+
+struct KeystreamCipher<G : KeystreamGenerator + Clone> {
+    initial : G,
+    generator : G,
+    buffer : Vec<u8>,
+    position : u64,
+}
+
+impl<G : KeystreamGenerator + Clone> KeystreamCipher<G> {
+    fn new(generator : G) -> KeystreamCipher<G> {
+        KeystreamCipher {
+            initial : generator.clone(),
+            generator : generator,
+            buffer : Vec::new(),
+            position : 0,
+        }
+    }
+
+    fn position(&self) -> u64 {
+        self.position
+    }
+
+    fn keystream(&mut self, length : usize) -> Vec<u8> {
+        while self.buffer.len() < length {
+            let block = self.generator.next_block();
+            assert!(!block.is_empty());
+            self.buffer.extend(block);
+        }
+        self.position += length as u64;
+        self.buffer.drain(.. length).collect()
+    }
+
+    fn apply(&mut self, input : &[u8]) -> Vec<u8> {
+        let keystream = self.keystream(input.len());
+        fixed_xor(input, &keystream)
+    }
+
+    fn apply_in_place(&mut self, buffer : &mut [u8]) {
+        let keystream = self.keystream(buffer.len());
+        for (octet, key) in buffer.iter_mut().zip(keystream.iter()) {
+            *octet ^= *key;
+        }
+    }
+
+    fn seek(&mut self, position : u64) {
+        if position < self.position {
+            self.generator = self.initial.clone();
+            self.buffer.clear();
+            self.position = 0;
+        }
+        while self.position < position {
+            let step = std::cmp::min(position - self.position, 4096) as usize;
+            self.keystream(step);
+        }
+    }
+}
+
+fn mt_keystream(seed : u32, length : usize) -> Vec<u8> {
+    KeystreamCipher::new(Mt19937::new(seed)).keystream(length)
+}
+
+fn mt_cipher(seed : u16, input : &[u8]) -> Vec<u8> {
+    KeystreamCipher::new(Mt19937::new(seed as u32)).apply(input)
+}
+
+fn encrypt_with_prefix(seed : u16, known : &[u8]) -> Vec<u8> {
+    let count = random_octets(1)[0] as usize % 32 + 1;
+    let mut plaintext = random_octets(count);
+    plaintext.extend_from_slice(known);
+    mt_cipher(seed, &plaintext)
+}
+
+fn recover_cipher_seed(ciphertext : &[u8], known : &[u8]) -> Option<u16> {
+    if known.len() > ciphertext.len() {
+        return None;
+    }
+    let offset = ciphertext.len() - known.len();
+    let expected = fixed_xor(&ciphertext[offset ..], known);
+    for seed in 0u32 .. 0x10000u32 {
+        if mt_keystream(seed, ciphertext.len())[offset ..] == expected[..] {
+            return Some(seed as u16);
+        }
+    }
+    None
+}
+
+fn reset_token<C : Clock>(clock : &mut C) -> Vec<u8> {
+    mt_keystream(clock.now() as u32, 16)
+}
+
+fn detect_time_seeded_token(token : &[u8], now : u64, window : u64) -> Option<u32> {
+    for seed in (now.saturating_sub(window) .. now + 1).rev() {
+        if mt_keystream(seed as u32, token.len()) == token {
+            return Some(seed as u32);
+        }
+    }
+    None
+}
+
+fn main() {
+    let octets = random_octets(2);
+    let seed = (octets[0] as u16) << 8 | octets[1] as u16;
+    let ciphertext = encrypt_with_prefix(seed, b"AAAAAAAAAAAAAA");
+    println!("{} {:?}", seed, recover_cipher_seed(&ciphertext, b"AAAAAAAAAAAAAA"));
+
+    let mut clock = SystemClock;
+    let token = reset_token(&mut clock);
+    println!("{}", String::from_utf8(octets2hex(&token)).unwrap());
+    println!("{:?}", detect_time_seeded_token(&token, clock.now(), 3600));
+    println!("{:?}", detect_time_seeded_token(&random_octets(16), clock.now(), 3600));
+}
+
+#[cfg(test)]
+mod tests {
+
+    use Clock;
+    use KeystreamGenerator;
+
+    #[derive(Clone)]
+    struct Counter {
+        value : u8,
+    }
+
+    impl KeystreamGenerator for Counter {
+        fn next_block(&mut self) -> Vec<u8> {
+            self.value = self.value.wrapping_add(1);
+            vec![self.value; 3]
+        }
+    }
+
+    #[test]
+    fn test_mt_cipher() {
+        let plaintext = b"Ice, Ice, baby".to_vec();
+        let ciphertext = ::mt_cipher(0x1234, &plaintext);
+        assert!(ciphertext != plaintext);
+        assert!(::mt_cipher(0x1234, &ciphertext) == plaintext);
+        assert!(::mt_cipher(0x1235, &ciphertext) != plaintext);
+        assert!(::mt_keystream(5489, 4) == 3499211612u32.to_le_bytes().to_vec());
+        assert!(::mt_keystream(5489, 6)[.. 4] == ::mt_keystream(5489, 4)[..]);
+    }
+
+    #[test]
+    fn test_recover_cipher_seed() {
+        for seed in vec![0u16, 0x8000, 0xFFFF] {
+            let ciphertext = ::encrypt_with_prefix(seed, b"AAAAAAAAAAAAAA");
+            assert!(::recover_cipher_seed(&ciphertext, b"AAAAAAAAAAAAAA") == Some(seed));
+        }
+        assert!(::recover_cipher_seed(b"short", b"AAAAAAAAAAAAAA") == None);
+    }
+
+    #[test]
+    fn test_reset_token() {
+        let mut clock = ::MockClock::new(1500000000);
+        let token = ::reset_token(&mut clock);
+        assert!(token.len() == 16);
+        clock.sleep(600);
+        assert!(::detect_time_seeded_token(&token, clock.now(), 3600) == Some(1500000000));
+        assert!(::detect_time_seeded_token(&token, clock.now(), 599) == None);
+        assert!(::detect_time_seeded_token(&::random_octets(16), clock.now(), 3600) == None);
+    }
+
+    #[test]
+    fn test_keystream_cipher() {
+        let mut cipher = ::KeystreamCipher::new(Counter { value : 0 });
+        assert!(cipher.keystream(7) == vec![1u8, 1, 1, 2, 2, 2, 3]);
+        assert!(cipher.position() == 7);
+        assert!(cipher.apply(&[0xFFu8, 0xFF, 0x00]) == vec![0xFCu8, 0xFC, 0x04]);
+        cipher.seek(2);
+        assert!(cipher.keystream(2) == vec![1u8, 2]);
+        cipher.seek(12);
+        assert!(cipher.keystream(1) == vec![5u8]);
+    }
+
+    #[test]
+    fn test_streaming() {
+        let plaintext : Vec<u8> = (0u8 .. 200u8).collect();
+        let ciphertext = ::KeystreamCipher::new(::Mt19937::new(42)).apply(&plaintext);
+
+        let mut cipher = ::KeystreamCipher::new(::Mt19937::new(42));
+        let mut streamed : Vec<u8> = Vec::new();
+        for chunk in plaintext.chunks(7) {
+            streamed.extend(cipher.apply(chunk));
+        }
+        assert!(streamed == ciphertext);
+
+        let mut buffer = ciphertext.clone();
+        let mut cipher = ::KeystreamCipher::new(::Mt19937::new(42));
+        cipher.apply_in_place(&mut buffer);
+        assert!(buffer == plaintext);
+
+        for offset in vec![0usize, 3, 4, 101, 199] {
+            cipher.seek(offset as u64);
+            assert!(cipher.apply(&ciphertext[offset ..]) == plaintext[offset ..].to_vec());
+        }
+    }
+}
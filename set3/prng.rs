@@ -0,0 +1,497 @@
+fn fixed_xor(left: &[u8], right: &[u8]) -> Vec<u8> {
+    assert!(left.len() == right.len());
+    let mut xor : Vec<u8> = Vec::with_capacity(left.len());
+    for i in 0 .. left.len() {
+        xor.push(left[i] ^ right[i])
+    }
+    xor
+}
+
+trait KeystreamGenerator {
+    fn next_block(&mut self) -> Vec<u8>;
+}
+
+struct KeystreamCipher<G : KeystreamGenerator + Clone> {
+    initial : G,
+    generator : G,
+    buffer : Vec<u8>,
+    position : u64,
+}
+
+impl<G : KeystreamGenerator + Clone> KeystreamCipher<G> {
+    fn new(generator : G) -> KeystreamCipher<G> {
+        KeystreamCipher {
+            initial : generator.clone(),
+            generator : generator,
+            buffer : Vec::new(),
+            position : 0,
+        }
+    }
+
+    fn position(&self) -> u64 {
+        self.position
+    }
+
+    fn keystream(&mut self, length : usize) -> Vec<u8> {
+        while self.buffer.len() < length {
+            let block = self.generator.next_block();
+            assert!(!block.is_empty());
+            self.buffer.extend(block);
+        }
+        self.position += length as u64;
+        self.buffer.drain(.. length).collect()
+    }
+
+    fn apply(&mut self, input : &[u8]) -> Vec<u8> {
+        let keystream = self.keystream(input.len());
+        fixed_xor(input, &keystream)
+    }
+
+    fn apply_in_place(&mut self, buffer : &mut [u8]) {
+        let keystream = self.keystream(buffer.len());
+        for (octet, key) in buffer.iter_mut().zip(keystream.iter()) {
+            *octet ^= *key;
+        }
+    }
+
+    fn seek(&mut self, position : u64) {
+        if position < self.position {
+            self.generator = self.initial.clone();
+            self.buffer.clear();
+            self.position = 0;
+        }
+        while self.position < position {
+            let step = std::cmp::min(position - self.position, 4096) as usize;
+            self.keystream(step);
+        }
+    }
+}
+
+fn octets2bits(octets : &[u8]) -> Vec<u8> {
+    let mut bits : Vec<u8> = Vec::with_capacity(octets.len() * 8);
+    for octet in octets {
+        for i in 0 .. 8 {
+            bits.push((octet >> i) & 1);
+        }
+    }
+    bits
+}
+
+fn bits2octets(bits : &[u8]) -> Vec<u8> {
+    let mut octets : Vec<u8> = Vec::with_capacity((bits.len() + 7) / 8);
+    for chunk in bits.chunks(8) {
+        let mut octet = 0u8;
+        for (i, bit) in chunk.iter().enumerate() {
+            octet |= (bit & 1) << i;
+        }
+        octets.push(octet);
+    }
+    octets
+}
+
+#[derive(Clone, PartialEq, Debug)]
+struct FibonacciLfsr {
+    taps : Vec<u8>,
+    state : Vec<u8>,
+}
+
+impl FibonacciLfsr {
+    fn new(taps : &[u8], state : &[u8]) -> Result<FibonacciLfsr, ()> {
+        if taps.len() != state.len() {
+            return Err(());
+        }
+        Ok(FibonacciLfsr {
+            taps : taps.iter().map(|tap| tap & 1).collect(),
+            state : state.iter().map(|bit| bit & 1).collect(),
+        })
+    }
+
+    fn next_bit(&mut self) -> u8 {
+        if self.state.is_empty() {
+            return 0;
+        }
+        let length = self.state.len();
+        let mut feedback = 0u8;
+        for (i, tap) in self.taps.iter().enumerate() {
+            feedback ^= tap & self.state[length - 1 - i];
+        }
+        let output = self.state.remove(0);
+        self.state.push(feedback);
+        output
+    }
+}
+
+impl KeystreamGenerator for FibonacciLfsr {
+    fn next_block(&mut self) -> Vec<u8> {
+        let bits : Vec<u8> = (0 .. 8).map(|_| self.next_bit()).collect();
+        bits2octets(&bits)
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+struct GaloisLfsr {
+    taps : u64,
+    state : u64,
+}
+
+impl GaloisLfsr {
+    fn new(taps : u64, state : u64) -> Result<GaloisLfsr, ()> {
+        if taps == 0 || state == 0 {
+            return Err(());
+        }
+        Ok(GaloisLfsr {
+            taps : taps,
+            state : state,
+        })
+    }
+
+    fn next_bit(&mut self) -> u8 {
+        let output = (self.state & 1) as u8;
+        self.state >>= 1;
+        if output == 1 {
+            self.state ^= self.taps;
+        }
+        output
+    }
+}
+
+impl KeystreamGenerator for GaloisLfsr {
+    fn next_block(&mut self) -> Vec<u8> {
+        let bits : Vec<u8> = (0 .. 8).map(|_| self.next_bit()).collect();
+        bits2octets(&bits)
+    }
+}
+
+fn berlekamp_massey(bits : &[u8]) -> (usize, Vec<u8>) {
+    let mut connection = vec![0u8; bits.len() + 1];
+    let mut previous = vec![0u8; bits.len() + 1];
+    connection[0] = 1;
+    previous[0] = 1;
+    let mut length = 0usize;
+    let mut shift = 1usize;
+    for n in 0 .. bits.len() {
+        let mut discrepancy = bits[n] & 1;
+        for i in 1 .. length + 1 {
+            discrepancy ^= connection[i] & bits[n - i];
+        }
+        if discrepancy == 0 {
+            shift += 1;
+            continue;
+        }
+        let saved = connection.clone();
+        for i in shift .. connection.len() {
+            connection[i] ^= previous[i - shift];
+        }
+        if 2 * length <= n {
+            length = n + 1 - length;
+            previous = saved;
+            shift = 1;
+        } else {
+            shift += 1;
+        }
+    }
+    connection.truncate(length + 1);
+    (length, connection)
+}
+
+fn recover_lfsr(bits : &[u8]) -> Result<FibonacciLfsr, ()> {
+    let (length, connection) = berlekamp_massey(bits);
+    if 2 * length > bits.len() {
+        return Err(());
+    }
+    FibonacciLfsr::new(&connection[1 ..], &bits[.. length])
+}
+
+#[derive(Clone, PartialEq, Debug)]
+struct Lcg {
+    modulus : u64,
+    multiplier : u64,
+    increment : u64,
+    state : u64,
+}
+
+impl Lcg {
+    fn new(modulus : u64, multiplier : u64, increment : u64, seed : u64) -> Result<Lcg, ()> {
+        if modulus < 2 {
+            return Err(());
+        }
+        Ok(Lcg {
+            modulus : modulus,
+            multiplier : multiplier % modulus,
+            increment : increment % modulus,
+            state : seed % modulus,
+        })
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = ((self.multiplier as u128 * self.state as u128 + self.increment as u128) % self.modulus as u128) as u64;
+        self.state
+    }
+
+    fn output_octets(&self) -> usize {
+        ((64 - (self.modulus - 1).leading_zeros() as usize) + 7) / 8
+    }
+}
+
+impl KeystreamGenerator for Lcg {
+    fn next_block(&mut self) -> Vec<u8> {
+        let octets = self.output_octets();
+        self.next_u64().to_le_bytes()[.. octets].to_vec()
+    }
+}
+
+fn gcd(left : u128, right : u128) -> u128 {
+    let (mut a, mut b) = (left, right);
+    while b != 0 {
+        let r = a % b;
+        a = b;
+        b = r;
+    }
+    a
+}
+
+fn modular_inverse(value : i128, modulus : i128) -> Option<i128> {
+    let (mut r0, mut r1) = (modulus, value.rem_euclid(modulus));
+    let (mut t0, mut t1) = (0i128, 1i128);
+    while r1 != 0 {
+        let q = r0 / r1;
+        let r = r0 - q * r1;
+        r0 = r1;
+        r1 = r;
+        let t = t0 - q * t1;
+        t0 = t1;
+        t1 = t;
+    }
+    match r0 {
+        1 => Some(t0.rem_euclid(modulus)),
+        _ => None,
+    }
+}
+
+fn recover_lcg_modulus(outputs : &[u64]) -> Result<u64, ()> {
+    let differences : Vec<(bool, u64)> = outputs.windows(2).map(|pair| match pair[1] < pair[0] {
+        true => (true, pair[0] - pair[1]),
+        false => (false, pair[1] - pair[0]),
+    }).collect();
+    let mut modulus = 0u128;
+    for window in differences.windows(3) {
+        let ((negative_0, t0), (_, t1), (negative_2, t2)) = (window[0], window[1], window[2]);
+        let product = t2 as u128 * t0 as u128;
+        let square = t1 as u128 * t1 as u128;
+        // The sum only arises when the outer differences point in opposite directions, so all
+        // three steps stay within one u64 range and product + square cannot exceed 2^128 - 1.
+        let determinant = match negative_0 == negative_2 {
+            true => product.max(square) - product.min(square),
+            false => product + square,
+        };
+        modulus = gcd(modulus, determinant);
+    }
+    match modulus {
+        0 | 1 => Err(()),
+        _ if modulus > std::u64::MAX as u128 => Err(()),
+        _ => Ok(modulus as u64),
+    }
+}
+
+fn mulmod(left : u64, right : u64, modulus : u64) -> u64 {
+    ((left as u128 * right as u128) % modulus as u128) as u64
+}
+
+fn submod(left : u64, right : u64, modulus : u64) -> u64 {
+    let m = modulus as u128;
+    ((left as u128 % m + m - right as u128 % m) % m) as u64
+}
+
+fn recover_lcg_parameters(outputs : &[u64], modulus : u64) -> Result<(u64, u64), ()> {
+    if modulus < 2 {
+        return Err(());
+    }
+    for window in outputs.windows(3) {
+        let (s0, s1, s2) = (window[0], window[1], window[2]);
+        let inverse = match modular_inverse(submod(s1, s0, modulus) as i128, modulus as i128) {
+            Some(inverse) => inverse as u64,
+            None => continue,
+        };
+        let multiplier = mulmod(submod(s2, s1, modulus), inverse, modulus);
+        let increment = submod(s1, mulmod(multiplier, s0, modulus), modulus);
+        return Ok((multiplier, increment));
+    }
+    Err(())
+}
+
+fn recover_lcg(outputs : &[u64], modulus : Option<u64>) -> Result<Lcg, ()> {
+    let modulus = match modulus {
+        Some(modulus) => modulus,
+        None => recover_lcg_modulus(outputs)?,
+    };
+    let (multiplier, increment) = recover_lcg_parameters(outputs, modulus)?;
+    let mut lcg = Lcg::new(modulus, multiplier, increment, outputs[0])?;
+    for output in &outputs[1 ..] {
+        if lcg.next_u64() != *output {
+            return Err(());
+        }
+    }
+    Ok(lcg)
+}
+
+fn main() {
+    let lfsr = GaloisLfsr::new(0xB400, 0xACE1).unwrap();
+    let plaintext = b"Yo, VIP Let's kick it Ice, Ice, baby Ice, Ice, baby".to_vec();
+    let ciphertext = KeystreamCipher::new(lfsr).apply(&plaintext);
+    let known = 8;
+    let keystream = fixed_xor(&ciphertext[.. known], &plaintext[.. known]);
+    let recovered = recover_lfsr(&octets2bits(&keystream)).unwrap();
+    println!("{} {:?}", recovered.state.len(), recovered.taps);
+    println!("{}", String::from_utf8_lossy(&KeystreamCipher::new(recovered).apply(&ciphertext)));
+
+    let mut lcg = Lcg::new(1 << 31, 1103515245, 12345, 1).unwrap();
+    let outputs : Vec<u64> = (0 .. 10).map(|_| lcg.next_u64()).collect();
+    println!("{:?}", recover_lcg(&outputs, None));
+}
+
+#[cfg(test)]
+mod tests {
+
+    #[test]
+    fn test_bits() {
+        assert!(::octets2bits(&[0x01u8, 0x80]) == vec![1u8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1]);
+        assert!(::bits2octets(&::octets2bits(b"YELLOW")) == b"YELLOW".to_vec());
+        assert!(::bits2octets(&[1u8, 1, 0, 1]) == vec![0x0Bu8]);
+    }
+
+    #[test]
+    fn test_fibonacci_lfsr() {
+        let mut lfsr = ::FibonacciLfsr::new(&[0u8, 1, 1], &[1u8, 0, 0]).unwrap();
+        let bits : Vec<u8> = (0 .. 14).map(|_| lfsr.next_bit()).collect();
+        assert!(bits == vec![1u8, 0, 0, 1, 0, 1, 1, 1, 0, 0, 1, 0, 1, 1]);
+        assert!(::FibonacciLfsr::new(&[1u8], &[1u8, 0]).is_err());
+    }
+
+    #[test]
+    fn test_galois_lfsr() {
+        let mut lfsr = ::GaloisLfsr::new(0xB400, 0xACE1).unwrap();
+        let mut period = 0usize;
+        loop {
+            lfsr.next_bit();
+            period += 1;
+            if lfsr.state == 0xACE1 {
+                break;
+            }
+        }
+        assert!(period == 65535);
+        assert!(::GaloisLfsr::new(0xB400, 0).is_err());
+    }
+
+    #[test]
+    fn test_berlekamp_massey() {
+        let (length, connection) = ::berlekamp_massey(&[1u8, 0, 0, 1, 0, 1, 1, 1, 0, 0, 1, 0, 1, 1]);
+        assert!(length == 3);
+        assert!(connection == vec![1u8, 0, 1, 1]);
+        assert!(::berlekamp_massey(&[0u8; 10]) == (0, vec![1u8]));
+        assert!(::berlekamp_massey(&[0u8, 0, 0, 1]).0 == 4);
+
+        let mut lfsr = ::GaloisLfsr::new(0xB400, 0xACE1).unwrap();
+        let bits : Vec<u8> = (0 .. 200).map(|_| lfsr.next_bit()).collect();
+        let (length, _) = ::berlekamp_massey(&bits);
+        assert!(length == 16);
+        let mut recovered = ::recover_lfsr(&bits[.. 32]).unwrap();
+        assert!((0 .. 200).map(|_| recovered.next_bit()).collect::<Vec<u8>>() == bits);
+        let mut short = ::recover_lfsr(&bits[.. 20]).unwrap();
+        assert!(short.state.len() <= 10);
+        assert!((0 .. 200).map(|_| short.next_bit()).collect::<Vec<u8>>() != bits);
+        assert!(::recover_lfsr(&[0u8, 0, 0, 1]).is_err());
+    }
+
+    #[test]
+    fn test_lfsr_known_plaintext() {
+        let plaintext = b"Yo, VIP Let's kick it Ice, Ice, baby Ice, Ice, baby".to_vec();
+        let taps = vec![1u8, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1];
+        let lfsr = ::FibonacciLfsr::new(&taps, &::octets2bits(&[0x5Au8, 0xC3, 0x1E])[.. 21]).unwrap();
+        let ciphertext = ::KeystreamCipher::new(lfsr).apply(&plaintext);
+        let keystream = ::fixed_xor(&ciphertext[.. 8], &plaintext[.. 8]);
+        let recovered = ::recover_lfsr(&::octets2bits(&keystream)).unwrap();
+        assert!(recovered.state.len() <= 21);
+        assert!(::KeystreamCipher::new(recovered).apply(&ciphertext) == plaintext);
+    }
+
+    #[test]
+    fn test_lcg() {
+        let mut lcg = ::Lcg::new(1 << 31, 1103515245, 12345, 0).unwrap();
+        assert!(lcg.next_u64() == 12345);
+        assert!(lcg.next_u64() == 1406932606);
+        assert!(lcg.output_octets() == 4);
+        assert!(::Lcg::new(1, 2, 3, 4).is_err());
+        let mut lcg = ::Lcg::new(2147483647, 48271, 0, 1).unwrap();
+        assert!(lcg.next_u64() == 48271);
+        assert!(lcg.next_u64() == 182605794);
+    }
+
+    #[test]
+    fn test_recover_lcg() {
+        let mut lcg = ::Lcg::new(1 << 31, 1103515245, 12345, 42).unwrap();
+        let outputs : Vec<u64> = (0 .. 12).map(|_| lcg.next_u64()).collect();
+        let mut recovered = ::recover_lcg(&outputs, None).unwrap();
+        assert!(recovered.modulus == 1 << 31);
+        assert!(recovered.multiplier == 1103515245 && recovered.increment == 12345);
+        for _ in 0 .. 100 {
+            assert!(recovered.next_u64() == lcg.next_u64());
+        }
+
+        let mut lcg = ::Lcg::new(2147483647, 48271, 0, 7).unwrap();
+        let outputs : Vec<u64> = (0 .. 12).map(|_| lcg.next_u64()).collect();
+        assert!(::recover_lcg_modulus(&outputs) == Ok(2147483647));
+        assert!(::recover_lcg_parameters(&outputs, 2147483647) == Ok((48271, 0)));
+        assert!(::recover_lcg(&outputs[.. 3], None).is_err());
+        let mut tampered = outputs.clone();
+        tampered[11] ^= 1;
+        assert!(::recover_lcg(&tampered, Some(2147483647)).is_err());
+    }
+
+    #[test]
+    fn test_recover_lcg_large_modulus() {
+        let modulus = 18446744073709551557u64;
+        assert!(::mulmod(modulus - 1, modulus - 1, modulus) == 1);
+        assert!(::submod(1, modulus - 1, modulus) == 2);
+        for seed in 0 .. 50u64 {
+            let multiplier = 6364136223846793005u64.wrapping_mul(seed + 1);
+            let increment = 1442695040888963407u64 ^ seed;
+            let mut lcg = ::Lcg::new(modulus, multiplier, increment, seed.wrapping_mul(0x9E3779B97F4A7C15)).unwrap();
+            let outputs : Vec<u64> = (0 .. 8).map(|_| lcg.next_u64()).collect();
+            assert!(::recover_lcg_parameters(&outputs, modulus) == Ok((multiplier % modulus, increment % modulus)));
+            let mut recovered = ::recover_lcg(&outputs, Some(modulus)).unwrap();
+            for _ in 0 .. 100 {
+                assert!(recovered.next_u64() == lcg.next_u64());
+            }
+        }
+
+        assert!(::recover_lcg_modulus(&[std::u64::MAX, 0, 1 << 63, std::u64::MAX, 0, 1 << 62]) == Err(()));
+        for modulus in vec![18446744073709551557u64, 9223372036854775837u64] {
+            for seed in 0 .. 50u64 {
+                let multiplier = 6364136223846793005u64.wrapping_mul(seed + 1);
+                let increment = 1442695040888963407u64 ^ seed;
+                let mut lcg = ::Lcg::new(modulus, multiplier, increment, seed.wrapping_mul(0x9E3779B97F4A7C15)).unwrap();
+                let outputs : Vec<u64> = (0 .. 16).map(|_| lcg.next_u64()).collect();
+                assert!(::recover_lcg_modulus(&outputs) == Ok(modulus));
+                let mut recovered = ::recover_lcg(&outputs, None).unwrap();
+                for _ in 0 .. 100 {
+                    assert!(recovered.next_u64() == lcg.next_u64());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_lcg_known_plaintext() {
+        let plaintext = b"Yo, VIP Let's kick it Ice, Ice, baby Ice, Ice, baby".to_vec();
+        let lcg = ::Lcg::new(4294967291, 279470273, 1013904223, 31337).unwrap();
+        let ciphertext = ::KeystreamCipher::new(lcg).apply(&plaintext);
+        let keystream = ::fixed_xor(&ciphertext[.. 32], &plaintext[.. 32]);
+        let outputs : Vec<u64> = keystream.chunks(4).map(|chunk| u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]) as u64).collect();
+        let mut lcg = ::recover_lcg(&outputs, None).unwrap();
+        let mut recovered : Vec<u8> = Vec::new();
+        while recovered.len() < plaintext.len() - 32 {
+            recovered.extend_from_slice(&(lcg.next_u64() as u32).to_le_bytes());
+        }
+        assert!(::fixed_xor(&ciphertext[32 ..], &recovered[.. plaintext.len() - 32]) == plaintext[32 ..].to_vec());
+    }
+}
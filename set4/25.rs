@@ -0,0 +1,514 @@
+use std::fs::File;
+use std::io;
+use std::io::Read;
+use std::io::Seek;
+use std::io::SeekFrom;
+use std::io::Write;
+
+fn base64decode(input : &[u8]) -> Vec<u8> {
+    let mut octets : Vec<u8> = Vec::new();
+
+    let mut input_octets = input.iter().map(|c| match *c {
+        0x41u8 ... 0x5Au8 => c - 0x41u8,
+        0x61u8 ... 0x7Au8 => c - 0x47u8,
+        0x30u8 ... 0x39u8 => c + 0x04u8,
+        0x2Bu8 => 0x3Eu8,
+        0x2Fu8 => 0x3Fu8,
+        0x3Du8 => 0xFFu8,
+        i => panic!("{}", i),
+    }).collect::<Vec<u8>>();
+    input_octets.retain(|c| *c != 0xFFu8);
+    for chunk in input_octets.chunks(4) {
+        let groups = match chunk.len() {
+            2 => vec![((chunk[0] << 2) & 0xFCu8) + ((chunk[1] >> 4) & 0x03u8)],
+            3 => vec![((chunk[0] << 2) & 0xFCu8) + ((chunk[1] >> 4) & 0x03u8), ((chunk[1] << 4) & 0xF0u8) + ((chunk[2] >> 2) & 0x0F)],
+            4 => vec![((chunk[0] << 2) & 0xFCu8) + ((chunk[1] >> 4) & 0x03u8), ((chunk[1] << 4) & 0xF0u8) + ((chunk[2] >> 2) & 0x0F), ((chunk[2] << 6) & 0xC0u8) + (chunk[3] & 0x3Fu8)],
+            _ => panic!(),
+        };
+        octets.reserve(groups.len());
+        for group in groups {
+            octets.push(group)
+        }
+    }
+
+    octets
+}
+
+fn random_octets(count : usize) -> Vec<u8> {
+    let mut f = File::open("/dev/urandom").unwrap();
+    let mut octets = vec![0u8; count];
+    match f.read_exact(&mut octets) {
+        Ok(_) => (),
+        Err(_) => panic!(),
+    };
+    octets
+}
+
+fn fixed_xor(left: &[u8], right: &[u8]) -> Vec<u8> {
+    assert!(left.len() == right.len());
+    let mut xor : Vec<u8> = Vec::with_capacity(left.len());
+    for i in 0 .. left.len() {
+        xor.push(left[i] ^ right[i])
+    }
+    xor
+}
+
+fn gf_multiply(left : u8, right : u8) -> u8 {
+    let mut a = left;
+    let mut b = right;
+    let mut product = 0u8;
+    while b != 0 {
+        if b & 1 == 1 {
+            product ^= a;
+        }
+        a = (a << 1) ^ match a & 0x80u8 {
+            0 => 0x00u8,
+            _ => 0x1Bu8,
+        };
+        b >>= 1;
+    }
+    product
+}
+
+fn gf_inverse(octet : u8) -> u8 {
+    if octet == 0 {
+        return 0;
+    }
+    let mut inverse = 1u8;
+    while gf_multiply(octet, inverse) != 1 {
+        inverse += 1;
+    }
+    inverse
+}
+
+fn sboxes() -> ([u8; 256], [u8; 256]) {
+    let mut sbox = [0u8; 256];
+    let mut inverse_sbox = [0u8; 256];
+    for i in 0 .. 256 {
+        let b = gf_inverse(i as u8);
+        let s = b ^ b.rotate_left(1) ^ b.rotate_left(2) ^ b.rotate_left(3) ^ b.rotate_left(4) ^ 0x63u8;
+        sbox[i] = s;
+        inverse_sbox[s as usize] = i as u8;
+    }
+    (sbox, inverse_sbox)
+}
+
+struct Aes {
+    sbox : [u8; 256],
+    inverse_sbox : [u8; 256],
+    round_keys : Vec<[u8; 16]>,
+}
+
+impl Aes {
+    fn new(key : &[u8]) -> Result<Aes, ()> {
+        let key_words = match key.len() {
+            16 | 24 | 32 => key.len() / 4,
+            _ => return Err(()),
+        };
+        let rounds = key_words + 6;
+        let (sbox, inverse_sbox) = sboxes();
+
+        let mut words : Vec<[u8; 4]> = Vec::with_capacity(4 * (rounds + 1));
+        for chunk in key.chunks(4) {
+            words.push([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        }
+        let mut round_constant = 0x01u8;
+        for i in key_words .. 4 * (rounds + 1) {
+            let mut word = words[i - 1];
+            if i % key_words == 0 {
+                word = [sbox[word[1] as usize] ^ round_constant, sbox[word[2] as usize], sbox[word[3] as usize], sbox[word[0] as usize]];
+                round_constant = gf_multiply(round_constant, 0x02u8);
+            } else if key_words > 6 && i % key_words == 4 {
+                word = [sbox[word[0] as usize], sbox[word[1] as usize], sbox[word[2] as usize], sbox[word[3] as usize]];
+            }
+            let previous = words[i - key_words];
+            words.push([previous[0] ^ word[0], previous[1] ^ word[1], previous[2] ^ word[2], previous[3] ^ word[3]]);
+        }
+
+        let mut round_keys : Vec<[u8; 16]> = Vec::with_capacity(rounds + 1);
+        for chunk in words.chunks(4) {
+            let mut round_key = [0u8; 16];
+            for (i, word) in chunk.iter().enumerate() {
+                round_key[4 * i .. 4 * i + 4].copy_from_slice(word);
+            }
+            round_keys.push(round_key);
+        }
+
+        Ok(Aes {
+            sbox : sbox,
+            inverse_sbox : inverse_sbox,
+            round_keys : round_keys,
+        })
+    }
+
+    fn rounds(&self) -> usize {
+        self.round_keys.len() - 1
+    }
+
+    fn add_round_key(state : &mut [u8; 16], round_key : &[u8; 16]) {
+        for (octet, key) in state.iter_mut().zip(round_key.iter()) {
+            *octet ^= *key;
+        }
+    }
+
+    fn substitute_bytes(state : &mut [u8; 16], sbox : &[u8; 256]) {
+        for octet in state.iter_mut() {
+            *octet = sbox[*octet as usize];
+        }
+    }
+
+    fn shift_rows(state : &mut [u8; 16]) {
+        let original = *state;
+        for row in 1 .. 4 {
+            for column in 0 .. 4 {
+                state[row + 4 * column] = original[row + 4 * ((column + row) % 4)];
+            }
+        }
+    }
+
+    fn inverse_shift_rows(state : &mut [u8; 16]) {
+        let original = *state;
+        for row in 1 .. 4 {
+            for column in 0 .. 4 {
+                state[row + 4 * ((column + row) % 4)] = original[row + 4 * column];
+            }
+        }
+    }
+
+    fn mix_columns(state : &mut [u8; 16], coefficients : &[u8; 4]) {
+        for column in state.chunks_mut(4) {
+            let original = [column[0], column[1], column[2], column[3]];
+            for row in 0 .. 4 {
+                column[row] = 0;
+                for i in 0 .. 4 {
+                    column[row] ^= gf_multiply(coefficients[(4 + i - row) % 4], original[i]);
+                }
+            }
+        }
+    }
+
+    fn encrypt_block(&self, block : &[u8]) -> Vec<u8> {
+        assert!(block.len() == 16);
+        let mut state = [0u8; 16];
+        state.copy_from_slice(block);
+
+        Aes::add_round_key(&mut state, &self.round_keys[0]);
+        for round in 1 .. self.rounds() + 1 {
+            Aes::substitute_bytes(&mut state, &self.sbox);
+            Aes::shift_rows(&mut state);
+            if round != self.rounds() {
+                Aes::mix_columns(&mut state, &[0x02u8, 0x03u8, 0x01u8, 0x01u8]);
+            }
+            Aes::add_round_key(&mut state, &self.round_keys[round]);
+        }
+
+        state.to_vec()
+    }
+
+    fn decrypt_block(&self, block : &[u8]) -> Vec<u8> {
+        assert!(block.len() == 16);
+        let mut state = [0u8; 16];
+        state.copy_from_slice(block);
+
+        for round in (1 .. self.rounds() + 1).rev() {
+            Aes::add_round_key(&mut state, &self.round_keys[round]);
+            if round != self.rounds() {
+                Aes::mix_columns(&mut state, &[0x0Eu8, 0x0Bu8, 0x0Du8, 0x09u8]);
+            }
+            Aes::inverse_shift_rows(&mut state);
+            Aes::substitute_bytes(&mut state, &self.inverse_sbox);
+        }
+        Aes::add_round_key(&mut state, &self.round_keys[0]);
+
+        state.to_vec()
+    }
+}
+
+trait BlockCipher {
+    fn block_size(&self) -> usize;
+    fn encrypt_block(&self, block : &[u8]) -> Vec<u8>;
+    fn decrypt_block(&self, block : &[u8]) -> Vec<u8>;
+}
+
+impl BlockCipher for Aes {
+    fn block_size(&self) -> usize {
+        16
+    }
+
+    fn encrypt_block(&self, block : &[u8]) -> Vec<u8> {
+        Aes::encrypt_block(self, block)
+    }
+
+    fn decrypt_block(&self, block : &[u8]) -> Vec<u8> {
+        Aes::decrypt_block(self, block)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum CounterLayout {
+    LittleEndian64,
+    BigEndian32,
+}
+
+impl CounterLayout {
+    fn counter_size(&self) -> usize {
+        match *self {
+            CounterLayout::LittleEndian64 => 8,
+            CounterLayout::BigEndian32 => 4,
+        }
+    }
+}
+
+struct Ctr<C : BlockCipher> {
+    cipher : C,
+    nonce : Vec<u8>,
+    layout : CounterLayout,
+    initial_counter : u64,
+}
+
+impl<C : BlockCipher> Ctr<C> {
+    fn new(cipher : C, nonce : &[u8], layout : CounterLayout, initial_counter : u64) -> Result<Ctr<C>, ()> {
+        if nonce.len() + layout.counter_size() != cipher.block_size() {
+            return Err(());
+        }
+        Ok(Ctr {
+            cipher : cipher,
+            nonce : nonce.to_vec(),
+            layout : layout,
+            initial_counter : initial_counter,
+        })
+    }
+
+    fn counter_block(&self, index : u64) -> Vec<u8> {
+        let counter = self.initial_counter.wrapping_add(index);
+        let mut block = self.nonce.clone();
+        match self.layout {
+            CounterLayout::LittleEndian64 => block.extend_from_slice(&counter.to_le_bytes()),
+            CounterLayout::BigEndian32 => block.extend_from_slice(&(counter as u32).to_be_bytes()),
+        }
+        block
+    }
+
+    fn keystream(&self, offset : u64, length : usize) -> Vec<u8> {
+        let block_size = self.cipher.block_size() as u64;
+        let skip = (offset % block_size) as usize;
+        let mut keystream : Vec<u8> = Vec::with_capacity(skip + length + block_size as usize);
+        let mut index = offset / block_size;
+        while keystream.len() < skip + length {
+            keystream.extend(self.cipher.encrypt_block(&self.counter_block(index)));
+            index += 1;
+        }
+        keystream.drain(.. skip);
+        keystream.truncate(length);
+        keystream
+    }
+
+    fn apply(&self, offset : u64, input : &[u8]) -> Vec<u8> {
+        fixed_xor(input, &self.keystream(offset, input.len()))
+    }
+
+    fn edit(&self, ciphertext : &mut Vec<u8>, offset : usize, newtext : &[u8]) {
+        if ciphertext.len() < offset {
+            let gap = self.apply(ciphertext.len() as u64, &vec![0u8; offset - ciphertext.len()]);
+            ciphertext.extend(gap);
+        }
+        if ciphertext.len() < offset + newtext.len() {
+            ciphertext.resize(offset + newtext.len(), 0u8);
+        }
+        let edited = self.apply(offset as u64, newtext);
+        ciphertext[offset .. offset + newtext.len()].copy_from_slice(&edited);
+    }
+}
+
+struct CtrStream<S, C : BlockCipher> {
+    inner : S,
+    ctr : Ctr<C>,
+    position : u64,
+}
+
+impl<S : Seek, C : BlockCipher> CtrStream<S, C> {
+    fn new(inner : S, ctr : Ctr<C>) -> CtrStream<S, C> {
+        CtrStream {
+            inner : inner,
+            ctr : ctr,
+            position : 0,
+        }
+    }
+
+    fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S : Read + Seek, C : BlockCipher> Read for CtrStream<S, C> {
+    fn read(&mut self, buffer : &mut [u8]) -> io::Result<usize> {
+        let count = self.inner.read(buffer)?;
+        let plaintext = self.ctr.apply(self.position, &buffer[.. count]);
+        buffer[.. count].copy_from_slice(&plaintext);
+        self.position += count as u64;
+        Ok(count)
+    }
+}
+
+impl<S : Write + Seek, C : BlockCipher> Write for CtrStream<S, C> {
+    fn write(&mut self, buffer : &[u8]) -> io::Result<usize> {
+        let ciphertext = self.ctr.apply(self.position, buffer);
+        let count = self.inner.write(&ciphertext)?;
+        self.position += count as u64;
+        Ok(count)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl<S : Seek, C : BlockCipher> Seek for CtrStream<S, C> {
+    fn seek(&mut self, position : SeekFrom) -> io::Result<u64> {
+        self.position = self.inner.seek(position)?;
+        Ok(self.position)
+    }
+}
+
+fn edit(ciphertext : &[u8], key : &[u8], offset : usize, newtext : &[u8]) -> Result<Vec<u8>, ()> {
+    let ctr = Ctr::new(Aes::new(key)?, &[0u8; 8], CounterLayout::LittleEndian64, 0)?;
+    let mut edited = ciphertext.to_vec();
+    ctr.edit(&mut edited, offset, newtext);
+    Ok(edited)
+}
+
+trait EditOracle {
+    fn edit(&mut self, ciphertext : &[u8], offset : usize, newtext : &[u8]) -> Vec<u8>;
+}
+
+struct CtrEditOracle {
+    key : Vec<u8>,
+}
+
+impl CtrEditOracle {
+    fn new() -> CtrEditOracle {
+        CtrEditOracle {
+            key : random_octets(16),
+        }
+    }
+
+    fn encrypt(&self, plaintext : &[u8]) -> Vec<u8> {
+        edit(&[], &self.key, 0, plaintext).unwrap()
+    }
+}
+
+impl EditOracle for CtrEditOracle {
+    fn edit(&mut self, ciphertext : &[u8], offset : usize, newtext : &[u8]) -> Vec<u8> {
+        edit(ciphertext, &self.key, offset, newtext).unwrap()
+    }
+}
+
+fn recover_range<O : EditOracle>(oracle : &mut O, ciphertext : &[u8], offset : usize, length : usize) -> Vec<u8> {
+    let end = std::cmp::min(offset + length, ciphertext.len());
+    if offset >= end {
+        return Vec::new();
+    }
+    let known = vec![0x41u8; end - offset];
+    let edited = oracle.edit(ciphertext, offset, &known);
+    let keystream = fixed_xor(&edited[offset .. end], &known);
+    fixed_xor(&ciphertext[offset .. end], &keystream)
+}
+
+fn recover_plaintext<O : EditOracle>(oracle : &mut O, ciphertext : &[u8]) -> Vec<u8> {
+    recover_range(oracle, ciphertext, 0, ciphertext.len())
+}
+
+fn main() {
+    let mut f = File::open("25.txt").unwrap();
+    let mut text = Vec::new();
+    match f.read_to_end(&mut text) {
+        Ok(_) => (),
+        Err(_) => panic!(),
+    };
+    text.retain(|&c| c as char != '\n');
+    let data = base64decode(&text);
+
+    let aes = Aes::new(b"YELLOW SUBMARINE").unwrap();
+    let mut plaintext : Vec<u8> = Vec::with_capacity(data.len());
+    for block in data.chunks(16) {
+        plaintext.extend(aes.decrypt_block(block));
+    }
+
+    let mut oracle = CtrEditOracle::new();
+    let ciphertext = oracle.encrypt(&plaintext);
+    let recovered = recover_plaintext(&mut oracle, &ciphertext);
+    assert!(recovered == plaintext);
+    println!("{}", String::from_utf8_lossy(&recovered));
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::io::Cursor;
+    use std::io::Read;
+    use std::io::Seek;
+    use std::io::SeekFrom;
+    use std::io::Write;
+    use CounterLayout;
+    use EditOracle;
+
+    fn plaintext() -> Vec<u8> {
+        b"I'm back and I'm ringin' the bell \nA rockin' on the mike while the fly girls yell \nIn ecstasy in the back of me \n".to_vec()
+    }
+
+    #[test]
+    fn test_edit() {
+        let key = b"YELLOW SUBMARINE";
+        let ciphertext = ::edit(&[], key, 0, &plaintext()).unwrap();
+        assert!(ciphertext.len() == plaintext().len());
+        assert!(::edit(&ciphertext, &[0u8; 15], 0, b"x").is_err());
+
+        let edited = ::edit(&ciphertext, key, 4, b"BACK").unwrap();
+        assert!(edited.len() == ciphertext.len());
+        assert!(edited[.. 4] == ciphertext[.. 4]);
+        assert!(edited[8 ..] == ciphertext[8 ..]);
+        let mut expected = plaintext();
+        expected[4 .. 8].copy_from_slice(b"BACK");
+        assert!(::edit(&[], key, 0, &expected).unwrap() == edited);
+
+        let extended = ::edit(&ciphertext, key, ciphertext.len() + 3, b"!").unwrap();
+        let mut expected = plaintext();
+        expected.extend_from_slice(b"\0\0\0!");
+        assert!(::edit(&[], key, 0, &expected).unwrap() == extended);
+    }
+
+    #[test]
+    fn test_stream() {
+        let ctr = || ::Ctr::new(::Aes::new(b"YELLOW SUBMARINE").unwrap(), &[0u8; 8], CounterLayout::LittleEndian64, 0).unwrap();
+        let mut stream = ::CtrStream::new(Cursor::new(Vec::new()), ctr());
+        stream.write_all(&plaintext()).unwrap();
+        stream.seek(SeekFrom::Start(35)).unwrap();
+        stream.write_all(b"A ROCKIN'").unwrap();
+        let ciphertext = stream.into_inner().into_inner();
+        let mut expected = plaintext();
+        expected[35 .. 44].copy_from_slice(b"A ROCKIN'");
+        assert!(ciphertext == ::edit(&[], b"YELLOW SUBMARINE", 0, &expected).unwrap());
+
+        let mut stream = ::CtrStream::new(Cursor::new(ciphertext), ctr());
+        stream.seek(SeekFrom::Start(35)).unwrap();
+        let mut buffer = [0u8; 9];
+        stream.read_exact(&mut buffer).unwrap();
+        assert!(&buffer == b"A ROCKIN'");
+        stream.seek(SeekFrom::Start(0)).unwrap();
+        let mut decrypted = Vec::new();
+        stream.read_to_end(&mut decrypted).unwrap();
+        assert!(decrypted == expected);
+    }
+
+    #[test]
+    fn test_recover_plaintext() {
+        let mut oracle = ::CtrEditOracle::new();
+        let ciphertext = oracle.encrypt(&plaintext());
+        assert!(ciphertext != plaintext());
+        assert!(::recover_plaintext(&mut oracle, &ciphertext) == plaintext());
+        assert!(::recover_range(&mut oracle, &ciphertext, 37, 6) == b"rockin".to_vec());
+        assert!(::recover_range(&mut oracle, &ciphertext, 100, 50) == plaintext()[100 ..].to_vec());
+        assert!(::recover_range(&mut oracle, &ciphertext, 200, 1).is_empty());
+        assert!(oracle.edit(&ciphertext, 0, &[]) == ciphertext);
+    }
+}
@@ -0,0 +1,513 @@
+use std::fs::File;
+use std::io::Read;
+
+fn random_octets(count : usize) -> Vec<u8> {
+    let mut f = File::open("/dev/urandom").unwrap();
+    let mut octets = vec![0u8; count];
+    match f.read_exact(&mut octets) {
+        Ok(_) => (),
+        Err(_) => panic!(),
+    };
+    octets
+}
+
+fn pkcs7_pad(input : &[u8], block_size : usize) -> Vec<u8> {
+    let count = block_size - input.len() % block_size;
+    let mut padded : Vec<u8> = Vec::with_capacity(input.len() + count);
+    padded.extend_from_slice(input);
+    padded.extend(vec![count as u8; count]);
+    padded
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum PaddingError {
+    InvalidBlockSize,
+    InvalidLength,
+    InvalidPadding,
+}
+
+fn mask_less_than(left : usize, right : usize) -> u8 {
+    (((left as u32).wrapping_sub(right as u32) >> 31) as u8).wrapping_neg()
+}
+
+fn mask_zero(octet : u8) -> u8 {
+    mask_less_than(octet as usize, 1)
+}
+
+fn validate(input : &[u8], block_size : usize) -> Result<usize, PaddingError> {
+    if block_size == 0 || block_size > 255 {
+        return Err(PaddingError::InvalidBlockSize);
+    }
+    if input.is_empty() || input.len() % block_size != 0 {
+        return Err(PaddingError::InvalidLength);
+    }
+    let last = input[input.len() - 1];
+    let mut invalid = mask_zero(last) | !mask_less_than(last as usize, block_size + 1);
+    for (i, octet) in input[input.len() - block_size ..].iter().rev().enumerate().skip(1) {
+        invalid |= mask_less_than(i, last as usize) & (octet ^ last);
+    }
+    match invalid {
+        0 => Ok(last as usize),
+        _ => Err(PaddingError::InvalidPadding),
+    }
+}
+
+fn pkcs7_unpad(input : &[u8], block_size : usize) -> Result<Vec<u8>, PaddingError> {
+    let count = validate(input, block_size)?;
+    Ok(input[.. input.len() - count].to_vec())
+}
+
+fn fixed_xor(left: &[u8], right: &[u8]) -> Vec<u8> {
+    assert!(left.len() == right.len());
+    let mut xor : Vec<u8> = Vec::with_capacity(left.len());
+    for i in 0 .. left.len() {
+        xor.push(left[i] ^ right[i])
+    }
+    xor
+}
+
+fn gf_multiply(left : u8, right : u8) -> u8 {
+    let mut a = left;
+    let mut b = right;
+    let mut product = 0u8;
+    while b != 0 {
+        if b & 1 == 1 {
+            product ^= a;
+        }
+        a = (a << 1) ^ match a & 0x80u8 {
+            0 => 0x00u8,
+            _ => 0x1Bu8,
+        };
+        b >>= 1;
+    }
+    product
+}
+
+fn gf_inverse(octet : u8) -> u8 {
+    if octet == 0 {
+        return 0;
+    }
+    let mut inverse = 1u8;
+    while gf_multiply(octet, inverse) != 1 {
+        inverse += 1;
+    }
+    inverse
+}
+
+fn sboxes() -> ([u8; 256], [u8; 256]) {
+    let mut sbox = [0u8; 256];
+    let mut inverse_sbox = [0u8; 256];
+    for i in 0 .. 256 {
+        let b = gf_inverse(i as u8);
+        let s = b ^ b.rotate_left(1) ^ b.rotate_left(2) ^ b.rotate_left(3) ^ b.rotate_left(4) ^ 0x63u8;
+        sbox[i] = s;
+        inverse_sbox[s as usize] = i as u8;
+    }
+    (sbox, inverse_sbox)
+}
+
+struct Aes {
+    sbox : [u8; 256],
+    inverse_sbox : [u8; 256],
+    round_keys : Vec<[u8; 16]>,
+}
+
+impl Aes {
+    fn new(key : &[u8]) -> Result<Aes, ()> {
+        let key_words = match key.len() {
+            16 | 24 | 32 => key.len() / 4,
+            _ => return Err(()),
+        };
+        let rounds = key_words + 6;
+        let (sbox, inverse_sbox) = sboxes();
+
+        let mut words : Vec<[u8; 4]> = Vec::with_capacity(4 * (rounds + 1));
+        for chunk in key.chunks(4) {
+            words.push([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        }
+        let mut round_constant = 0x01u8;
+        for i in key_words .. 4 * (rounds + 1) {
+            let mut word = words[i - 1];
+            if i % key_words == 0 {
+                word = [sbox[word[1] as usize] ^ round_constant, sbox[word[2] as usize], sbox[word[3] as usize], sbox[word[0] as usize]];
+                round_constant = gf_multiply(round_constant, 0x02u8);
+            } else if key_words > 6 && i % key_words == 4 {
+                word = [sbox[word[0] as usize], sbox[word[1] as usize], sbox[word[2] as usize], sbox[word[3] as usize]];
+            }
+            let previous = words[i - key_words];
+            words.push([previous[0] ^ word[0], previous[1] ^ word[1], previous[2] ^ word[2], previous[3] ^ word[3]]);
+        }
+
+        let mut round_keys : Vec<[u8; 16]> = Vec::with_capacity(rounds + 1);
+        for chunk in words.chunks(4) {
+            let mut round_key = [0u8; 16];
+            for (i, word) in chunk.iter().enumerate() {
+                round_key[4 * i .. 4 * i + 4].copy_from_slice(word);
+            }
+            round_keys.push(round_key);
+        }
+
+        Ok(Aes {
+            sbox : sbox,
+            inverse_sbox : inverse_sbox,
+            round_keys : round_keys,
+        })
+    }
+
+    fn rounds(&self) -> usize {
+        self.round_keys.len() - 1
+    }
+
+    fn add_round_key(state : &mut [u8; 16], round_key : &[u8; 16]) {
+        for (octet, key) in state.iter_mut().zip(round_key.iter()) {
+            *octet ^= *key;
+        }
+    }
+
+    fn substitute_bytes(state : &mut [u8; 16], sbox : &[u8; 256]) {
+        for octet in state.iter_mut() {
+            *octet = sbox[*octet as usize];
+        }
+    }
+
+    fn shift_rows(state : &mut [u8; 16]) {
+        let original = *state;
+        for row in 1 .. 4 {
+            for column in 0 .. 4 {
+                state[row + 4 * column] = original[row + 4 * ((column + row) % 4)];
+            }
+        }
+    }
+
+    fn inverse_shift_rows(state : &mut [u8; 16]) {
+        let original = *state;
+        for row in 1 .. 4 {
+            for column in 0 .. 4 {
+                state[row + 4 * ((column + row) % 4)] = original[row + 4 * column];
+            }
+        }
+    }
+
+    fn mix_columns(state : &mut [u8; 16], coefficients : &[u8; 4]) {
+        for column in state.chunks_mut(4) {
+            let original = [column[0], column[1], column[2], column[3]];
+            for row in 0 .. 4 {
+                column[row] = 0;
+                for i in 0 .. 4 {
+                    column[row] ^= gf_multiply(coefficients[(4 + i - row) % 4], original[i]);
+                }
+            }
+        }
+    }
+
+    fn encrypt_block(&self, block : &[u8]) -> Vec<u8> {
+        assert!(block.len() == 16);
+        let mut state = [0u8; 16];
+        state.copy_from_slice(block);
+
+        Aes::add_round_key(&mut state, &self.round_keys[0]);
+        for round in 1 .. self.rounds() + 1 {
+            Aes::substitute_bytes(&mut state, &self.sbox);
+            Aes::shift_rows(&mut state);
+            if round != self.rounds() {
+                Aes::mix_columns(&mut state, &[0x02u8, 0x03u8, 0x01u8, 0x01u8]);
+            }
+            Aes::add_round_key(&mut state, &self.round_keys[round]);
+        }
+
+        state.to_vec()
+    }
+
+    fn decrypt_block(&self, block : &[u8]) -> Vec<u8> {
+        assert!(block.len() == 16);
+        let mut state = [0u8; 16];
+        state.copy_from_slice(block);
+
+        for round in (1 .. self.rounds() + 1).rev() {
+            Aes::add_round_key(&mut state, &self.round_keys[round]);
+            if round != self.rounds() {
+                Aes::mix_columns(&mut state, &[0x0Eu8, 0x0Bu8, 0x0Du8, 0x09u8]);
+            }
+            Aes::inverse_shift_rows(&mut state);
+            Aes::substitute_bytes(&mut state, &self.inverse_sbox);
+        }
+        Aes::add_round_key(&mut state, &self.round_keys[0]);
+
+        state.to_vec()
+    }
+}
+
+trait BlockCipher {
+    fn block_size(&self) -> usize;
+    fn encrypt_block(&self, block : &[u8]) -> Vec<u8>;
+    fn decrypt_block(&self, block : &[u8]) -> Vec<u8>;
+}
+
+impl BlockCipher for Aes {
+    fn block_size(&self) -> usize {
+        16
+    }
+
+    fn encrypt_block(&self, block : &[u8]) -> Vec<u8> {
+        Aes::encrypt_block(self, block)
+    }
+
+    fn decrypt_block(&self, block : &[u8]) -> Vec<u8> {
+        Aes::decrypt_block(self, block)
+    }
+}
+
+impl<'a, C : BlockCipher> BlockCipher for &'a C {
+    fn block_size(&self) -> usize {
+        (*self).block_size()
+    }
+
+    fn encrypt_block(&self, block : &[u8]) -> Vec<u8> {
+        (*self).encrypt_block(block)
+    }
+
+    fn decrypt_block(&self, block : &[u8]) -> Vec<u8> {
+        (*self).decrypt_block(block)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Direction {
+    Encrypt,
+    Decrypt,
+}
+
+trait Mode {
+    fn update(&mut self, input : &[u8]) -> Vec<u8>;
+    fn finish(self) -> Result<Vec<u8>, ()>;
+}
+
+fn process<M : Mode>(mut mode : M, input : &[u8]) -> Result<Vec<u8>, ()> {
+    let mut output = mode.update(input);
+    output.extend(mode.finish()?);
+    Ok(output)
+}
+
+fn take_blocks(buffer : &mut Vec<u8>, input : &[u8], block_size : usize) -> Vec<u8> {
+    buffer.extend_from_slice(input);
+    let full = buffer.len() - buffer.len() % block_size;
+    buffer.drain(.. full).collect()
+}
+
+struct Cbc<C : BlockCipher> {
+    cipher : C,
+    direction : Direction,
+    previous : Vec<u8>,
+    buffer : Vec<u8>,
+}
+
+impl<C : BlockCipher> Cbc<C> {
+    fn new(cipher : C, iv : &[u8], direction : Direction) -> Cbc<C> {
+        assert!(iv.len() == cipher.block_size());
+        Cbc {
+            cipher : cipher,
+            direction : direction,
+            previous : iv.to_vec(),
+            buffer : Vec::new(),
+        }
+    }
+}
+
+impl<C : BlockCipher> Mode for Cbc<C> {
+    fn update(&mut self, input : &[u8]) -> Vec<u8> {
+        let blocks = take_blocks(&mut self.buffer, input, self.cipher.block_size());
+        let mut output : Vec<u8> = Vec::with_capacity(blocks.len());
+        for block in blocks.chunks(self.cipher.block_size()) {
+            match self.direction {
+                Direction::Encrypt => {
+                    self.previous = self.cipher.encrypt_block(&fixed_xor(block, &self.previous));
+                    output.extend_from_slice(&self.previous);
+                },
+                Direction::Decrypt => {
+                    output.extend(fixed_xor(&self.cipher.decrypt_block(block), &self.previous));
+                    self.previous = block.to_vec();
+                },
+            }
+        }
+        output
+    }
+
+    fn finish(self) -> Result<Vec<u8>, ()> {
+        match self.buffer.is_empty() {
+            true => Ok(Vec::new()),
+            false => Err(()),
+        }
+    }
+}
+
+trait EncryptionOracle {
+    fn encrypt(&mut self, input : &[u8]) -> Vec<u8>;
+}
+
+#[derive(Clone, PartialEq, Debug)]
+enum DecryptError {
+    InvalidPadding,
+    HighAscii(Vec<u8>),
+}
+
+trait DecryptionOracle {
+    fn decrypt(&mut self, ciphertext : &[u8]) -> Result<(), DecryptError>;
+}
+
+struct UrlOracle {
+    aes : Aes,
+    iv : Vec<u8>,
+}
+
+impl UrlOracle {
+    fn new() -> UrlOracle {
+        let key = random_octets(16);
+        UrlOracle {
+            aes : Aes::new(&key).unwrap(),
+            iv : key,
+        }
+    }
+
+    fn with_random_iv() -> UrlOracle {
+        UrlOracle {
+            aes : Aes::new(&random_octets(16)).unwrap(),
+            iv : random_octets(16),
+        }
+    }
+}
+
+impl EncryptionOracle for UrlOracle {
+    fn encrypt(&mut self, input : &[u8]) -> Vec<u8> {
+        let mut plaintext = b"comment1=cooking%20MCs;userdata=".to_vec();
+        plaintext.extend_from_slice(input);
+        plaintext.extend_from_slice(b";comment2=%20like%20a%20pound%20of%20bacon");
+        process(Cbc::new(&self.aes, &self.iv, Direction::Encrypt), &pkcs7_pad(&plaintext, 16)).unwrap()
+    }
+}
+
+impl DecryptionOracle for UrlOracle {
+    fn decrypt(&mut self, ciphertext : &[u8]) -> Result<(), DecryptError> {
+        let decrypted = process(Cbc::new(&self.aes, &self.iv, Direction::Decrypt), ciphertext).map_err(|_| DecryptError::InvalidPadding)?;
+        let plaintext = pkcs7_unpad(&decrypted, 16).map_err(|_| DecryptError::InvalidPadding)?;
+        match plaintext.iter().any(|octet| *octet >= 0x80u8) {
+            true => Err(DecryptError::HighAscii(plaintext)),
+            false => Ok(()),
+        }
+    }
+}
+
+fn key_recovery_ciphertext(ciphertext : &[u8], block_size : usize) -> Result<Vec<u8>, ()> {
+    if block_size == 0 || ciphertext.len() % block_size != 0 || ciphertext.len() < 3 * block_size {
+        return Err(());
+    }
+    let mut modified : Vec<u8> = Vec::with_capacity(ciphertext.len());
+    modified.extend_from_slice(&ciphertext[.. block_size]);
+    modified.extend(vec![0u8; block_size]);
+    modified.extend_from_slice(&ciphertext[.. block_size]);
+    modified.extend_from_slice(&ciphertext[3 * block_size ..]);
+    Ok(modified)
+}
+
+fn key_from_plaintext(plaintext : &[u8], block_size : usize) -> Result<Vec<u8>, ()> {
+    if plaintext.len() < 3 * block_size {
+        return Err(());
+    }
+    Ok(fixed_xor(&plaintext[.. block_size], &plaintext[2 * block_size .. 3 * block_size]))
+}
+
+fn recover_key<O : EncryptionOracle + DecryptionOracle>(oracle : &mut O, block_size : usize) -> Result<Vec<u8>, ()> {
+    let ciphertext = oracle.encrypt(&vec![0x41u8; 3 * block_size]);
+    match oracle.decrypt(&key_recovery_ciphertext(&ciphertext, block_size)?) {
+        Err(DecryptError::HighAscii(plaintext)) => key_from_plaintext(&plaintext, block_size),
+        _ => Err(()),
+    }
+}
+
+fn detect_iv_equals_key<O : EncryptionOracle + DecryptionOracle>(oracle : &mut O, block_size : usize) -> Option<Vec<u8>> {
+    let key = match recover_key(oracle, block_size) {
+        Ok(key) => key,
+        Err(_) => return None,
+    };
+    let aes = match Aes::new(&key) {
+        Ok(aes) => aes,
+        Err(_) => return None,
+    };
+    let input = vec![0x42u8; block_size];
+    let ciphertext = oracle.encrypt(&input);
+    match process(Cbc::new(&aes, &key, Direction::Decrypt), &ciphertext) {
+        Ok(plaintext) if plaintext.windows(input.len()).any(|window| window == &input[..]) => Some(key),
+        _ => None,
+    }
+}
+
+fn main() {
+    let mut oracle = UrlOracle::new();
+    let key = detect_iv_equals_key(&mut oracle, 16).unwrap();
+    println!("{:?}", key);
+
+    let ciphertext = oracle.encrypt(b"hello");
+    let aes = Aes::new(&key).unwrap();
+    let plaintext = pkcs7_unpad(&process(Cbc::new(&aes, &key, Direction::Decrypt), &ciphertext).unwrap(), 16).unwrap();
+    println!("{}", String::from_utf8_lossy(&plaintext));
+
+    let mut oracle = UrlOracle::with_random_iv();
+    println!("{:?}", detect_iv_equals_key(&mut oracle, 16));
+}
+
+#[cfg(test)]
+mod tests {
+
+    use DecryptError;
+    use DecryptionOracle;
+    use EncryptionOracle;
+    use PaddingError;
+
+    #[test]
+    fn test_pkcs7_unpad() {
+        assert!(::pkcs7_unpad(b"ICE ICE BABY\x04\x04\x04\x04", 16) == Ok(String::from("ICE ICE BABY").into_bytes()));
+        assert!(::pkcs7_unpad(b"ICE ICE BABY\x05\x05\x05\x05", 16) == Err(PaddingError::InvalidPadding));
+        assert!(::pkcs7_unpad(b"ICE ICE BABY\x01\x02\x03\x04", 16) == Err(PaddingError::InvalidPadding));
+        assert!(::pkcs7_unpad(b"ICE ICE BABY\x00", 13) == Err(PaddingError::InvalidPadding));
+        assert!(::pkcs7_unpad(b"ICE ICE BABY", 16) == Err(PaddingError::InvalidLength));
+    }
+
+    #[test]
+    fn test_key_recovery_ciphertext() {
+        assert!(::key_recovery_ciphertext(&[0u8; 32], 16) == Err(()));
+        assert!(::key_recovery_ciphertext(&[0u8; 50], 16) == Err(()));
+        let ciphertext : Vec<u8> = (0u8 .. 64u8).collect();
+        let modified = ::key_recovery_ciphertext(&ciphertext, 16).unwrap();
+        assert!(modified.len() == ciphertext.len());
+        assert!(modified[.. 16] == ciphertext[.. 16]);
+        assert!(modified[16 .. 32] == [0u8; 16]);
+        assert!(modified[32 .. 48] == ciphertext[.. 16]);
+        assert!(modified[48 ..] == ciphertext[48 ..]);
+    }
+
+    #[test]
+    fn test_high_ascii() {
+        let mut oracle = ::UrlOracle::new();
+        let ciphertext = oracle.encrypt(b"plain");
+        assert!(oracle.decrypt(&ciphertext) == Ok(()));
+        let ciphertext = oracle.encrypt(b"caf\xC3\xA9");
+        match oracle.decrypt(&ciphertext) {
+            Err(DecryptError::HighAscii(plaintext)) => assert!(plaintext[32 .. 37] == b"caf\xC3\xA9"[..]),
+            _ => panic!(),
+        }
+        assert!(oracle.decrypt(&ciphertext[.. 40]) == Err(DecryptError::InvalidPadding));
+    }
+
+    #[test]
+    fn test_recover_key() {
+        let mut oracle = ::UrlOracle::new();
+        let key = ::recover_key(&mut oracle, 16).unwrap();
+        assert!(key == oracle.iv);
+        assert!(::detect_iv_equals_key(&mut oracle, 16) == Some(key));
+    }
+
+    #[test]
+    fn test_random_iv() {
+        let mut oracle = ::UrlOracle::with_random_iv();
+        assert!(::detect_iv_equals_key(&mut oracle, 16) == None);
+    }
+}
@@ -0,0 +1,286 @@
+use std::fs::File;
+use std::io::Read;
+
+fn nibble2hex(nibble : u8) -> Result<u8, ()> {
+    match nibble {
+        0u8 ... 9u8 => Ok(nibble + 0x30u8),
+        10u8 ... 15u8 => Ok(nibble + 0x57u8),
+        _ => Err(()),
+    }
+}
+
+fn octets2hex(octets: &[u8]) -> Vec<u8> {
+    let mut hex : Vec<u8> = Vec::with_capacity(octets.len() * 2);
+    for octet in octets {
+        hex.push(nibble2hex((octet & 0xF0u8) >> 4).unwrap());
+        hex.push(nibble2hex(octet & 0x0Fu8).unwrap());
+    }
+    hex
+}
+
+fn random_octets(count : usize) -> Vec<u8> {
+    let mut f = File::open("/dev/urandom").unwrap();
+    let mut octets = vec![0u8; count];
+    match f.read_exact(&mut octets) {
+        Ok(_) => (),
+        Err(_) => panic!(),
+    };
+    octets
+}
+
+fn sha1_padding(length : u64) -> Vec<u8> {
+    let mut padding = vec![0x80u8];
+    while (length as usize + padding.len()) % 64 != 56 {
+        padding.push(0u8);
+    }
+    padding.extend_from_slice(&(length.wrapping_mul(8)).to_be_bytes());
+    padding
+}
+
+#[derive(Clone, Debug)]
+struct Sha1 {
+    state : [u32; 5],
+    buffer : Vec<u8>,
+    length : u64,
+}
+
+impl Sha1 {
+    fn new() -> Sha1 {
+        Sha1::with_state([0x67452301u32, 0xEFCDAB89, 0x98BADCFE, 0x10325476, 0xC3D2E1F0], 0)
+    }
+
+    fn with_state(state : [u32; 5], length : u64) -> Sha1 {
+        Sha1 {
+            state : state,
+            buffer : Vec::with_capacity(64),
+            length : length,
+        }
+    }
+
+    fn compress(state : &mut [u32; 5], block : &[u8]) {
+        let mut w = [0u32; 80];
+        for (i, chunk) in block.chunks(4).enumerate() {
+            w[i] = u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        }
+        for i in 16 .. 80 {
+            w[i] = (w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16]).rotate_left(1);
+        }
+
+        let (mut a, mut b, mut c, mut d, mut e) = (state[0], state[1], state[2], state[3], state[4]);
+        for i in 0 .. 80 {
+            let (f, k) = match i {
+                0 ... 19 => ((b & c) | (!b & d), 0x5A827999u32),
+                20 ... 39 => (b ^ c ^ d, 0x6ED9EBA1u32),
+                40 ... 59 => ((b & c) | (b & d) | (c & d), 0x8F1BBCDCu32),
+                _ => (b ^ c ^ d, 0xCA62C1D6u32),
+            };
+            let temp = a.rotate_left(5).wrapping_add(f).wrapping_add(e).wrapping_add(k).wrapping_add(w[i]);
+            e = d;
+            d = c;
+            c = b.rotate_left(30);
+            b = a;
+            a = temp;
+        }
+        state[0] = state[0].wrapping_add(a);
+        state[1] = state[1].wrapping_add(b);
+        state[2] = state[2].wrapping_add(c);
+        state[3] = state[3].wrapping_add(d);
+        state[4] = state[4].wrapping_add(e);
+    }
+
+    fn update(&mut self, input : &[u8]) {
+        self.length = self.length.wrapping_add(input.len() as u64);
+        self.buffer.extend_from_slice(input);
+        let blocks = self.buffer.len() / 64;
+        for block in self.buffer[.. blocks * 64].chunks(64) {
+            Sha1::compress(&mut self.state, block);
+        }
+        self.buffer.drain(.. blocks * 64);
+    }
+
+    fn finalize(mut self) -> Vec<u8> {
+        let padding = sha1_padding(self.length);
+        let length = self.length;
+        self.update(&padding);
+        self.length = length;
+        assert!(self.buffer.is_empty());
+        let mut digest : Vec<u8> = Vec::with_capacity(20);
+        for word in self.state.iter() {
+            digest.extend_from_slice(&word.to_be_bytes());
+        }
+        digest
+    }
+}
+
+fn sha1(input : &[u8]) -> Vec<u8> {
+    let mut sha1 = Sha1::new();
+    sha1.update(input);
+    sha1.finalize()
+}
+
+fn state_from_digest(digest : &[u8]) -> Result<[u32; 5], ()> {
+    if digest.len() != 20 {
+        return Err(());
+    }
+    let mut state = [0u32; 5];
+    for (word, chunk) in state.iter_mut().zip(digest.chunks(4)) {
+        *word = u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+    }
+    Ok(state)
+}
+
+fn secret_prefix_mac(key : &[u8], message : &[u8]) -> Vec<u8> {
+    let mut sha1 = Sha1::new();
+    sha1.update(key);
+    sha1.update(message);
+    sha1.finalize()
+}
+
+trait MacVerifier {
+    fn verify(&mut self, message : &[u8], mac : &[u8]) -> bool;
+}
+
+struct MacOracle {
+    key : Vec<u8>,
+}
+
+impl MacOracle {
+    fn new() -> MacOracle {
+        let length = random_octets(1)[0] as usize % 32 + 1;
+        MacOracle {
+            key : random_octets(length),
+        }
+    }
+
+    fn sign(&self, message : &[u8]) -> Vec<u8> {
+        secret_prefix_mac(&self.key, message)
+    }
+}
+
+impl MacVerifier for MacOracle {
+    fn verify(&mut self, message : &[u8], mac : &[u8]) -> bool {
+        secret_prefix_mac(&self.key, message) == mac
+    }
+}
+
+fn length_extension(message : &[u8], mac : &[u8], key_length : usize, extension : &[u8]) -> Result<(Vec<u8>, Vec<u8>), ()> {
+    let state = state_from_digest(mac)?;
+    let glue = sha1_padding((key_length + message.len()) as u64);
+    let mut forged_message = message.to_vec();
+    forged_message.extend(glue);
+    let mut sha1 = Sha1::with_state(state, (key_length + forged_message.len()) as u64);
+    sha1.update(extension);
+    forged_message.extend_from_slice(extension);
+    Ok((forged_message, sha1.finalize()))
+}
+
+fn forge<V : MacVerifier>(verifier : &mut V, message : &[u8], mac : &[u8], extension : &[u8], max_key_length : usize) -> Option<(Vec<u8>, Vec<u8>, usize)> {
+    for key_length in 0 .. max_key_length + 1 {
+        let (forged_message, forged_mac) = match length_extension(message, mac, key_length, extension) {
+            Ok(forgery) => forgery,
+            Err(_) => return None,
+        };
+        if verifier.verify(&forged_message, &forged_mac) {
+            return Some((forged_message, forged_mac, key_length));
+        }
+    }
+    None
+}
+
+fn main() {
+    println!("{}", String::from_utf8(octets2hex(&sha1(b"abc"))).unwrap());
+
+    let mut oracle = MacOracle::new();
+    let message = b"comment1=cooking%20MCs;userdata=foo;comment2=%20like%20a%20pound%20of%20bacon";
+    let mac = oracle.sign(message);
+    let (forged_message, forged_mac, key_length) = forge(&mut oracle, message, &mac, b";admin=true", 64).unwrap();
+    println!("{}", key_length);
+    println!("{:?}", String::from_utf8_lossy(&forged_message));
+    println!("{}", String::from_utf8(octets2hex(&forged_mac)).unwrap());
+}
+
+#[cfg(test)]
+mod tests {
+
+    use MacVerifier;
+
+    fn hex(octets : &[u8]) -> String {
+        String::from_utf8(::octets2hex(octets)).unwrap()
+    }
+
+    #[test]
+    fn test_vectors() {
+        assert!(hex(&::sha1(b"")) == "da39a3ee5e6b4b0d3255bfef95601890afd80709");
+        assert!(hex(&::sha1(b"abc")) == "a9993e364706816aba3e25717850c26c9cd0d89d");
+        assert!(hex(&::sha1(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq")) == "84983e441c3bd26ebaae4aa1f95129e5e54670f1");
+        assert!(hex(&::sha1(b"The quick brown fox jumps over the lazy dog")) == "2fd4e1c67a2d28fced849ee1bb76e7391b93eb12");
+        let mut sha1 = ::Sha1::new();
+        for _ in 0 .. 1000 {
+            sha1.update(&[0x61u8; 1000]);
+        }
+        assert!(hex(&sha1.finalize()) == "34aa973cd4c4daa4f61eeb2bdbad27316534016f");
+    }
+
+    #[test]
+    fn test_streaming() {
+        let input : Vec<u8> = (0 .. 300).map(|i| i as u8).collect();
+        for split in vec![0usize, 1, 55, 56, 63, 64, 65, 128, 300] {
+            let mut sha1 = ::Sha1::new();
+            sha1.update(&input[.. split]);
+            sha1.update(&input[split ..]);
+            assert!(sha1.finalize() == ::sha1(&input));
+        }
+    }
+
+    #[test]
+    fn test_padding() {
+        assert!(::sha1_padding(0).len() == 64);
+        assert!(::sha1_padding(3) == {
+            let mut padding = vec![0x80u8];
+            padding.extend(vec![0u8; 52]);
+            padding.extend_from_slice(&[0u8, 0, 0, 0, 0, 0, 0, 0x18]);
+            padding
+        });
+        for length in 0 .. 200u64 {
+            assert!((length as usize + ::sha1_padding(length).len()) % 64 == 0);
+        }
+    }
+
+    #[test]
+    fn test_custom_state() {
+        let mut padded = b"abc".to_vec();
+        padded.extend(::sha1_padding(3));
+        let state = ::state_from_digest(&::sha1(b"abc")).unwrap();
+        let mut sha1 = ::Sha1::with_state(state, padded.len() as u64);
+        sha1.update(b"def");
+        padded.extend_from_slice(b"def");
+        assert!(sha1.finalize() == ::sha1(&padded));
+        assert!(::state_from_digest(&[0u8; 19]).is_err());
+    }
+
+    #[test]
+    fn test_length_extension() {
+        let key = b"YELLOW SUBMARINE";
+        let message = b"comment1=cooking%20MCs;userdata=foo";
+        let mac = ::secret_prefix_mac(key, message);
+        let (forged_message, forged_mac) = ::length_extension(message, &mac, key.len(), b";admin=true").unwrap();
+        assert!(forged_message.starts_with(message));
+        assert!(forged_message.ends_with(b";admin=true"));
+        assert!(::secret_prefix_mac(key, &forged_message) == forged_mac);
+    }
+
+    #[test]
+    fn test_forge() {
+        let mut oracle = ::MacOracle::new();
+        let message = b"comment1=cooking%20MCs;userdata=foo;comment2=%20like%20a%20pound%20of%20bacon";
+        let mac = oracle.sign(message);
+        assert!(oracle.verify(message, &mac));
+        assert!(!oracle.verify(b";admin=true", &mac));
+        let (forged_message, forged_mac, key_length) = ::forge(&mut oracle, message, &mac, b";admin=true", 64).unwrap();
+        assert!(key_length == oracle.key.len());
+        assert!(forged_message.ends_with(b";admin=true"));
+        assert!(oracle.verify(&forged_message, &forged_mac));
+        let max_key_length = key_length - 1;
+        assert!(::forge(&mut oracle, message, &mac, b";admin=true", max_key_length).is_none());
+    }
+}
@@ -0,0 +1,282 @@
+use std::fs::File;
+use std::io::Read;
+
+fn nibble2hex(nibble : u8) -> Result<u8, ()> {
+    match nibble {
+        0u8 ... 9u8 => Ok(nibble + 0x30u8),
+        10u8 ... 15u8 => Ok(nibble + 0x57u8),
+        _ => Err(()),
+    }
+}
+
+fn octets2hex(octets: &[u8]) -> Vec<u8> {
+    let mut hex : Vec<u8> = Vec::with_capacity(octets.len() * 2);
+    for octet in octets {
+        hex.push(nibble2hex((octet & 0xF0u8) >> 4).unwrap());
+        hex.push(nibble2hex(octet & 0x0Fu8).unwrap());
+    }
+    hex
+}
+
+fn random_octets(count : usize) -> Vec<u8> {
+    let mut f = File::open("/dev/urandom").unwrap();
+    let mut octets = vec![0u8; count];
+    match f.read_exact(&mut octets) {
+        Ok(_) => (),
+        Err(_) => panic!(),
+    };
+    octets
+}
+
+fn md4_padding(length : u64) -> Vec<u8> {
+    let mut padding = vec![0x80u8];
+    while (length as usize + padding.len()) % 64 != 56 {
+        padding.push(0u8);
+    }
+    padding.extend_from_slice(&(length.wrapping_mul(8)).to_le_bytes());
+    padding
+}
+
+#[derive(Clone, Debug)]
+struct Md4 {
+    state : [u32; 4],
+    buffer : Vec<u8>,
+    length : u64,
+}
+
+impl Md4 {
+    fn new() -> Md4 {
+        Md4::with_state([0x67452301u32, 0xEFCDAB89, 0x98BADCFE, 0x10325476], 0)
+    }
+
+    fn with_state(state : [u32; 4], length : u64) -> Md4 {
+        Md4 {
+            state : state,
+            buffer : Vec::with_capacity(64),
+            length : length,
+        }
+    }
+
+    fn compress(state : &mut [u32; 4], block : &[u8]) {
+        let mut x = [0u32; 16];
+        for (i, chunk) in block.chunks(4).enumerate() {
+            x[i] = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        }
+
+        let rounds : [([usize; 16], [u32; 4], u32); 3] = [
+            ([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15], [3, 7, 11, 19], 0x00000000u32),
+            ([0, 4, 8, 12, 1, 5, 9, 13, 2, 6, 10, 14, 3, 7, 11, 15], [3, 5, 9, 13], 0x5A827999u32),
+            ([0, 8, 4, 12, 2, 10, 6, 14, 1, 9, 5, 13, 3, 11, 7, 15], [3, 9, 11, 15], 0x6ED9EBA1u32),
+        ];
+        let mut h = *state;
+        for (round, &(ref order, ref shifts, constant)) in rounds.iter().enumerate() {
+            for i in 0 .. 16 {
+                let j = (4 - i % 4) % 4;
+                let (b, c, d) = (h[(j + 1) % 4], h[(j + 2) % 4], h[(j + 3) % 4]);
+                let f = match round {
+                    0 => (b & c) | (!b & d),
+                    1 => (b & c) | (b & d) | (c & d),
+                    _ => b ^ c ^ d,
+                };
+                h[j] = h[j].wrapping_add(f).wrapping_add(x[order[i]]).wrapping_add(constant).rotate_left(shifts[i % 4]);
+            }
+        }
+        for (word, value) in state.iter_mut().zip(h.iter()) {
+            *word = word.wrapping_add(*value);
+        }
+    }
+
+    fn update(&mut self, input : &[u8]) {
+        self.length = self.length.wrapping_add(input.len() as u64);
+        self.buffer.extend_from_slice(input);
+        let blocks = self.buffer.len() / 64;
+        for block in self.buffer[.. blocks * 64].chunks(64) {
+            Md4::compress(&mut self.state, block);
+        }
+        self.buffer.drain(.. blocks * 64);
+    }
+
+    fn finalize(mut self) -> Vec<u8> {
+        let padding = md4_padding(self.length);
+        let length = self.length;
+        self.update(&padding);
+        self.length = length;
+        assert!(self.buffer.is_empty());
+        let mut digest : Vec<u8> = Vec::with_capacity(16);
+        for word in self.state.iter() {
+            digest.extend_from_slice(&word.to_le_bytes());
+        }
+        digest
+    }
+}
+
+fn md4(input : &[u8]) -> Vec<u8> {
+    let mut md4 = Md4::new();
+    md4.update(input);
+    md4.finalize()
+}
+
+fn state_from_digest(digest : &[u8]) -> Result<[u32; 4], ()> {
+    if digest.len() != 16 {
+        return Err(());
+    }
+    let mut state = [0u32; 4];
+    for (word, chunk) in state.iter_mut().zip(digest.chunks(4)) {
+        *word = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+    }
+    Ok(state)
+}
+
+fn secret_prefix_mac(key : &[u8], message : &[u8]) -> Vec<u8> {
+    let mut md4 = Md4::new();
+    md4.update(key);
+    md4.update(message);
+    md4.finalize()
+}
+
+trait MacVerifier {
+    fn verify(&mut self, message : &[u8], mac : &[u8]) -> bool;
+}
+
+struct MacOracle {
+    key : Vec<u8>,
+}
+
+impl MacOracle {
+    fn new() -> MacOracle {
+        let length = random_octets(1)[0] as usize % 32 + 1;
+        MacOracle {
+            key : random_octets(length),
+        }
+    }
+
+    fn sign(&self, message : &[u8]) -> Vec<u8> {
+        secret_prefix_mac(&self.key, message)
+    }
+}
+
+impl MacVerifier for MacOracle {
+    fn verify(&mut self, message : &[u8], mac : &[u8]) -> bool {
+        secret_prefix_mac(&self.key, message) == mac
+    }
+}
+
+fn length_extension(message : &[u8], mac : &[u8], key_length : usize, extension : &[u8]) -> Result<(Vec<u8>, Vec<u8>), ()> {
+    let state = state_from_digest(mac)?;
+    let glue = md4_padding((key_length + message.len()) as u64);
+    let mut forged_message = message.to_vec();
+    forged_message.extend(glue);
+    let mut md4 = Md4::with_state(state, (key_length + forged_message.len()) as u64);
+    md4.update(extension);
+    forged_message.extend_from_slice(extension);
+    Ok((forged_message, md4.finalize()))
+}
+
+fn forge<V : MacVerifier>(verifier : &mut V, message : &[u8], mac : &[u8], extension : &[u8], max_key_length : usize) -> Option<(Vec<u8>, Vec<u8>, usize)> {
+    for key_length in 0 .. max_key_length + 1 {
+        let (forged_message, forged_mac) = match length_extension(message, mac, key_length, extension) {
+            Ok(forgery) => forgery,
+            Err(_) => return None,
+        };
+        if verifier.verify(&forged_message, &forged_mac) {
+            return Some((forged_message, forged_mac, key_length));
+        }
+    }
+    None
+}
+
+fn main() {
+    println!("{}", String::from_utf8(octets2hex(&md4(b"abc"))).unwrap());
+
+    let mut oracle = MacOracle::new();
+    let message = b"comment1=cooking%20MCs;userdata=foo;comment2=%20like%20a%20pound%20of%20bacon";
+    let mac = oracle.sign(message);
+    let (forged_message, forged_mac, key_length) = forge(&mut oracle, message, &mac, b";admin=true", 64).unwrap();
+    println!("{}", key_length);
+    println!("{:?}", String::from_utf8_lossy(&forged_message));
+    println!("{}", String::from_utf8(octets2hex(&forged_mac)).unwrap());
+}
+
+#[cfg(test)]
+mod tests {
+
+    use MacVerifier;
+
+    fn hex(octets : &[u8]) -> String {
+        String::from_utf8(::octets2hex(octets)).unwrap()
+    }
+
+    #[test]
+    fn test_vectors() {
+        assert!(hex(&::md4(b"")) == "31d6cfe0d16ae931b73c59d7e0c089c0");
+        assert!(hex(&::md4(b"a")) == "bde52cb31de33e46245e05fbdbd6fb24");
+        assert!(hex(&::md4(b"abc")) == "a448017aaf21d8525fc10ae87aa6729d");
+        assert!(hex(&::md4(b"message digest")) == "d9130a8164549fe818874806e1c7014b");
+        assert!(hex(&::md4(b"abcdefghijklmnopqrstuvwxyz")) == "d79e1c308aa5bbcdeea8ed63df412da9");
+        assert!(hex(&::md4(b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789")) == "043f8582f241db351ce627e153e7f0e4");
+        assert!(hex(&::md4(b"12345678901234567890123456789012345678901234567890123456789012345678901234567890")) == "e33b4ddc9c38f2199c3e7b164fcc0536");
+    }
+
+    #[test]
+    fn test_streaming() {
+        let input : Vec<u8> = (0 .. 300).map(|i| i as u8).collect();
+        for split in vec![0usize, 1, 55, 56, 63, 64, 65, 128, 300] {
+            let mut md4 = ::Md4::new();
+            md4.update(&input[.. split]);
+            md4.update(&input[split ..]);
+            assert!(md4.finalize() == ::md4(&input));
+        }
+    }
+
+    #[test]
+    fn test_padding() {
+        assert!(::md4_padding(0).len() == 64);
+        assert!(::md4_padding(3) == {
+            let mut padding = vec![0x80u8];
+            padding.extend(vec![0u8; 52]);
+            padding.extend_from_slice(&[0x18u8, 0, 0, 0, 0, 0, 0, 0]);
+            padding
+        });
+        for length in 0 .. 200u64 {
+            assert!((length as usize + ::md4_padding(length).len()) % 64 == 0);
+        }
+    }
+
+    #[test]
+    fn test_custom_state() {
+        let mut padded = b"abc".to_vec();
+        padded.extend(::md4_padding(3));
+        let state = ::state_from_digest(&::md4(b"abc")).unwrap();
+        let mut md4 = ::Md4::with_state(state, padded.len() as u64);
+        md4.update(b"def");
+        padded.extend_from_slice(b"def");
+        assert!(md4.finalize() == ::md4(&padded));
+        assert!(::state_from_digest(&[0u8; 20]).is_err());
+    }
+
+    #[test]
+    fn test_length_extension() {
+        let key = b"YELLOW SUBMARINE";
+        let message = b"comment1=cooking%20MCs;userdata=foo";
+        let mac = ::secret_prefix_mac(key, message);
+        let (forged_message, forged_mac) = ::length_extension(message, &mac, key.len(), b";admin=true").unwrap();
+        assert!(forged_message.starts_with(message));
+        assert!(forged_message.ends_with(b";admin=true"));
+        assert!(::secret_prefix_mac(key, &forged_message) == forged_mac);
+    }
+
+    #[test]
+    fn test_forge() {
+        let mut oracle = ::MacOracle::new();
+        let message = b"comment1=cooking%20MCs;userdata=foo;comment2=%20like%20a%20pound%20of%20bacon";
+        let mac = oracle.sign(message);
+        assert!(oracle.verify(message, &mac));
+        assert!(!oracle.verify(b";admin=true", &mac));
+        let (forged_message, forged_mac, key_length) = ::forge(&mut oracle, message, &mac, b";admin=true", 64).unwrap();
+        assert!(key_length == oracle.key.len());
+        assert!(forged_message.ends_with(b";admin=true"));
+        assert!(oracle.verify(&forged_message, &forged_mac));
+        let max_key_length = key_length - 1;
+        assert!(::forge(&mut oracle, message, &mac, b";admin=true", max_key_length).is_none());
+    }
+}
@@ -0,0 +1,548 @@
+use std::fs::File;
+use std::io;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::io::Read;
+use std::io::Write;
+use std::net::SocketAddr;
+use std::net::TcpListener;
+use std::net::TcpStream;
+use std::thread;
+use std::time::Duration;
+use std::time::Instant;
+
+fn nibble2hex(nibble : u8) -> Result<u8, ()> {
+    match nibble {
+        0u8 ... 9u8 => Ok(nibble + 0x30u8),
+        10u8 ... 15u8 => Ok(nibble + 0x57u8),
+        _ => Err(()),
+    }
+}
+
+fn hex2nibble(hex : u8) -> Result<u8, ()> {
+    match hex {
+        0x30u8 ... 0x39u8 => Ok(hex - 0x30u8),
+        0x41u8 ... 0x46u8 | 0x61u8 ... 0x66u8 => Ok((hex & !0x20u8) - 0x37),
+        _ => Err(()),
+    }
+}
+
+fn hex2octets(hex: &[u8]) -> Result<Vec<u8>, ()> {
+    let mut octets : Vec<u8> = Vec::with_capacity(&hex.len() / 2 + &hex.len() % 2);
+    if hex.len() % 2 == 1 {
+        octets.push(hex2nibble(hex[0])?)
+    }
+    for octet in hex[&hex.len() % 2 ..].chunks(2) {
+        octets.push((hex2nibble(octet[0])? << 4) + hex2nibble(octet[1])?)
+    }
+    Ok(octets)
+}
+
+fn octets2hex(octets: &[u8]) -> Vec<u8> {
+    let mut hex : Vec<u8> = Vec::with_capacity(octets.len() * 2);
+    for octet in octets {
+        hex.push(nibble2hex((octet & 0xF0u8) >> 4).unwrap());
+        hex.push(nibble2hex(octet & 0x0Fu8).unwrap());
+    }
+    hex
+}
+
+fn random_octets(count : usize) -> Vec<u8> {
+    let mut f = File::open("/dev/urandom").unwrap();
+    let mut octets = vec![0u8; count];
+    match f.read_exact(&mut octets) {
+        Ok(_) => (),
+        Err(_) => panic!(),
+    };
+    octets
+}
+
+fn sha1_padding(length : u64) -> Vec<u8> {
+    let mut padding = vec![0x80u8];
+    while (length as usize + padding.len()) % 64 != 56 {
+        padding.push(0u8);
+    }
+    padding.extend_from_slice(&(length.wrapping_mul(8)).to_be_bytes());
+    padding
+}
+
+#[derive(Clone, Debug)]
+struct Sha1 {
+    state : [u32; 5],
+    buffer : Vec<u8>,
+    length : u64,
+}
+
+impl Sha1 {
+    fn new() -> Sha1 {
+        Sha1 {
+            state : [0x67452301u32, 0xEFCDAB89, 0x98BADCFE, 0x10325476, 0xC3D2E1F0],
+            buffer : Vec::with_capacity(64),
+            length : 0,
+        }
+    }
+
+    fn compress(state : &mut [u32; 5], block : &[u8]) {
+        let mut w = [0u32; 80];
+        for (i, chunk) in block.chunks(4).enumerate() {
+            w[i] = u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        }
+        for i in 16 .. 80 {
+            w[i] = (w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16]).rotate_left(1);
+        }
+
+        let (mut a, mut b, mut c, mut d, mut e) = (state[0], state[1], state[2], state[3], state[4]);
+        for i in 0 .. 80 {
+            let (f, k) = match i {
+                0 ... 19 => ((b & c) | (!b & d), 0x5A827999u32),
+                20 ... 39 => (b ^ c ^ d, 0x6ED9EBA1u32),
+                40 ... 59 => ((b & c) | (b & d) | (c & d), 0x8F1BBCDCu32),
+                _ => (b ^ c ^ d, 0xCA62C1D6u32),
+            };
+            let temp = a.rotate_left(5).wrapping_add(f).wrapping_add(e).wrapping_add(k).wrapping_add(w[i]);
+            e = d;
+            d = c;
+            c = b.rotate_left(30);
+            b = a;
+            a = temp;
+        }
+        state[0] = state[0].wrapping_add(a);
+        state[1] = state[1].wrapping_add(b);
+        state[2] = state[2].wrapping_add(c);
+        state[3] = state[3].wrapping_add(d);
+        state[4] = state[4].wrapping_add(e);
+    }
+
+    fn update(&mut self, input : &[u8]) {
+        self.length = self.length.wrapping_add(input.len() as u64);
+        self.buffer.extend_from_slice(input);
+        let blocks = self.buffer.len() / 64;
+        for block in self.buffer[.. blocks * 64].chunks(64) {
+            Sha1::compress(&mut self.state, block);
+        }
+        self.buffer.drain(.. blocks * 64);
+    }
+
+    fn finalize(mut self) -> Vec<u8> {
+        let padding = sha1_padding(self.length);
+        self.update(&padding);
+        assert!(self.buffer.is_empty());
+        let mut digest : Vec<u8> = Vec::with_capacity(20);
+        for word in self.state.iter() {
+            digest.extend_from_slice(&word.to_be_bytes());
+        }
+        digest
+    }
+}
+
+fn sha1(input : &[u8]) -> Vec<u8> {
+    let mut sha1 = Sha1::new();
+    sha1.update(input);
+    sha1.finalize()
+}
+
+fn hmac_sha1(key : &[u8], message : &[u8]) -> Vec<u8> {
+    let mut padded_key = match key.len() > 64 {
+        true => sha1(key),
+        false => key.to_vec(),
+    };
+    padded_key.resize(64, 0u8);
+
+    let mut inner = Sha1::new();
+    inner.update(&padded_key.iter().map(|octet| octet ^ 0x36u8).collect::<Vec<u8>>());
+    inner.update(message);
+    let mut outer = Sha1::new();
+    outer.update(&padded_key.iter().map(|octet| octet ^ 0x5Cu8).collect::<Vec<u8>>());
+    outer.update(&inner.finalize());
+    outer.finalize()
+}
+
+fn percent_encode(input : &[u8]) -> Vec<u8> {
+    let mut encoded : Vec<u8> = Vec::with_capacity(input.len());
+    for octet in input {
+        match *octet {
+            0x30u8 ... 0x39u8 | 0x41u8 ... 0x5Au8 | 0x61u8 ... 0x7Au8 | 0x2Du8 | 0x2Eu8 | 0x5Fu8 | 0x7Eu8 => encoded.push(*octet),
+            _ => {
+                encoded.push(0x25u8);
+                encoded.push(nibble2hex((octet & 0xF0u8) >> 4).unwrap());
+                encoded.push(nibble2hex(octet & 0x0Fu8).unwrap());
+            },
+        }
+    }
+    encoded
+}
+
+fn percent_decode(input : &[u8]) -> Result<Vec<u8>, ()> {
+    let mut decoded : Vec<u8> = Vec::with_capacity(input.len());
+    let mut octets = input.iter();
+    while let Some(octet) = octets.next() {
+        match *octet {
+            0x25u8 => {
+                let high = hex2nibble(*octets.next().ok_or(())?)?;
+                let low = hex2nibble(*octets.next().ok_or(())?)?;
+                decoded.push((high << 4) + low);
+            },
+            _ => decoded.push(*octet),
+        }
+    }
+    Ok(decoded)
+}
+
+fn insecure_compare(left : &[u8], right : &[u8], delay : Duration) -> bool {
+    for (a, b) in left.iter().zip(right.iter()) {
+        if a != b {
+            return false;
+        }
+        thread::sleep(delay);
+    }
+    left.len() == right.len()
+}
+
+struct SignatureServer {
+    key : Vec<u8>,
+    delay : Duration,
+    length : usize,
+}
+
+impl SignatureServer {
+    fn new(delay : Duration, length : usize) -> Result<SignatureServer, ()> {
+        if length == 0 || length > 20 {
+            return Err(());
+        }
+        Ok(SignatureServer {
+            key : random_octets(16),
+            delay : delay,
+            length : length,
+        })
+    }
+
+    fn sign(&self, file : &[u8]) -> Vec<u8> {
+        let mut signature = hmac_sha1(&self.key, file);
+        signature.truncate(self.length);
+        signature
+    }
+
+    fn check(&self, file : &[u8], signature : &[u8]) -> bool {
+        insecure_compare(&self.sign(file), signature, self.delay)
+    }
+}
+
+trait TimingOracle {
+    fn query(&mut self, file : &[u8], signature : &[u8]) -> io::Result<(bool, Duration)>;
+}
+
+impl TimingOracle for SignatureServer {
+    fn query(&mut self, file : &[u8], signature : &[u8]) -> io::Result<(bool, Duration)> {
+        let start = Instant::now();
+        let valid = self.check(file, signature);
+        Ok((valid, start.elapsed()))
+    }
+}
+
+fn query_parameter<'a>(query : &'a str, name : &str) -> Option<&'a str> {
+    query.split('&').filter_map(|pair| {
+        let mut parts = pair.splitn(2, '=');
+        match (parts.next(), parts.next()) {
+            (Some(key), Some(value)) if key == name => Some(value),
+            _ => None,
+        }
+    }).next()
+}
+
+fn handle_request(server : &SignatureServer, request : &str) -> &'static str {
+    let target = match request.split(' ').nth(1) {
+        Some(target) => target,
+        None => return "400 Bad Request",
+    };
+    let query = target.splitn(2, '?').nth(1).unwrap_or("");
+    let file = query_parameter(query, "file").ok_or(()).and_then(|file| percent_decode(file.as_bytes()));
+    let signature = query_parameter(query, "signature").ok_or(()).and_then(|signature| hex2octets(signature.as_bytes()));
+    match (file, signature) {
+        (Ok(file), Ok(signature)) => match server.check(&file, &signature) {
+            true => "200 OK",
+            false => "500 Internal Server Error",
+        },
+        _ => "400 Bad Request",
+    }
+}
+
+fn skip_headers<R : BufRead>(reader : &mut R) -> io::Result<()> {
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 || header.trim().is_empty() {
+            return Ok(());
+        }
+    }
+}
+
+fn serve_connection(server : &SignatureServer, stream : TcpStream) -> io::Result<()> {
+    stream.set_nodelay(true)?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut writer = stream;
+    loop {
+        let mut request = String::new();
+        if reader.read_line(&mut request)? == 0 {
+            return Ok(());
+        }
+        skip_headers(&mut reader)?;
+        let status = handle_request(server, request.trim());
+        writer.write_all(format!("HTTP/1.1 {}\r\nContent-Length: 0\r\n\r\n", status).as_bytes())?;
+    }
+}
+
+fn serve(server : SignatureServer) -> io::Result<SocketAddr> {
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    let address = listener.local_addr()?;
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            if let Ok(stream) = stream {
+                let _ = serve_connection(&server, stream);
+            }
+        }
+    });
+    Ok(address)
+}
+
+struct HttpTimingOracle {
+    reader : BufReader<TcpStream>,
+    writer : TcpStream,
+}
+
+impl HttpTimingOracle {
+    fn connect(address : SocketAddr) -> io::Result<HttpTimingOracle> {
+        let stream = TcpStream::connect(address)?;
+        stream.set_nodelay(true)?;
+        Ok(HttpTimingOracle {
+            reader : BufReader::new(stream.try_clone()?),
+            writer : stream,
+        })
+    }
+}
+
+impl TimingOracle for HttpTimingOracle {
+    fn query(&mut self, file : &[u8], signature : &[u8]) -> io::Result<(bool, Duration)> {
+        let request = format!("GET /test?file={}&signature={} HTTP/1.1\r\nHost: localhost\r\n\r\n", String::from_utf8(percent_encode(file)).unwrap(), String::from_utf8(octets2hex(signature)).unwrap());
+        let start = Instant::now();
+        self.writer.write_all(request.as_bytes())?;
+
+        let mut status = String::new();
+        self.reader.read_line(&mut status)?;
+        let elapsed = start.elapsed();
+        skip_headers(&mut self.reader)?;
+        match status.split(' ').nth(1) {
+            Some("200") => Ok((true, elapsed)),
+            Some("500") => Ok((false, elapsed)),
+            _ => Err(io::Error::new(io::ErrorKind::InvalidData, status.trim().to_string())),
+        }
+    }
+}
+
+fn lower_quartile(samples : &mut Vec<Duration>) -> Duration {
+    samples.sort();
+    samples[samples.len() / 4]
+}
+
+fn median(timings : &[Duration]) -> Duration {
+    let mut sorted = timings.to_vec();
+    sorted.sort();
+    sorted[sorted.len() / 2]
+}
+
+fn measure<O : TimingOracle>(oracle : &mut O, file : &[u8], signature : &mut Vec<u8>, position : usize, candidates : &[u8], rounds : usize) -> io::Result<(Vec<Duration>, Option<u8>)> {
+    if rounds == 0 || candidates.is_empty() {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "at least one round and one candidate are required"));
+    }
+    let mut samples : Vec<Vec<Duration>> = vec![Vec::with_capacity(rounds); candidates.len()];
+    for _ in 0 .. rounds {
+        for (i, candidate) in candidates.iter().enumerate() {
+            signature[position] = *candidate;
+            let (valid, elapsed) = oracle.query(file, signature)?;
+            if valid {
+                return Ok((Vec::new(), Some(*candidate)));
+            }
+            samples[i].push(elapsed);
+        }
+    }
+    Ok((samples.iter_mut().map(|samples| lower_quartile(samples)).collect(), None))
+}
+
+struct Guess {
+    baseline : Duration,
+    timing : Duration,
+}
+
+fn recover_signature<O : TimingOracle>(oracle : &mut O, file : &[u8], length : usize, known : &[u8], rounds : usize) -> io::Result<Option<Vec<u8>>> {
+    if rounds == 0 {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "at least one round is required"));
+    }
+    let mut signature = known.to_vec();
+    signature.resize(length, 0u8);
+    let all : Vec<u8> = (0u16 .. 256u16).map(|candidate| candidate as u8).collect();
+    let mut guesses : Vec<Guess> = Vec::with_capacity(length);
+    let mut backtracks = 0usize;
+    while known.len() + guesses.len() < length {
+        let position = known.len() + guesses.len();
+        let (timings, found) = measure(oracle, file, &mut signature, position, &all, rounds)?;
+        if let Some(candidate) = found {
+            signature[position] = candidate;
+            return Ok(Some(signature));
+        }
+
+        let baseline = median(&timings);
+        let stalled = match guesses.last() {
+            Some(previous) => baseline < previous.baseline + previous.timing.checked_sub(previous.baseline).unwrap_or(Duration::from_secs(0)) / 2,
+            None => false,
+        };
+        if stalled || position + 1 == length {
+            if guesses.pop().is_none() || backtracks == 2 * length {
+                return Ok(None);
+            }
+            backtracks += 1;
+            continue;
+        }
+
+        let mut ranked : Vec<(Duration, u8)> = timings.into_iter().zip(all.iter().cloned()).collect();
+        ranked.sort_by(|a, b| b.cmp(a));
+        let finalists : Vec<u8> = ranked.iter().take(3).map(|&(_, candidate)| candidate).collect();
+        let (timings, found) = measure(oracle, file, &mut signature, position, &finalists, 4 * rounds)?;
+        if let Some(candidate) = found {
+            signature[position] = candidate;
+            return Ok(Some(signature));
+        }
+        let (best, timing) = timings.into_iter().enumerate().max_by_key(|&(_, timing)| timing).unwrap();
+        signature[position] = finalists[best];
+        guesses.push(Guess {
+            baseline : baseline,
+            timing : timing,
+        });
+    }
+    match oracle.query(file, &signature)?.0 {
+        true => Ok(Some(signature)),
+        false => Ok(None),
+    }
+}
+
+fn main() {
+    let server = SignatureServer::new(Duration::from_millis(5), 20).unwrap();
+    let expected = server.sign(b"foo");
+    let address = serve(server).unwrap();
+    println!("Server listening on http://{}/", address);
+
+    let mut oracle = HttpTimingOracle::connect(address).unwrap();
+    let signature = recover_signature(&mut oracle, b"foo", 20, &[], 5).unwrap();
+    println!("{:?}", signature.as_ref().map(|signature| String::from_utf8(octets2hex(signature)).unwrap()));
+    println!("{}", String::from_utf8(octets2hex(&expected)).unwrap());
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::io;
+    use std::time::Duration;
+    use TimingOracle;
+
+    struct Rigged {
+        expected : Vec<u8>,
+        step : Duration,
+        misleading : u8,
+        remaining : usize,
+    }
+
+    impl TimingOracle for Rigged {
+        fn query(&mut self, _ : &[u8], signature : &[u8]) -> io::Result<(bool, Duration)> {
+            let matched = self.expected.iter().zip(signature.iter()).take_while(|&(a, b)| a == b).count();
+            let mut elapsed = self.step * matched as u32;
+            if signature[0] == self.misleading && self.remaining > 0 {
+                self.remaining -= 1;
+                elapsed += self.step * 2;
+            }
+            Ok((signature == &self.expected[..], elapsed))
+        }
+    }
+
+    #[test]
+    fn test_hmac_sha1() {
+        assert!(::octets2hex(&::hmac_sha1(&[0x0Bu8; 20], b"Hi There")) == b"b617318655057264e28bc0b6fb378c8ef146be00".to_vec());
+        assert!(::octets2hex(&::hmac_sha1(&[0xAAu8; 80], b"Test Using Larger Than Block-Size Key - Hash Key First")) == b"aa4ae5e15272d00e95705637ce8a3b55ed402112".to_vec());
+    }
+
+    #[test]
+    fn test_percent_encoding() {
+        assert!(::percent_encode(b"foo.txt") == b"foo.txt".to_vec());
+        assert!(::percent_encode(b"a&b c%\xFF") == b"a%26b%20c%25%ff".to_vec());
+        assert!(::percent_decode(b"a%26b%20c%25%ff") == Ok(b"a&b c%\xFF".to_vec()));
+        assert!(::percent_decode(b"a%2") == Err(()));
+        assert!(::percent_decode(b"a%zz") == Err(()));
+    }
+
+    #[test]
+    fn test_insecure_compare() {
+        assert!(::insecure_compare(b"abc", b"abc", Duration::from_millis(0)));
+        assert!(!::insecure_compare(b"abc", b"abd", Duration::from_millis(0)));
+        assert!(!::insecure_compare(b"abc", b"ab", Duration::from_millis(0)));
+        assert!(!::insecure_compare(b"ab", b"abc", Duration::from_millis(0)));
+        let delay = Duration::from_millis(5);
+        let mut server = ::SignatureServer::new(delay, 20).unwrap();
+        let mut signature = server.sign(b"foo");
+        signature[3] ^= 1;
+        let (valid, elapsed) = server.query(b"foo", &signature).unwrap();
+        assert!(!valid);
+        assert!(elapsed >= 3 * delay);
+        assert!(::SignatureServer::new(delay, 0).is_err());
+        assert!(::SignatureServer::new(delay, 32).is_err());
+    }
+
+    #[test]
+    fn test_http() {
+        let server = ::SignatureServer::new(Duration::from_millis(0), 20).unwrap();
+        let signature = server.sign(b"foo");
+        let awkward = server.sign(b"a&b c%\xFF");
+        let address = ::serve(server).unwrap();
+        let mut oracle = ::HttpTimingOracle::connect(address).unwrap();
+        assert!(oracle.query(b"foo", &signature).unwrap().0);
+        assert!(!oracle.query(b"bar", &signature).unwrap().0);
+        assert!(!oracle.query(b"foo", &signature[.. 19]).unwrap().0);
+        assert!(oracle.query(b"a&b c%\xFF", &awkward).unwrap().0);
+        assert!(!oracle.query(b"a", &awkward).unwrap().0);
+        assert!(::handle_request(&::SignatureServer::new(Duration::from_millis(0), 20).unwrap(), "GET /test?file=foo&signature=zz HTTP/1.1") == "400 Bad Request");
+        assert!(::handle_request(&::SignatureServer::new(Duration::from_millis(0), 20).unwrap(), "GET /test?signature=00 HTTP/1.1") == "400 Bad Request");
+        assert!(::handle_request(&::SignatureServer::new(Duration::from_millis(0), 20).unwrap(), "GET /test?file=%zz&signature=00 HTTP/1.1") == "400 Bad Request");
+    }
+
+    #[test]
+    fn test_backtrack() {
+        let expected = vec![0x13u8, 0x37, 0x42];
+        let mut oracle = Rigged {
+            expected : expected.clone(),
+            step : Duration::from_micros(100),
+            misleading : 0x99,
+            remaining : 25,
+        };
+        assert!(::recover_signature(&mut oracle, b"foo", 3, &[], 5).unwrap() == Some(expected.clone()));
+        assert!(oracle.remaining == 0);
+
+        let mut oracle = Rigged {
+            expected : expected.clone(),
+            step : Duration::from_micros(100),
+            misleading : 0x99,
+            remaining : 0,
+        };
+        assert!(::recover_signature(&mut oracle, b"foo", 3, &[0x14u8], 5).unwrap() == None);
+        assert!(::recover_signature(&mut oracle, b"foo", 3, &[], 0).is_err());
+    }
+
+    #[test]
+    fn test_recover_signature() {
+        let server = ::SignatureServer::new(Duration::from_micros(500), 3).unwrap();
+        let expected = server.sign(b"foo");
+        let address = ::serve(server).unwrap();
+        let mut oracle = ::HttpTimingOracle::connect(address).unwrap();
+        let signature = ::recover_signature(&mut oracle, b"foo", 3, &[], 5).unwrap();
+        assert!(signature == Some(expected));
+
+        let mut server = ::SignatureServer::new(Duration::from_micros(500), 3).unwrap();
+        let expected = server.sign(b"bar");
+        let signature = ::recover_signature(&mut server, b"bar", 3, &[], 5).unwrap();
+        assert!(signature == Some(expected));
+    }
+}
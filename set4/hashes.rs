@@ -0,0 +1,686 @@
+fn nibble2hex(nibble : u8) -> Result<u8, ()> {
+    match nibble {
+        0u8 ... 9u8 => Ok(nibble + 0x30u8),
+        10u8 ... 15u8 => Ok(nibble + 0x57u8),
+        _ => Err(()),
+    }
+}
+
+fn octets2hex(octets: &[u8]) -> Vec<u8> {
+    let mut hex : Vec<u8> = Vec::with_capacity(octets.len() * 2);
+    for octet in octets {
+        hex.push(nibble2hex((octet & 0xF0u8) >> 4).unwrap());
+        hex.push(nibble2hex(octet & 0x0Fu8).unwrap());
+    }
+    hex
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Endianness {
+    Little,
+    Big,
+}
+
+fn read_u32s(octets : &[u8], endianness : Endianness) -> Vec<u32> {
+    octets.chunks(4).map(|chunk| {
+        let word = [chunk[0], chunk[1], chunk[2], chunk[3]];
+        match endianness {
+            Endianness::Little => u32::from_le_bytes(word),
+            Endianness::Big => u32::from_be_bytes(word),
+        }
+    }).collect()
+}
+
+fn write_u32s(words : &[u32], endianness : Endianness) -> Vec<u8> {
+    let mut octets : Vec<u8> = Vec::with_capacity(words.len() * 4);
+    for word in words {
+        match endianness {
+            Endianness::Little => octets.extend_from_slice(&word.to_le_bytes()),
+            Endianness::Big => octets.extend_from_slice(&word.to_be_bytes()),
+        }
+    }
+    octets
+}
+
+fn read_u64s(octets : &[u8]) -> Vec<u64> {
+    octets.chunks(8).map(|chunk| {
+        let mut word = [0u8; 8];
+        word.copy_from_slice(chunk);
+        u64::from_be_bytes(word)
+    }).collect()
+}
+
+fn write_u64s(words : &[u64]) -> Vec<u8> {
+    let mut octets : Vec<u8> = Vec::with_capacity(words.len() * 8);
+    for word in words {
+        octets.extend_from_slice(&word.to_be_bytes());
+    }
+    octets
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+struct MdParameters {
+    block_size : usize,
+    length_size : usize,
+    endianness : Endianness,
+    output_size : usize,
+}
+
+fn md_padding(length : u64, parameters : &MdParameters) -> Vec<u8> {
+    let mut padding = vec![0x80u8];
+    while (length as usize + padding.len()) % parameters.block_size != parameters.block_size - parameters.length_size {
+        padding.push(0u8);
+    }
+    let bits = (length as u128).wrapping_mul(8);
+    let encoded = match parameters.endianness {
+        Endianness::Little => bits.to_le_bytes()[.. parameters.length_size].to_vec(),
+        Endianness::Big => bits.to_be_bytes()[16 - parameters.length_size ..].to_vec(),
+    };
+    padding.extend(encoded);
+    padding
+}
+
+trait Compression : Clone {
+    fn parameters() -> MdParameters;
+    fn initial() -> Self;
+    fn compress(&mut self, block : &[u8]);
+    fn digest(&self) -> Vec<u8>;
+    fn from_digest(digest : &[u8]) -> Result<Self, ()>;
+}
+
+trait Hash : Clone {
+    fn new() -> Self;
+    fn block_size(&self) -> usize;
+    fn output_size(&self) -> usize;
+    fn update(&mut self, input : &[u8]);
+    fn finalize(self) -> Vec<u8>;
+}
+
+#[derive(Clone, Debug)]
+struct MerkleDamgard<F : Compression> {
+    state : F,
+    buffer : Vec<u8>,
+    length : u64,
+}
+
+impl<F : Compression> MerkleDamgard<F> {
+    fn with_state(state : F, length : u64) -> MerkleDamgard<F> {
+        MerkleDamgard {
+            state : state,
+            buffer : Vec::with_capacity(F::parameters().block_size),
+            length : length,
+        }
+    }
+}
+
+impl<F : Compression> Hash for MerkleDamgard<F> {
+    fn new() -> MerkleDamgard<F> {
+        MerkleDamgard::with_state(F::initial(), 0)
+    }
+
+    fn block_size(&self) -> usize {
+        F::parameters().block_size
+    }
+
+    fn output_size(&self) -> usize {
+        F::parameters().output_size
+    }
+
+    fn update(&mut self, input : &[u8]) {
+        let block_size = F::parameters().block_size;
+        self.length = self.length.wrapping_add(input.len() as u64);
+        self.buffer.extend_from_slice(input);
+        let blocks = self.buffer.len() / block_size;
+        for block in self.buffer[.. blocks * block_size].chunks(block_size) {
+            self.state.compress(block);
+        }
+        self.buffer.drain(.. blocks * block_size);
+    }
+
+    fn finalize(mut self) -> Vec<u8> {
+        let padding = md_padding(self.length, &F::parameters());
+        self.update(&padding);
+        assert!(self.buffer.is_empty());
+        self.state.digest()
+    }
+}
+
+fn md4_compress(state : &mut [u32; 4], block : &[u8]) {
+    let mut x = [0u32; 16];
+    for (i, chunk) in block.chunks(4).enumerate() {
+        x[i] = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+    }
+
+    let rounds : [([usize; 16], [u32; 4], u32); 3] = [
+        ([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15], [3, 7, 11, 19], 0x00000000u32),
+        ([0, 4, 8, 12, 1, 5, 9, 13, 2, 6, 10, 14, 3, 7, 11, 15], [3, 5, 9, 13], 0x5A827999u32),
+        ([0, 8, 4, 12, 2, 10, 6, 14, 1, 9, 5, 13, 3, 11, 7, 15], [3, 9, 11, 15], 0x6ED9EBA1u32),
+    ];
+    let mut h = *state;
+    for (round, &(ref order, ref shifts, constant)) in rounds.iter().enumerate() {
+        for i in 0 .. 16 {
+            let j = (4 - i % 4) % 4;
+            let (b, c, d) = (h[(j + 1) % 4], h[(j + 2) % 4], h[(j + 3) % 4]);
+            let f = match round {
+                0 => (b & c) | (!b & d),
+                1 => (b & c) | (b & d) | (c & d),
+                _ => b ^ c ^ d,
+            };
+            h[j] = h[j].wrapping_add(f).wrapping_add(x[order[i]]).wrapping_add(constant).rotate_left(shifts[i % 4]);
+        }
+    }
+    for (word, value) in state.iter_mut().zip(h.iter()) {
+        *word = word.wrapping_add(*value);
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Md4Compression {
+    state : [u32; 4],
+}
+
+impl Compression for Md4Compression {
+    fn parameters() -> MdParameters {
+        MdParameters {
+            block_size : 64,
+            length_size : 8,
+            endianness : Endianness::Little,
+            output_size : 16,
+        }
+    }
+
+    fn initial() -> Md4Compression {
+        Md4Compression {
+            state : [0x67452301u32, 0xEFCDAB89, 0x98BADCFE, 0x10325476],
+        }
+    }
+
+    fn compress(&mut self, block : &[u8]) {
+        md4_compress(&mut self.state, block);
+    }
+
+    fn digest(&self) -> Vec<u8> {
+        write_u32s(&self.state, Endianness::Little)
+    }
+
+    fn from_digest(digest : &[u8]) -> Result<Md4Compression, ()> {
+        if digest.len() != 16 {
+            return Err(());
+        }
+        let mut state = [0u32; 4];
+        state.copy_from_slice(&read_u32s(digest, Endianness::Little));
+        Ok(Md4Compression {
+            state : state,
+        })
+    }
+}
+
+fn sha1_compress(state : &mut [u32; 5], block : &[u8]) {
+    let mut w = [0u32; 80];
+    for (i, chunk) in block.chunks(4).enumerate() {
+        w[i] = u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+    }
+    for i in 16 .. 80 {
+        w[i] = (w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16]).rotate_left(1);
+    }
+
+    let (mut a, mut b, mut c, mut d, mut e) = (state[0], state[1], state[2], state[3], state[4]);
+    for i in 0 .. 80 {
+        let (f, k) = match i {
+            0 ... 19 => ((b & c) | (!b & d), 0x5A827999u32),
+            20 ... 39 => (b ^ c ^ d, 0x6ED9EBA1u32),
+            40 ... 59 => ((b & c) | (b & d) | (c & d), 0x8F1BBCDCu32),
+            _ => (b ^ c ^ d, 0xCA62C1D6u32),
+        };
+        let temp = a.rotate_left(5).wrapping_add(f).wrapping_add(e).wrapping_add(k).wrapping_add(w[i]);
+        e = d;
+        d = c;
+        c = b.rotate_left(30);
+        b = a;
+        a = temp;
+    }
+    state[0] = state[0].wrapping_add(a);
+    state[1] = state[1].wrapping_add(b);
+    state[2] = state[2].wrapping_add(c);
+    state[3] = state[3].wrapping_add(d);
+    state[4] = state[4].wrapping_add(e);
+}
+
+#[derive(Clone, Debug)]
+struct Sha1Compression {
+    state : [u32; 5],
+}
+
+impl Compression for Sha1Compression {
+    fn parameters() -> MdParameters {
+        MdParameters {
+            block_size : 64,
+            length_size : 8,
+            endianness : Endianness::Big,
+            output_size : 20,
+        }
+    }
+
+    fn initial() -> Sha1Compression {
+        Sha1Compression {
+            state : [0x67452301u32, 0xEFCDAB89, 0x98BADCFE, 0x10325476, 0xC3D2E1F0],
+        }
+    }
+
+    fn compress(&mut self, block : &[u8]) {
+        sha1_compress(&mut self.state, block);
+    }
+
+    fn digest(&self) -> Vec<u8> {
+        write_u32s(&self.state, Endianness::Big)
+    }
+
+    fn from_digest(digest : &[u8]) -> Result<Sha1Compression, ()> {
+        if digest.len() != 20 {
+            return Err(());
+        }
+        let mut state = [0u32; 5];
+        state.copy_from_slice(&read_u32s(digest, Endianness::Big));
+        Ok(Sha1Compression {
+            state : state,
+        })
+    }
+}
+
+const SHA256_K : [u32; 64] = [
+    0x428a2f98u32, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+fn sha256_compress(state : &mut [u32; 8], block : &[u8]) {
+    let mut w = [0u32; 64];
+    w[.. 16].copy_from_slice(&read_u32s(block, Endianness::Big));
+    for i in 16 .. 64 {
+        let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+        let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+        w[i] = w[i - 16].wrapping_add(s0).wrapping_add(w[i - 7]).wrapping_add(s1);
+    }
+
+    let mut h = *state;
+    for i in 0 .. 64 {
+        let s1 = h[4].rotate_right(6) ^ h[4].rotate_right(11) ^ h[4].rotate_right(25);
+        let choice = (h[4] & h[5]) ^ (!h[4] & h[6]);
+        let temp1 = h[7].wrapping_add(s1).wrapping_add(choice).wrapping_add(SHA256_K[i]).wrapping_add(w[i]);
+        let s0 = h[0].rotate_right(2) ^ h[0].rotate_right(13) ^ h[0].rotate_right(22);
+        let majority = (h[0] & h[1]) ^ (h[0] & h[2]) ^ (h[1] & h[2]);
+        let temp2 = s0.wrapping_add(majority);
+        h = [temp1.wrapping_add(temp2), h[0], h[1], h[2], h[3].wrapping_add(temp1), h[4], h[5], h[6]];
+    }
+    for (word, value) in state.iter_mut().zip(h.iter()) {
+        *word = word.wrapping_add(*value);
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Sha256Compression {
+    state : [u32; 8],
+}
+
+impl Compression for Sha256Compression {
+    fn parameters() -> MdParameters {
+        MdParameters {
+            block_size : 64,
+            length_size : 8,
+            endianness : Endianness::Big,
+            output_size : 32,
+        }
+    }
+
+    fn initial() -> Sha256Compression {
+        Sha256Compression {
+            state : [0x6A09E667u32, 0xBB67AE85, 0x3C6EF372, 0xA54FF53A, 0x510E527F, 0x9B05688C, 0x1F83D9AB, 0x5BE0CD19],
+        }
+    }
+
+    fn compress(&mut self, block : &[u8]) {
+        sha256_compress(&mut self.state, block);
+    }
+
+    fn digest(&self) -> Vec<u8> {
+        write_u32s(&self.state, Endianness::Big)
+    }
+
+    fn from_digest(digest : &[u8]) -> Result<Sha256Compression, ()> {
+        if digest.len() != 32 {
+            return Err(());
+        }
+        let mut state = [0u32; 8];
+        state.copy_from_slice(&read_u32s(digest, Endianness::Big));
+        Ok(Sha256Compression {
+            state : state,
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Sha224Compression {
+    state : [u32; 8],
+}
+
+impl Compression for Sha224Compression {
+    fn parameters() -> MdParameters {
+        MdParameters {
+            block_size : 64,
+            length_size : 8,
+            endianness : Endianness::Big,
+            output_size : 28,
+        }
+    }
+
+    fn initial() -> Sha224Compression {
+        Sha224Compression {
+            state : [0xC1059ED8u32, 0x367CD507, 0x3070DD17, 0xF70E5939, 0xFFC00B31, 0x68581511, 0x64F98FA7, 0xBEFA4FA4],
+        }
+    }
+
+    fn compress(&mut self, block : &[u8]) {
+        sha256_compress(&mut self.state, block);
+    }
+
+    fn digest(&self) -> Vec<u8> {
+        write_u32s(&self.state[.. 7], Endianness::Big)
+    }
+
+    fn from_digest(_ : &[u8]) -> Result<Sha224Compression, ()> {
+        Err(())
+    }
+}
+
+const SHA512_K : [u64; 80] = [
+    0x428a2f98d728ae22u64, 0x7137449123ef65cd, 0xb5c0fbcfec4d3b2f, 0xe9b5dba58189dbbc,
+    0x3956c25bf348b538, 0x59f111f1b605d019, 0x923f82a4af194f9b, 0xab1c5ed5da6d8118,
+    0xd807aa98a3030242, 0x12835b0145706fbe, 0x243185be4ee4b28c, 0x550c7dc3d5ffb4e2,
+    0x72be5d74f27b896f, 0x80deb1fe3b1696b1, 0x9bdc06a725c71235, 0xc19bf174cf692694,
+    0xe49b69c19ef14ad2, 0xefbe4786384f25e3, 0x0fc19dc68b8cd5b5, 0x240ca1cc77ac9c65,
+    0x2de92c6f592b0275, 0x4a7484aa6ea6e483, 0x5cb0a9dcbd41fbd4, 0x76f988da831153b5,
+    0x983e5152ee66dfab, 0xa831c66d2db43210, 0xb00327c898fb213f, 0xbf597fc7beef0ee4,
+    0xc6e00bf33da88fc2, 0xd5a79147930aa725, 0x06ca6351e003826f, 0x142929670a0e6e70,
+    0x27b70a8546d22ffc, 0x2e1b21385c26c926, 0x4d2c6dfc5ac42aed, 0x53380d139d95b3df,
+    0x650a73548baf63de, 0x766a0abb3c77b2a8, 0x81c2c92e47edaee6, 0x92722c851482353b,
+    0xa2bfe8a14cf10364, 0xa81a664bbc423001, 0xc24b8b70d0f89791, 0xc76c51a30654be30,
+    0xd192e819d6ef5218, 0xd69906245565a910, 0xf40e35855771202a, 0x106aa07032bbd1b8,
+    0x19a4c116b8d2d0c8, 0x1e376c085141ab53, 0x2748774cdf8eeb99, 0x34b0bcb5e19b48a8,
+    0x391c0cb3c5c95a63, 0x4ed8aa4ae3418acb, 0x5b9cca4f7763e373, 0x682e6ff3d6b2b8a3,
+    0x748f82ee5defb2fc, 0x78a5636f43172f60, 0x84c87814a1f0ab72, 0x8cc702081a6439ec,
+    0x90befffa23631e28, 0xa4506cebde82bde9, 0xbef9a3f7b2c67915, 0xc67178f2e372532b,
+    0xca273eceea26619c, 0xd186b8c721c0c207, 0xeada7dd6cde0eb1e, 0xf57d4f7fee6ed178,
+    0x06f067aa72176fba, 0x0a637dc5a2c898a6, 0x113f9804bef90dae, 0x1b710b35131c471b,
+    0x28db77f523047d84, 0x32caab7b40c72493, 0x3c9ebe0a15c9bebc, 0x431d67c49c100d4c,
+    0x4cc5d4becb3e42b6, 0x597f299cfc657e2a, 0x5fcb6fab3ad6faec, 0x6c44198c4a475817,
+];
+
+fn sha512_compress(state : &mut [u64; 8], block : &[u8]) {
+    let mut w = [0u64; 80];
+    w[.. 16].copy_from_slice(&read_u64s(block));
+    for i in 16 .. 80 {
+        let s0 = w[i - 15].rotate_right(1) ^ w[i - 15].rotate_right(8) ^ (w[i - 15] >> 7);
+        let s1 = w[i - 2].rotate_right(19) ^ w[i - 2].rotate_right(61) ^ (w[i - 2] >> 6);
+        w[i] = w[i - 16].wrapping_add(s0).wrapping_add(w[i - 7]).wrapping_add(s1);
+    }
+
+    let mut h = *state;
+    for i in 0 .. 80 {
+        let s1 = h[4].rotate_right(14) ^ h[4].rotate_right(18) ^ h[4].rotate_right(41);
+        let choice = (h[4] & h[5]) ^ (!h[4] & h[6]);
+        let temp1 = h[7].wrapping_add(s1).wrapping_add(choice).wrapping_add(SHA512_K[i]).wrapping_add(w[i]);
+        let s0 = h[0].rotate_right(28) ^ h[0].rotate_right(34) ^ h[0].rotate_right(39);
+        let majority = (h[0] & h[1]) ^ (h[0] & h[2]) ^ (h[1] & h[2]);
+        let temp2 = s0.wrapping_add(majority);
+        h = [temp1.wrapping_add(temp2), h[0], h[1], h[2], h[3].wrapping_add(temp1), h[4], h[5], h[6]];
+    }
+    for (word, value) in state.iter_mut().zip(h.iter()) {
+        *word = word.wrapping_add(*value);
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Sha512Compression {
+    state : [u64; 8],
+}
+
+impl Compression for Sha512Compression {
+    fn parameters() -> MdParameters {
+        MdParameters {
+            block_size : 128,
+            length_size : 16,
+            endianness : Endianness::Big,
+            output_size : 64,
+        }
+    }
+
+    fn initial() -> Sha512Compression {
+        Sha512Compression {
+            state : [0x6A09E667F3BCC908u64, 0xBB67AE8584CAA73B, 0x3C6EF372FE94F82B, 0xA54FF53A5F1D36F1, 0x510E527FADE682D1, 0x9B05688C2B3E6C1F, 0x1F83D9ABFB41BD6B, 0x5BE0CD19137E2179],
+        }
+    }
+
+    fn compress(&mut self, block : &[u8]) {
+        sha512_compress(&mut self.state, block);
+    }
+
+    fn digest(&self) -> Vec<u8> {
+        write_u64s(&self.state)
+    }
+
+    fn from_digest(digest : &[u8]) -> Result<Sha512Compression, ()> {
+        if digest.len() != 64 {
+            return Err(());
+        }
+        let mut state = [0u64; 8];
+        state.copy_from_slice(&read_u64s(digest));
+        Ok(Sha512Compression {
+            state : state,
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Sha384Compression {
+    state : [u64; 8],
+}
+
+impl Compression for Sha384Compression {
+    fn parameters() -> MdParameters {
+        MdParameters {
+            block_size : 128,
+            length_size : 16,
+            endianness : Endianness::Big,
+            output_size : 48,
+        }
+    }
+
+    fn initial() -> Sha384Compression {
+        Sha384Compression {
+            state : [0xCBBB9D5DC1059ED8u64, 0x629A292A367CD507, 0x9159015A3070DD17, 0x152FECD8F70E5939, 0x67332667FFC00B31, 0x8EB44A8768581511, 0xDB0C2E0D64F98FA7, 0x47B5481DBEFA4FA4],
+        }
+    }
+
+    fn compress(&mut self, block : &[u8]) {
+        sha512_compress(&mut self.state, block);
+    }
+
+    fn digest(&self) -> Vec<u8> {
+        write_u64s(&self.state[.. 6])
+    }
+
+    fn from_digest(_ : &[u8]) -> Result<Sha384Compression, ()> {
+        Err(())
+    }
+}
+
+type Md4 = MerkleDamgard<Md4Compression>;
+type Sha1 = MerkleDamgard<Sha1Compression>;
+type Sha224 = MerkleDamgard<Sha224Compression>;
+type Sha256 = MerkleDamgard<Sha256Compression>;
+type Sha384 = MerkleDamgard<Sha384Compression>;
+type Sha512 = MerkleDamgard<Sha512Compression>;
+
+fn digest<H : Hash>(input : &[u8]) -> Vec<u8> {
+    let mut hash = H::new();
+    hash.update(input);
+    hash.finalize()
+}
+
+fn hex_digest<H : Hash>(input : &[u8]) -> String {
+    String::from_utf8(octets2hex(&digest::<H>(input))).unwrap()
+}
+
+fn secret_prefix_mac<H : Hash>(key : &[u8], message : &[u8]) -> Vec<u8> {
+    let mut hash = H::new();
+    hash.update(key);
+    hash.update(message);
+    hash.finalize()
+}
+
+fn length_extension<F : Compression>(message : &[u8], mac : &[u8], key_length : usize, extension : &[u8]) -> Result<(Vec<u8>, Vec<u8>), ()> {
+    let state = F::from_digest(mac)?;
+    let glue = md_padding((key_length + message.len()) as u64, &F::parameters());
+    let mut forged_message = message.to_vec();
+    forged_message.extend(glue);
+    let mut hash = MerkleDamgard::with_state(state, (key_length + forged_message.len()) as u64);
+    hash.update(extension);
+    forged_message.extend_from_slice(extension);
+    Ok((forged_message, hash.finalize()))
+}
+
+fn forge<F : Compression, V : FnMut(&[u8], &[u8]) -> bool>(mut verify : V, message : &[u8], mac : &[u8], extension : &[u8], max_key_length : usize) -> Option<(Vec<u8>, Vec<u8>, usize)> {
+    for key_length in 0 .. max_key_length + 1 {
+        let (forged_message, forged_mac) = match length_extension::<F>(message, mac, key_length, extension) {
+            Ok(forgery) => forgery,
+            Err(_) => return None,
+        };
+        if verify(&forged_message, &forged_mac) {
+            return Some((forged_message, forged_mac, key_length));
+        }
+    }
+    None
+}
+
+fn main() {
+    println!("{}", hex_digest::<Md4>(b"abc"));
+    println!("{}", hex_digest::<Sha1>(b"abc"));
+    println!("{}", hex_digest::<Sha224>(b"abc"));
+    println!("{}", hex_digest::<Sha256>(b"abc"));
+    println!("{}", hex_digest::<Sha384>(b"abc"));
+    println!("{}", hex_digest::<Sha512>(b"abc"));
+
+    let key = b"YELLOW SUBMARINE";
+    let message = b"comment1=cooking%20MCs;userdata=foo;comment2=%20like%20a%20pound%20of%20bacon";
+    let mac = secret_prefix_mac::<Sha256>(key, message);
+    let verify = |message : &[u8], mac : &[u8]| secret_prefix_mac::<Sha256>(key, message) == mac;
+    let (forged_message, forged_mac, key_length) = forge::<Sha256Compression, _>(verify, message, &mac, b";admin=true", 64).unwrap();
+    println!("{}", key_length);
+    println!("{:?}", String::from_utf8_lossy(&forged_message));
+    println!("{}", String::from_utf8(octets2hex(&forged_mac)).unwrap());
+}
+
+#[cfg(test)]
+mod tests {
+
+    use Compression;
+    use Endianness;
+    use Hash;
+    use Md4;
+    use Sha1;
+    use Sha224;
+    use Sha256;
+    use Sha384;
+    use Sha512;
+
+    #[test]
+    fn test_md4() {
+        assert!(::hex_digest::<Md4>(b"") == "31d6cfe0d16ae931b73c59d7e0c089c0");
+        assert!(::hex_digest::<Md4>(b"abc") == "a448017aaf21d8525fc10ae87aa6729d");
+        assert!(::hex_digest::<Md4>(b"12345678901234567890123456789012345678901234567890123456789012345678901234567890") == "e33b4ddc9c38f2199c3e7b164fcc0536");
+    }
+
+    #[test]
+    fn test_sha1() {
+        assert!(::hex_digest::<Sha1>(b"") == "da39a3ee5e6b4b0d3255bfef95601890afd80709");
+        assert!(::hex_digest::<Sha1>(b"abc") == "a9993e364706816aba3e25717850c26c9cd0d89d");
+        assert!(::hex_digest::<Sha1>(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq") == "84983e441c3bd26ebaae4aa1f95129e5e54670f1");
+    }
+
+    #[test]
+    fn test_sha256() {
+        assert!(::hex_digest::<Sha224>(b"") == "d14a028c2a3a2bc9476102bb288234c415a2b01f828ea62ac5b3e42f");
+        assert!(::hex_digest::<Sha224>(b"abc") == "23097d223405d8228642a477bda255b32aadbce4bda0b3f7e36c9da7");
+        assert!(::hex_digest::<Sha256>(b"") == "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855");
+        assert!(::hex_digest::<Sha256>(b"abc") == "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+        assert!(::hex_digest::<Sha256>(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq") == "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1");
+    }
+
+    #[test]
+    fn test_sha512() {
+        assert!(::hex_digest::<Sha384>(b"") == "38b060a751ac96384cd9327eb1b1e36a21fdb71114be07434c0cc7bf63f6e1da274edebfe76f65fbd51ad2f14898b95b");
+        assert!(::hex_digest::<Sha384>(b"abc") == "cb00753f45a35e8bb5a03d699ac65007272c32ab0eded1631a8b605a43ff5bed8086072ba1e7cc2358baeca134c825a7");
+        assert!(::hex_digest::<Sha512>(b"") == "cf83e1357eefb8bdf1542850d66d8007d620e4050b5715dc83f4a921d36ce9ce47d0d13c5d85f2b0ff8318d2877eec2f63b931bd47417a81a538327af927da3e");
+        assert!(::hex_digest::<Sha512>(b"abc") == "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f");
+        assert!(::hex_digest::<Sha512>(b"abcdefghbcdefghicdefghijdefghijkefghijklfghijklmghijklmnhijklmnoijklmnopjklmnopqklmnopqrlmnopqrsmnopqrstnopqrstu") == "8e959b75dae313da8cf4f72814fc143f8f7779c6eb9f7fa17299aeadb6889018501d289e4900f7e4331b99dec4b5433ac7d329eeb6dd26545e96e55b874be909");
+    }
+
+    #[test]
+    fn test_streaming() {
+        let input : Vec<u8> = (0 .. 400).map(|i| i as u8).collect();
+        for split in vec![0usize, 1, 63, 64, 111, 112, 127, 128, 129, 400] {
+            let mut sha256 = Sha256::new();
+            sha256.update(&input[.. split]);
+            sha256.update(&input[split ..]);
+            assert!(sha256.finalize() == ::digest::<Sha256>(&input));
+            let mut sha512 = Sha512::new();
+            sha512.update(&input[.. split]);
+            sha512.update(&input[split ..]);
+            assert!(sha512.finalize() == ::digest::<Sha512>(&input));
+        }
+        assert!(Sha256::new().block_size() == 64 && Sha256::new().output_size() == 32);
+        assert!(Sha384::new().block_size() == 128 && Sha384::new().output_size() == 48);
+    }
+
+    #[test]
+    fn test_padding() {
+        for length in 0 .. 300u64 {
+            for parameters in vec![::Md4Compression::parameters(), ::Sha1Compression::parameters(), ::Sha512Compression::parameters()] {
+                assert!((length as usize + ::md_padding(length, &parameters).len()) % parameters.block_size == 0);
+            }
+        }
+        let padding = ::md_padding(3, &::Md4Compression::parameters());
+        assert!(padding[padding.len() - 8 ..] == [0x18u8, 0, 0, 0, 0, 0, 0, 0]);
+        let padding = ::md_padding(3, &::Sha512Compression::parameters());
+        assert!(padding.len() == 125);
+        assert!(padding[padding.len() - 16 ..] == [0u8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x18]);
+        assert!(::write_u32s(&::read_u32s(b"abcdefgh", Endianness::Little), Endianness::Big) == b"dcbahgfe".to_vec());
+    }
+
+    fn check_length_extension<F : Compression, H : Hash>() {
+        let key = b"YELLOW SUBMARINE";
+        let message = b"comment1=cooking%20MCs;userdata=foo";
+        let mac = ::secret_prefix_mac::<H>(key, message);
+        let (forged_message, forged_mac) = ::length_extension::<F>(message, &mac, key.len(), b";admin=true").unwrap();
+        assert!(forged_message.ends_with(b";admin=true"));
+        assert!(::secret_prefix_mac::<H>(key, &forged_message) == forged_mac);
+
+        let forgery = ::forge::<F, _>(|message, mac| ::secret_prefix_mac::<H>(key, message) == mac, message, &mac, b";admin=true", 32);
+        assert!(forgery == Some((forged_message, forged_mac, key.len())));
+    }
+
+    #[test]
+    fn test_length_extension() {
+        check_length_extension::<::Md4Compression, Md4>();
+        check_length_extension::<::Sha1Compression, Sha1>();
+        check_length_extension::<::Sha256Compression, Sha256>();
+        check_length_extension::<::Sha512Compression, Sha512>();
+        assert!(::length_extension::<::Sha224Compression>(b"", &::digest::<Sha224>(b""), 0, b"x").is_err());
+        assert!(::length_extension::<::Sha384Compression>(b"", &::digest::<Sha384>(b""), 0, b"x").is_err());
+    }
+}
@@ -0,0 +1,621 @@
+fn nibble2hex(nibble : u8) -> Result<u8, ()> {
+    match nibble {
+        0u8 ... 9u8 => Ok(nibble + 0x30u8),
+        10u8 ... 15u8 => Ok(nibble + 0x57u8),
+        _ => Err(()),
+    }
+}
+
+fn octets2hex(octets: &[u8]) -> Vec<u8> {
+    let mut hex : Vec<u8> = Vec::with_capacity(octets.len() * 2);
+    for octet in octets {
+        hex.push(nibble2hex((octet & 0xF0u8) >> 4).unwrap());
+        hex.push(nibble2hex(octet & 0x0Fu8).unwrap());
+    }
+    hex
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Endianness {
+    Little,
+    Big,
+}
+
+fn read_u32s(octets : &[u8], endianness : Endianness) -> Vec<u32> {
+    octets.chunks(4).map(|chunk| {
+        let word = [chunk[0], chunk[1], chunk[2], chunk[3]];
+        match endianness {
+            Endianness::Little => u32::from_le_bytes(word),
+            Endianness::Big => u32::from_be_bytes(word),
+        }
+    }).collect()
+}
+
+fn write_u32s(words : &[u32], endianness : Endianness) -> Vec<u8> {
+    let mut octets : Vec<u8> = Vec::with_capacity(words.len() * 4);
+    for word in words {
+        match endianness {
+            Endianness::Little => octets.extend_from_slice(&word.to_le_bytes()),
+            Endianness::Big => octets.extend_from_slice(&word.to_be_bytes()),
+        }
+    }
+    octets
+}
+
+fn read_u64s(octets : &[u8]) -> Vec<u64> {
+    octets.chunks(8).map(|chunk| {
+        let mut word = [0u8; 8];
+        word.copy_from_slice(chunk);
+        u64::from_be_bytes(word)
+    }).collect()
+}
+
+fn write_u64s(words : &[u64]) -> Vec<u8> {
+    let mut octets : Vec<u8> = Vec::with_capacity(words.len() * 8);
+    for word in words {
+        octets.extend_from_slice(&word.to_be_bytes());
+    }
+    octets
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+struct MdParameters {
+    block_size : usize,
+    length_size : usize,
+    endianness : Endianness,
+}
+
+fn md_padding(length : u64, parameters : &MdParameters) -> Vec<u8> {
+    let mut padding = vec![0x80u8];
+    while (length as usize + padding.len()) % parameters.block_size != parameters.block_size - parameters.length_size {
+        padding.push(0u8);
+    }
+    let bits = (length as u128).wrapping_mul(8);
+    let encoded = match parameters.endianness {
+        Endianness::Little => bits.to_le_bytes()[.. parameters.length_size].to_vec(),
+        Endianness::Big => bits.to_be_bytes()[16 - parameters.length_size ..].to_vec(),
+    };
+    padding.extend(encoded);
+    padding
+}
+
+trait Compression : Clone {
+    fn parameters() -> MdParameters;
+    fn initial() -> Self;
+    fn compress(&mut self, block : &[u8]);
+    fn digest(&self) -> Vec<u8>;
+}
+
+trait Hash : Clone {
+    fn new() -> Self;
+    fn block_size(&self) -> usize;
+    fn update(&mut self, input : &[u8]);
+    fn finalize(self) -> Vec<u8>;
+}
+
+#[derive(Clone, Debug)]
+struct MerkleDamgard<F : Compression> {
+    state : F,
+    buffer : Vec<u8>,
+    length : u64,
+}
+
+impl<F : Compression> MerkleDamgard<F> {
+    fn with_state(state : F, length : u64) -> MerkleDamgard<F> {
+        MerkleDamgard {
+            state : state,
+            buffer : Vec::with_capacity(F::parameters().block_size),
+            length : length,
+        }
+    }
+}
+
+impl<F : Compression> Hash for MerkleDamgard<F> {
+    fn new() -> MerkleDamgard<F> {
+        MerkleDamgard::with_state(F::initial(), 0)
+    }
+
+    fn block_size(&self) -> usize {
+        F::parameters().block_size
+    }
+
+    fn update(&mut self, input : &[u8]) {
+        let block_size = F::parameters().block_size;
+        self.length = self.length.wrapping_add(input.len() as u64);
+        self.buffer.extend_from_slice(input);
+        let blocks = self.buffer.len() / block_size;
+        for block in self.buffer[.. blocks * block_size].chunks(block_size) {
+            self.state.compress(block);
+        }
+        self.buffer.drain(.. blocks * block_size);
+    }
+
+    fn finalize(mut self) -> Vec<u8> {
+        let padding = md_padding(self.length, &F::parameters());
+        self.update(&padding);
+        assert!(self.buffer.is_empty());
+        self.state.digest()
+    }
+}
+
+fn md4_compress(state : &mut [u32; 4], block : &[u8]) {
+    let mut x = [0u32; 16];
+    for (i, chunk) in block.chunks(4).enumerate() {
+        x[i] = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+    }
+
+    let rounds : [([usize; 16], [u32; 4], u32); 3] = [
+        ([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15], [3, 7, 11, 19], 0x00000000u32),
+        ([0, 4, 8, 12, 1, 5, 9, 13, 2, 6, 10, 14, 3, 7, 11, 15], [3, 5, 9, 13], 0x5A827999u32),
+        ([0, 8, 4, 12, 2, 10, 6, 14, 1, 9, 5, 13, 3, 11, 7, 15], [3, 9, 11, 15], 0x6ED9EBA1u32),
+    ];
+    let mut h = *state;
+    for (round, &(ref order, ref shifts, constant)) in rounds.iter().enumerate() {
+        for i in 0 .. 16 {
+            let j = (4 - i % 4) % 4;
+            let (b, c, d) = (h[(j + 1) % 4], h[(j + 2) % 4], h[(j + 3) % 4]);
+            let f = match round {
+                0 => (b & c) | (!b & d),
+                1 => (b & c) | (b & d) | (c & d),
+                _ => b ^ c ^ d,
+            };
+            h[j] = h[j].wrapping_add(f).wrapping_add(x[order[i]]).wrapping_add(constant).rotate_left(shifts[i % 4]);
+        }
+    }
+    for (word, value) in state.iter_mut().zip(h.iter()) {
+        *word = word.wrapping_add(*value);
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Md4Compression {
+    state : [u32; 4],
+}
+
+impl Compression for Md4Compression {
+    fn parameters() -> MdParameters {
+        MdParameters {
+            block_size : 64,
+            length_size : 8,
+            endianness : Endianness::Little,
+        }
+    }
+
+    fn initial() -> Md4Compression {
+        Md4Compression {
+            state : [0x67452301u32, 0xEFCDAB89, 0x98BADCFE, 0x10325476],
+        }
+    }
+
+    fn compress(&mut self, block : &[u8]) {
+        md4_compress(&mut self.state, block);
+    }
+
+    fn digest(&self) -> Vec<u8> {
+        write_u32s(&self.state, Endianness::Little)
+    }
+}
+
+fn sha1_compress(state : &mut [u32; 5], block : &[u8]) {
+    let mut w = [0u32; 80];
+    for (i, chunk) in block.chunks(4).enumerate() {
+        w[i] = u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+    }
+    for i in 16 .. 80 {
+        w[i] = (w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16]).rotate_left(1);
+    }
+
+    let (mut a, mut b, mut c, mut d, mut e) = (state[0], state[1], state[2], state[3], state[4]);
+    for i in 0 .. 80 {
+        let (f, k) = match i {
+            0 ... 19 => ((b & c) | (!b & d), 0x5A827999u32),
+            20 ... 39 => (b ^ c ^ d, 0x6ED9EBA1u32),
+            40 ... 59 => ((b & c) | (b & d) | (c & d), 0x8F1BBCDCu32),
+            _ => (b ^ c ^ d, 0xCA62C1D6u32),
+        };
+        let temp = a.rotate_left(5).wrapping_add(f).wrapping_add(e).wrapping_add(k).wrapping_add(w[i]);
+        e = d;
+        d = c;
+        c = b.rotate_left(30);
+        b = a;
+        a = temp;
+    }
+    state[0] = state[0].wrapping_add(a);
+    state[1] = state[1].wrapping_add(b);
+    state[2] = state[2].wrapping_add(c);
+    state[3] = state[3].wrapping_add(d);
+    state[4] = state[4].wrapping_add(e);
+}
+
+#[derive(Clone, Debug)]
+struct Sha1Compression {
+    state : [u32; 5],
+}
+
+impl Compression for Sha1Compression {
+    fn parameters() -> MdParameters {
+        MdParameters {
+            block_size : 64,
+            length_size : 8,
+            endianness : Endianness::Big,
+        }
+    }
+
+    fn initial() -> Sha1Compression {
+        Sha1Compression {
+            state : [0x67452301u32, 0xEFCDAB89, 0x98BADCFE, 0x10325476, 0xC3D2E1F0],
+        }
+    }
+
+    fn compress(&mut self, block : &[u8]) {
+        sha1_compress(&mut self.state, block);
+    }
+
+    fn digest(&self) -> Vec<u8> {
+        write_u32s(&self.state, Endianness::Big)
+    }
+}
+
+const SHA256_K : [u32; 64] = [
+    0x428a2f98u32, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+fn sha256_compress(state : &mut [u32; 8], block : &[u8]) {
+    let mut w = [0u32; 64];
+    w[.. 16].copy_from_slice(&read_u32s(block, Endianness::Big));
+    for i in 16 .. 64 {
+        let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+        let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+        w[i] = w[i - 16].wrapping_add(s0).wrapping_add(w[i - 7]).wrapping_add(s1);
+    }
+
+    let mut h = *state;
+    for i in 0 .. 64 {
+        let s1 = h[4].rotate_right(6) ^ h[4].rotate_right(11) ^ h[4].rotate_right(25);
+        let choice = (h[4] & h[5]) ^ (!h[4] & h[6]);
+        let temp1 = h[7].wrapping_add(s1).wrapping_add(choice).wrapping_add(SHA256_K[i]).wrapping_add(w[i]);
+        let s0 = h[0].rotate_right(2) ^ h[0].rotate_right(13) ^ h[0].rotate_right(22);
+        let majority = (h[0] & h[1]) ^ (h[0] & h[2]) ^ (h[1] & h[2]);
+        let temp2 = s0.wrapping_add(majority);
+        h = [temp1.wrapping_add(temp2), h[0], h[1], h[2], h[3].wrapping_add(temp1), h[4], h[5], h[6]];
+    }
+    for (word, value) in state.iter_mut().zip(h.iter()) {
+        *word = word.wrapping_add(*value);
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Sha256Compression {
+    state : [u32; 8],
+}
+
+impl Compression for Sha256Compression {
+    fn parameters() -> MdParameters {
+        MdParameters {
+            block_size : 64,
+            length_size : 8,
+            endianness : Endianness::Big,
+        }
+    }
+
+    fn initial() -> Sha256Compression {
+        Sha256Compression {
+            state : [0x6A09E667u32, 0xBB67AE85, 0x3C6EF372, 0xA54FF53A, 0x510E527F, 0x9B05688C, 0x1F83D9AB, 0x5BE0CD19],
+        }
+    }
+
+    fn compress(&mut self, block : &[u8]) {
+        sha256_compress(&mut self.state, block);
+    }
+
+    fn digest(&self) -> Vec<u8> {
+        write_u32s(&self.state, Endianness::Big)
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Sha224Compression {
+    state : [u32; 8],
+}
+
+impl Compression for Sha224Compression {
+    fn parameters() -> MdParameters {
+        MdParameters {
+            block_size : 64,
+            length_size : 8,
+            endianness : Endianness::Big,
+        }
+    }
+
+    fn initial() -> Sha224Compression {
+        Sha224Compression {
+            state : [0xC1059ED8u32, 0x367CD507, 0x3070DD17, 0xF70E5939, 0xFFC00B31, 0x68581511, 0x64F98FA7, 0xBEFA4FA4],
+        }
+    }
+
+    fn compress(&mut self, block : &[u8]) {
+        sha256_compress(&mut self.state, block);
+    }
+
+    fn digest(&self) -> Vec<u8> {
+        write_u32s(&self.state[.. 7], Endianness::Big)
+    }
+}
+
+const SHA512_K : [u64; 80] = [
+    0x428a2f98d728ae22u64, 0x7137449123ef65cd, 0xb5c0fbcfec4d3b2f, 0xe9b5dba58189dbbc,
+    0x3956c25bf348b538, 0x59f111f1b605d019, 0x923f82a4af194f9b, 0xab1c5ed5da6d8118,
+    0xd807aa98a3030242, 0x12835b0145706fbe, 0x243185be4ee4b28c, 0x550c7dc3d5ffb4e2,
+    0x72be5d74f27b896f, 0x80deb1fe3b1696b1, 0x9bdc06a725c71235, 0xc19bf174cf692694,
+    0xe49b69c19ef14ad2, 0xefbe4786384f25e3, 0x0fc19dc68b8cd5b5, 0x240ca1cc77ac9c65,
+    0x2de92c6f592b0275, 0x4a7484aa6ea6e483, 0x5cb0a9dcbd41fbd4, 0x76f988da831153b5,
+    0x983e5152ee66dfab, 0xa831c66d2db43210, 0xb00327c898fb213f, 0xbf597fc7beef0ee4,
+    0xc6e00bf33da88fc2, 0xd5a79147930aa725, 0x06ca6351e003826f, 0x142929670a0e6e70,
+    0x27b70a8546d22ffc, 0x2e1b21385c26c926, 0x4d2c6dfc5ac42aed, 0x53380d139d95b3df,
+    0x650a73548baf63de, 0x766a0abb3c77b2a8, 0x81c2c92e47edaee6, 0x92722c851482353b,
+    0xa2bfe8a14cf10364, 0xa81a664bbc423001, 0xc24b8b70d0f89791, 0xc76c51a30654be30,
+    0xd192e819d6ef5218, 0xd69906245565a910, 0xf40e35855771202a, 0x106aa07032bbd1b8,
+    0x19a4c116b8d2d0c8, 0x1e376c085141ab53, 0x2748774cdf8eeb99, 0x34b0bcb5e19b48a8,
+    0x391c0cb3c5c95a63, 0x4ed8aa4ae3418acb, 0x5b9cca4f7763e373, 0x682e6ff3d6b2b8a3,
+    0x748f82ee5defb2fc, 0x78a5636f43172f60, 0x84c87814a1f0ab72, 0x8cc702081a6439ec,
+    0x90befffa23631e28, 0xa4506cebde82bde9, 0xbef9a3f7b2c67915, 0xc67178f2e372532b,
+    0xca273eceea26619c, 0xd186b8c721c0c207, 0xeada7dd6cde0eb1e, 0xf57d4f7fee6ed178,
+    0x06f067aa72176fba, 0x0a637dc5a2c898a6, 0x113f9804bef90dae, 0x1b710b35131c471b,
+    0x28db77f523047d84, 0x32caab7b40c72493, 0x3c9ebe0a15c9bebc, 0x431d67c49c100d4c,
+    0x4cc5d4becb3e42b6, 0x597f299cfc657e2a, 0x5fcb6fab3ad6faec, 0x6c44198c4a475817,
+];
+
+fn sha512_compress(state : &mut [u64; 8], block : &[u8]) {
+    let mut w = [0u64; 80];
+    w[.. 16].copy_from_slice(&read_u64s(block));
+    for i in 16 .. 80 {
+        let s0 = w[i - 15].rotate_right(1) ^ w[i - 15].rotate_right(8) ^ (w[i - 15] >> 7);
+        let s1 = w[i - 2].rotate_right(19) ^ w[i - 2].rotate_right(61) ^ (w[i - 2] >> 6);
+        w[i] = w[i - 16].wrapping_add(s0).wrapping_add(w[i - 7]).wrapping_add(s1);
+    }
+
+    let mut h = *state;
+    for i in 0 .. 80 {
+        let s1 = h[4].rotate_right(14) ^ h[4].rotate_right(18) ^ h[4].rotate_right(41);
+        let choice = (h[4] & h[5]) ^ (!h[4] & h[6]);
+        let temp1 = h[7].wrapping_add(s1).wrapping_add(choice).wrapping_add(SHA512_K[i]).wrapping_add(w[i]);
+        let s0 = h[0].rotate_right(28) ^ h[0].rotate_right(34) ^ h[0].rotate_right(39);
+        let majority = (h[0] & h[1]) ^ (h[0] & h[2]) ^ (h[1] & h[2]);
+        let temp2 = s0.wrapping_add(majority);
+        h = [temp1.wrapping_add(temp2), h[0], h[1], h[2], h[3].wrapping_add(temp1), h[4], h[5], h[6]];
+    }
+    for (word, value) in state.iter_mut().zip(h.iter()) {
+        *word = word.wrapping_add(*value);
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Sha512Compression {
+    state : [u64; 8],
+}
+
+impl Compression for Sha512Compression {
+    fn parameters() -> MdParameters {
+        MdParameters {
+            block_size : 128,
+            length_size : 16,
+            endianness : Endianness::Big,
+        }
+    }
+
+    fn initial() -> Sha512Compression {
+        Sha512Compression {
+            state : [0x6A09E667F3BCC908u64, 0xBB67AE8584CAA73B, 0x3C6EF372FE94F82B, 0xA54FF53A5F1D36F1, 0x510E527FADE682D1, 0x9B05688C2B3E6C1F, 0x1F83D9ABFB41BD6B, 0x5BE0CD19137E2179],
+        }
+    }
+
+    fn compress(&mut self, block : &[u8]) {
+        sha512_compress(&mut self.state, block);
+    }
+
+    fn digest(&self) -> Vec<u8> {
+        write_u64s(&self.state)
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Sha384Compression {
+    state : [u64; 8],
+}
+
+impl Compression for Sha384Compression {
+    fn parameters() -> MdParameters {
+        MdParameters {
+            block_size : 128,
+            length_size : 16,
+            endianness : Endianness::Big,
+        }
+    }
+
+    fn initial() -> Sha384Compression {
+        Sha384Compression {
+            state : [0xCBBB9D5DC1059ED8u64, 0x629A292A367CD507, 0x9159015A3070DD17, 0x152FECD8F70E5939, 0x67332667FFC00B31, 0x8EB44A8768581511, 0xDB0C2E0D64F98FA7, 0x47B5481DBEFA4FA4],
+        }
+    }
+
+    fn compress(&mut self, block : &[u8]) {
+        sha512_compress(&mut self.state, block);
+    }
+
+    fn digest(&self) -> Vec<u8> {
+        write_u64s(&self.state[.. 6])
+    }
+}
+
+type Md4 = MerkleDamgard<Md4Compression>;
+type Sha1 = MerkleDamgard<Sha1Compression>;
+type Sha224 = MerkleDamgard<Sha224Compression>;
+type Sha256 = MerkleDamgard<Sha256Compression>;
+type Sha384 = MerkleDamgard<Sha384Compression>;
+type Sha512 = MerkleDamgard<Sha512Compression>;
+
+fn digest<H : Hash>(input : &[u8]) -> Vec<u8> {
+    let mut hash = H::new();
+    hash.update(input);
+    hash.finalize()
+}
+
+struct Hmac<H : Hash> {
+    inner : H,
+    outer_key : Vec<u8>,
+}
+
+impl<H : Hash> Hmac<H> {
+    fn new(key : &[u8]) -> Hmac<H> {
+        let block_size = H::new().block_size();
+        let mut padded_key = match key.len() > block_size {
+            true => digest::<H>(key),
+            false => key.to_vec(),
+        };
+        padded_key.resize(block_size, 0u8);
+
+        let inner_key : Vec<u8> = padded_key.iter().map(|octet| octet ^ 0x36u8).collect();
+        let outer_key : Vec<u8> = padded_key.iter().map(|octet| octet ^ 0x5Cu8).collect();
+        let mut inner = H::new();
+        inner.update(&inner_key);
+        Hmac {
+            inner : inner,
+            outer_key : outer_key,
+        }
+    }
+
+    fn update(&mut self, input : &[u8]) {
+        self.inner.update(input);
+    }
+
+    fn finalize(self) -> Vec<u8> {
+        let mut outer = H::new();
+        outer.update(&self.outer_key);
+        outer.update(&self.inner.finalize());
+        outer.finalize()
+    }
+
+    fn verify(self, tag : &[u8]) -> bool {
+        constant_time_eq(&self.finalize(), tag)
+    }
+}
+
+fn hmac<H : Hash>(key : &[u8], message : &[u8]) -> Vec<u8> {
+    let mut hmac = Hmac::<H>::new(key);
+    hmac.update(message);
+    hmac.finalize()
+}
+
+fn hex_hmac<H : Hash>(key : &[u8], message : &[u8]) -> String {
+    String::from_utf8(octets2hex(&hmac::<H>(key, message))).unwrap()
+}
+
+fn constant_time_eq(left : &[u8], right : &[u8]) -> bool {
+    if left.len() != right.len() {
+        return false;
+    }
+    let mut difference = 0u8;
+    for (a, b) in left.iter().zip(right.iter()) {
+        difference |= a ^ b;
+    }
+    difference == 0
+}
+
+fn verify<H : Hash>(key : &[u8], message : &[u8], tag : &[u8]) -> bool {
+    constant_time_eq(&hmac::<H>(key, message), tag)
+}
+
+fn main() {
+    let message = b"The quick brown fox jumps over the lazy dog";
+    println!("{}", hex_hmac::<Md4>(b"key", message));
+    println!("{}", hex_hmac::<Sha1>(b"key", message));
+    println!("{}", hex_hmac::<Sha224>(b"key", message));
+    println!("{}", hex_hmac::<Sha256>(b"key", message));
+    println!("{}", hex_hmac::<Sha384>(b"key", message));
+    println!("{}", hex_hmac::<Sha512>(b"key", message));
+
+    let tag = hmac::<Sha256>(b"key", message);
+    println!("{}", verify::<Sha256>(b"key", message, &tag));
+    println!("{}", verify::<Sha256>(b"key", b"The quick brown fox jumps over the lazy cat", &tag));
+
+    let mut streaming = Hmac::<Sha256>::new(b"key");
+    for chunk in message.chunks(10) {
+        streaming.update(chunk);
+    }
+    println!("{}", streaming.verify(&tag));
+}
+
+#[cfg(test)]
+mod tests {
+
+    use Hmac;
+    use Md4;
+    use Sha1;
+    use Sha224;
+    use Sha256;
+    use Sha384;
+    use Sha512;
+
+    #[test]
+    fn test_rfc2202() {
+        assert!(::hex_hmac::<Sha1>(&[0x0Bu8; 20], b"Hi There") == "b617318655057264e28bc0b6fb378c8ef146be00");
+        assert!(::hex_hmac::<Sha1>(b"Jefe", b"what do ya want for nothing?") == "effcdf6ae5eb2fa2d27416d5f184df9c259a7c79");
+        assert!(::hex_hmac::<Sha1>(&[0xAAu8; 20], &[0xDDu8; 50]) == "125d7342b9ac11cd91a39af48aa17b4f63f175d3");
+        let key : Vec<u8> = (1u8 .. 26u8).collect();
+        assert!(::hex_hmac::<Sha1>(&key, &[0xCDu8; 50]) == "4c9007f4026250c6bc8414f9bf50c86c2d7235da");
+        assert!(::hex_hmac::<Sha1>(&[0x0Cu8; 20], b"Test With Truncation") == "4c1a03424b55e07fe7f27be1d58bb9324a9a5a04");
+        assert!(::hex_hmac::<Sha1>(&[0xAAu8; 80], b"Test Using Larger Than Block-Size Key - Hash Key First") == "aa4ae5e15272d00e95705637ce8a3b55ed402112");
+        assert!(::hex_hmac::<Sha1>(&[0xAAu8; 80], b"Test Using Larger Than Block-Size Key and Larger Than One Block-Size Data") == "e8e99d0f45237d786d6bbaa7965c7808bbff1a91");
+    }
+
+    #[test]
+    fn test_rfc4231() {
+        assert!(::hex_hmac::<Sha224>(&[0x0Bu8; 20], b"Hi There") == "896fb1128abbdf196832107cd49df33f47b4b1169912ba4f53684b22");
+        assert!(::hex_hmac::<Sha256>(&[0x0Bu8; 20], b"Hi There") == "b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7");
+        assert!(::hex_hmac::<Sha384>(&[0x0Bu8; 20], b"Hi There") == "afd03944d84895626b0825f4ab46907f15f9dadbe4101ec682aa034c7cebc59cfaea9ea9076ede7f4af152e8b2fa9cb6");
+        assert!(::hex_hmac::<Sha512>(&[0x0Bu8; 20], b"Hi There") == "87aa7cdea5ef619d4ff0b4241a1d6cb02379f4e2ce4ec2787ad0b30545e17cdedaa833b7d6b8a702038b274eaea3f4e4be9d914eeb61f1702e696c203a126854");
+        assert!(::hex_hmac::<Sha224>(b"Jefe", b"what do ya want for nothing?") == "a30e01098bc6dbbf45690f3a7e9e6d0f8bbea2a39e6148008fd05e44");
+        assert!(::hex_hmac::<Sha256>(b"Jefe", b"what do ya want for nothing?") == "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843");
+        assert!(::hex_hmac::<Sha384>(b"Jefe", b"what do ya want for nothing?") == "af45d2e376484031617f78d2b58a6b1b9c7ef464f5a01b47e42ec3736322445e8e2240ca5e69e2c78b3239ecfab21649");
+        assert!(::hex_hmac::<Sha512>(b"Jefe", b"what do ya want for nothing?") == "164b7a7bfcf819e2e395fbe73b56e0a387bd64222e831fd610270cd7ea2505549758bf75c05a994a6d034f65f8f0e6fdcaeab1a34d4a6b4b636e070a38bce737");
+        assert!(::hex_hmac::<Sha256>(&[0x0Cu8; 20], b"Test With Truncation")[.. 32] == *"a3b6167473100ee06e0c796c2955552b");
+        assert!(::hex_hmac::<Sha256>(&[0xAAu8; 131], b"Test Using Larger Than Block-Size Key - Hash Key First") == "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54");
+        assert!(::hex_hmac::<Sha512>(&[0xAAu8; 131], b"Test Using Larger Than Block-Size Key - Hash Key First") == "80b24263c7c1a3ebb71493c1dd7be8b49b46d1f41b4aeec1121b013783f8f3526b56d037e05f2598bd0fd2215d6a1e5295e64f73f63f0aec8b915a985d786598");
+    }
+
+    #[test]
+    fn test_streaming() {
+        let message : Vec<u8> = (0 .. 300).map(|i| i as u8).collect();
+        for split in vec![0usize, 1, 64, 127, 128, 300] {
+            let mut hmac = Hmac::<Sha384>::new(b"YELLOW SUBMARINE");
+            hmac.update(&message[.. split]);
+            hmac.update(&message[split ..]);
+            assert!(hmac.finalize() == ::hmac::<Sha384>(b"YELLOW SUBMARINE", &message));
+        }
+        assert!(::hmac::<Md4>(b"key", b"message").len() == 16);
+        assert!(::hex_hmac::<Md4>(b"Jefe", b"what do ya want for nothing?") == "be192c588a8e914d8a59b474a828128f");
+        assert!(::hex_hmac::<Md4>(&[0x0Bu8; 16], b"Hi There") == "90a79458f58f437e21f169cdba283da6");
+    }
+
+    #[test]
+    fn test_verify() {
+        let tag = ::hmac::<Sha256>(b"key", b"message");
+        assert!(::verify::<Sha256>(b"key", b"message", &tag));
+        assert!(!::verify::<Sha256>(b"key", b"massage", &tag));
+        assert!(!::verify::<Sha256>(b"key", b"message", &tag[.. 31]));
+        let mut tampered = tag.clone();
+        tampered[31] ^= 0x01;
+        assert!(!::verify::<Sha256>(b"key", b"message", &tampered));
+        let mut hmac = Hmac::<Sha256>::new(b"key");
+        hmac.update(b"message");
+        assert!(hmac.verify(&tag));
+        assert!(::constant_time_eq(&[], &[]));
+    }
+}